@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use wgpu_tutorial::physics_engine::{
+    convex_body::Convex2D,
+    collision_dispatch::CollisionDispatcher,
+    physic_obj_traits::{CollisionObject, CollisionRelation, TransposeObject},
+};
+
+type Vec2 = cgmath::Vector2<f32>;
+
+fn hexagon(x: f32, y: f32, angle: f32) -> Convex2D{
+    let mut convex = Convex2D::new(x, y, vec![(-0.2, 0.3), (0.2, 0.3), (0.35, 0.0), (0.2, -0.3), (-0.2, -0.3), (-0.35, 0.0)], 1.);
+    convex.set_angle(cgmath::Rad(angle));
+    convex.update_cache();
+    convex
+}
+
+//moving a body without updating makes every query compute the vertices again,
+//like all queries did before the cache
+fn stale(mut convex: Convex2D) -> Convex2D{
+    convex.pos += Vec2::new(0.00001, 0.);
+    convex
+}
+
+fn grid(n: usize) -> Vec<Convex2D>{
+    (0..n*n).map(|i| hexagon((i % n) as f32 * 0.5, (i / n) as f32 * 0.5, i as f32 * 0.3)).collect()
+}
+
+fn contact(c: &mut Criterion){
+    let (a, b) = (hexagon(0., 0., 0.2), hexagon(0.55, 0.1, 0.5));
+    c.bench_function("convex_contact_cached", |bench| bench.iter(
+        || Convex2D::get_contact(black_box(&a), black_box(&b))
+    ));
+    let (a, b) = (stale(hexagon(0., 0., 0.2)), stale(hexagon(0.55, 0.1, 0.5)));
+    c.bench_function("convex_contact_uncached", |bench| bench.iter(
+        || Convex2D::get_contact(black_box(&a), black_box(&b))
+    ));
+}
+
+//all pairs of a grid of bodies, most of them are far apart
+fn all_pairs(c: &mut Criterion){
+    let dispatcher = CollisionDispatcher::new();
+    let run = |bodies: &[Convex2D]| {
+        let mut contacts = 0;
+        for i in 0..bodies.len(){
+            for j in i+1..bodies.len(){
+                if dispatcher.get_contact(&bodies[i].get_col_type(), &bodies[j].get_col_type()).is_some(){
+                    contacts += 1;
+                }
+            }
+        }
+        contacts
+    };
+    let cached = grid(8);
+    c.bench_function("convex_pairs_cached", |bench| bench.iter(|| run(black_box(&cached))));
+    let uncached : Vec<Convex2D> = grid(8).into_iter().map(stale).collect();
+    c.bench_function("convex_pairs_uncached", |bench| bench.iter(|| run(black_box(&uncached))));
+}
+
+criterion_group!(benches, contact, all_pairs);
+criterion_main!(benches);
@@ -0,0 +1,52 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    convex_body::Convex2D,
+    col_relations::{gjk_col_check, sat_col_check},
+    physic_obj_traits::{CollisionRelation, TransposeObject},
+};
+
+fn hexagon(x: f32, y: f32, angle: f32) -> Convex2D{
+    let mut convex = Convex2D::new(x, y, vec![(-0.2, 0.3), (0.2, 0.3), (0.35, 0.0), (0.2, -0.3), (-0.2, -0.3), (-0.35, 0.0)], 1.);
+    convex.set_angle(cgmath::Rad(angle));
+    convex.update_cache();
+    convex
+}
+
+//overlapping, touching and separated pairs take different paths
+fn pairs() -> [(&'static str, Convex2D, Convex2D); 3]{
+    [
+        ("overlapping", hexagon(0., 0., 0.2), hexagon(0.55, 0.1, 0.5)),
+        ("touching", hexagon(0., 0., 0.), hexagon(0.7, 0., 0.)),
+        ("separated", hexagon(0., 0., 0.2), hexagon(1.2, 0.4, 0.5)),
+    ]
+}
+
+fn overlap_checks(c: &mut Criterion){
+    let mut group = c.benchmark_group("convex_overlap");
+    for (name, a, b) in pairs(){
+        group.bench_function(format!("gjk_{name}"), |bench| bench.iter(
+            || gjk_col_check(black_box(&a), black_box(&b))
+        ));
+        group.bench_function(format!("sat_{name}"), |bench| bench.iter(
+            || sat_col_check(black_box(&a), black_box(&b))
+        ));
+    }
+    group.finish();
+}
+
+fn circle_convex(c: &mut Criterion){
+    let convex = hexagon(0., 0., 0.3);
+    let mut group = c.benchmark_group("circle_convex_contact");
+    for (name, x, y) in [("vertex", 0.4, 0.35), ("edge", 0., 0.38), ("inside", 0.05, 0.), ("separated", 1., 1.)]{
+        let circle = Circle::new(0, x, y, 0.1, 1.);
+        group.bench_function(name, |bench| bench.iter(
+            || Circle::get_contact(black_box(&circle), black_box(&convex))
+        ));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, overlap_checks, circle_convex);
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+use wgpu_tutorial::physics_engine::{
+    chain_body::Chain,
+    circle_body::Circle,
+    convex_body::Convex2D,
+    physics_world::World,
+    physic_obj_traits::BodyType,
+};
+
+type Vec2 = cgmath::Vector2<f32>;
+
+//every sample simulates this many steps of a freshly built scene
+const STEPS : usize = 10;
+
+fn static_box(x: f32, y: f32, half_width: f32, half_height: f32) -> Convex2D{
+    let (w, h) = (half_width, half_height);
+    let mut wall = Convex2D::new(x, y, vec![(-w, -h), (w, -h), (w, h), (-w, h)], 0.);
+    wall.body_type = BodyType::Static;
+    wall
+}
+
+//floor with its top at y = -0.9 and walls at x = -0.9 and 0.9
+fn world_with_box() -> World{
+    let mut world = World::new();
+    world.set_gravity_dir(Vec2::new(0., -1.));
+    world.add_phy_obj(static_box(0., -0.95, 1., 0.05));
+    world.add_phy_obj(static_box(-0.95, 0., 0.05, 1.));
+    world.add_phy_obj(static_box(0.95, 0., 0.05, 1.));
+    world
+}
+
+//n circles in a square grid above the floor, small enough to fit for 10k
+fn falling_circles(n: usize) -> World{
+    let mut world = world_with_box();
+    let cols = (n as f32).sqrt().ceil() as usize;
+    let spacing = 1.7 / cols as f32;
+    for i in 0..n{
+        let (x, y) = (-0.85 + (i % cols) as f32 * spacing, -0.85 + (i / cols) as f32 * spacing);
+        world.add_phy_obj(Circle::new(0, x, y, spacing * 0.4, 0.01));
+    }
+    world
+}
+
+//rows of boxes resting on each other, the bottom row has rows boxes
+fn pyramid(rows: usize) -> World{
+    let mut world = world_with_box();
+    let h = 0.02;
+    for row in 0..rows{
+        let count = rows - row;
+        for i in 0..count{
+            let x = (i as f32 - (count - 1) as f32 * 0.5) * 2. * h;
+            let y = -0.9 + h + row as f32 * 2. * h;
+            world.add_phy_obj(Convex2D::new(x, y, vec![(-h, -h), (h, -h), (h, h), (-h, h)], 0.1));
+        }
+    }
+    world
+}
+
+//rope pinned at its left end, starting horizontally so it swings down
+fn long_chain(segments: usize) -> World{
+    let mut world = World::new();
+    let mut rope = Chain::new([-0.8, 0.8], [0.8, 0.8], segments, 0.01, 0.005);
+    rope.pin(0);
+    world.add_chain(rope);
+    world
+}
+
+fn run(mut world: World) -> World{
+    for _ in 0..STEPS{
+        world.simulation_step();
+    }
+    world
+}
+
+fn circles(c: &mut Criterion){
+    let mut group = c.benchmark_group("falling_circles");
+    group.sample_size(10);
+    for n in [1000, 5000, 10000]{
+        group.bench_function(n.to_string(), |bench| bench.iter_batched(
+            || falling_circles(n), |world| black_box(run(world)), BatchSize::LargeInput
+        ));
+    }
+    group.finish();
+}
+
+fn boxes(c: &mut Criterion){
+    let mut group = c.benchmark_group("world");
+    group.sample_size(20);
+    group.bench_function("pyramid_20_rows", |bench| bench.iter_batched(
+        || pyramid(20), |world| black_box(run(world)), BatchSize::LargeInput
+    ));
+    group.bench_function("chain_500_segments", |bench| bench.iter_batched(
+        || long_chain(500), |world| black_box(run(world)), BatchSize::LargeInput
+    ));
+    group.finish();
+}
+
+criterion_group!(benches, circles, boxes);
+criterion_main!(benches);
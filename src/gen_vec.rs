@@ -2,6 +2,7 @@
 //
 
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Key{
     index: usize,
     generation: usize,
@@ -9,7 +10,9 @@ pub struct Key{
 
 pub struct GenVec<T>{
     data: Vec<GenEntry<T>>,
+    //index of the first free entry, data.len() if there is none
     free_head: usize,
+    //occupied entries
     len: usize,
 }
 
@@ -35,12 +38,13 @@ impl<T> GenVec<T> {
     pub fn insert(&mut self, to_insert: T) -> Key{
         let key = if let Some(GenEntry{value: entry, generation }) = self.data.get_mut(self.free_head){
             if let Entry::Free { next_free } = entry{
+                //remove already advanced the generation of the entry
                 let index = self.free_head;
-                let generation = *generation+1;
                 self.free_head = *next_free;
+                *entry = Entry::Occupied { value: to_insert };
                 Key{
                     index,
-                    generation,
+                    generation: *generation,
                 }
             }
             else{
@@ -55,9 +59,9 @@ impl<T> GenVec<T> {
                     generation: 0
                 }
             );
-            self.free_head += self.data.len()+1;
+            self.free_head = self.data.len();
             Key{
-                index: self.data.len(),
+                index: self.data.len()-1,
                 generation: 0,
             }
         };
@@ -90,25 +94,36 @@ impl<T> GenVec<T> {
 
 
 
-    pub fn remove(&mut self, key: &Key){
-        let GenEntry { value, generation } = &self.data[key.index];
+    //returns the value, None for keys of removed values
+    pub fn remove(&mut self, key: &Key) -> Option<T>{
+        let GenEntry { value, generation } = self.data.get(key.index)?;
         if let Entry::Occupied { .. } = value{
             if *generation!=key.generation{
-                return;
+                return None;
             }
 
-            self.data[key.index] =
+            let removed = std::mem::replace(
+                &mut self.data[key.index],
                 GenEntry{
                     value: Entry::Free { next_free: self.free_head },
-                    generation : generation+1,
-                };
+                    generation : key.generation+1,
+                });
             self.free_head = key.index;
             self.len -= 1;
+            if let Entry::Occupied { value } = removed.value{
+                return Some(value);
+            }
         }
+        None
     }
 
+    //number of values, not of entries
     pub fn len(&self) -> usize{
-        self.data.len()
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len == 0
     }
 
     pub fn iter(&self) -> impl Iterator<Item=&T>{
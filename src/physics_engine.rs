@@ -1,10 +1,31 @@
 //physics engine modules
 //
+//diagnostics go through the log crate with a target per subsystem:
+//physics::stats, physics::narrowphase, physics::solver, physics::ccd, physics::sleep and physics::fracture
+//disabled levels only cost a comparison, the max_level features of log remove them at compile time
 
 pub mod chain_body;
 pub mod physics_world;
 pub mod line_body;
 pub mod circle_body;
 pub mod convex_body;
+pub mod rect_body;
+pub mod capsule_body;
+pub mod compound_body;
 pub mod col_relations;
+pub mod collision_dispatch;
+pub mod ccd;
+pub mod gjk;
+pub mod sleeping;
+pub mod force_field;
+pub mod fluid;
+pub mod soft_body;
+pub mod particles;
+pub mod fracture;
+pub mod character;
+pub mod contact_filter;
+pub mod debug_draw;
+pub mod stats;
+pub mod polygon;
 pub mod physic_obj_traits;
+pub mod geometry;
@@ -0,0 +1,138 @@
+//capsule body
+//
+//segment with rounded ends along the local x axis
+//half_length: distance from center to the centers of the end circles
+
+
+use crate::primitives_2d::utils::{Vec2, Radians, Mat2};
+
+use super::physic_obj_traits::*;
+
+pub struct Capsule{
+    pub body_type : BodyType,
+    pub gravity_scale : f32,
+    pub bullet : bool,
+    pub pos: Vec2,
+    pub half_length: f32,
+    pub r: f32,
+    m : f32,
+    force: Vec2,
+    torque: f32,
+    vel: Vec2,
+    angle: cgmath::Rad<f32>,
+    ang_vel: cgmath::Rad<f32>,
+    inertia: f32,
+    id : usize,
+    nodes : Vec<usize>,
+}
+
+impl Capsule{
+    pub fn new(x: f32, y: f32, half_length: f32, r: f32, m: f32) -> Self{
+        //treated as a box of the full length plus the two half circles
+        let length = 2. * half_length;
+        let inertia = m * (length.powi(2) + (2. * r).powi(2)) / 12. + m * r.powi(2) * 0.5;
+        Self {
+            body_type: BodyType::Dynamic,
+            gravity_scale: 1.,
+            bullet: false,
+            pos: Vec2::new(x, y),
+            half_length, r, m,
+            force: Vec2::new(0., 0.),
+            torque: 0.,
+            vel: Vec2::new(0., 0.),
+            angle: cgmath::Rad(0.),
+            ang_vel: cgmath::Rad(0.),
+            inertia,
+            id: 0,
+            nodes: vec![],
+        }
+    }
+
+    //world space end points of the inner segment
+    pub fn segment(&self) -> (Vec2, Vec2){
+        let axis = Mat2::from_angle(self.angle).x * self.half_length;
+        (self.pos - axis, self.pos + axis)
+    }
+}
+
+impl PhysicsObject for Capsule{
+    fn body_type(&self)-> BodyType {
+        self.body_type
+    }
+    fn gravity_scale(&self)-> f32 {
+        self.gravity_scale
+    }
+    fn is_bullet(&self)-> bool {
+        self.bullet
+    }
+}
+
+impl ForceObject for Capsule{
+    fn get_mass(&self) -> f32 {
+        self.m
+    }
+    fn get_force(&self) -> &Vec2 {
+        &self.force
+    }
+    fn get_force_mut(&mut self) -> &mut Vec2 {
+        &mut self.force
+    }
+    fn get_vel(&self) -> &Vec2 {
+        &self.vel
+    }
+    fn get_vel_mut(&mut self) -> &mut Vec2 {
+        &mut self.vel
+    }
+    fn get_torque(&self) -> f32 {
+        self.torque
+    }
+    fn set_torque(&mut self, torque: f32) {
+        self.torque = torque;
+    }
+    fn get_inertia(&self) -> f32 {
+        self.inertia
+    }
+    fn set_inertia(&mut self, inertia: f32) {
+        self.inertia = inertia;
+    }
+    fn get_angular_accel(&self) -> Radians {
+        self.ang_vel
+    }
+    fn set_angular_accel(&mut self, angle : Radians) {
+        self.ang_vel = angle;
+    }
+
+}
+
+impl TransposeObject for Capsule{
+    fn get_pos(&self) -> &cgmath::Vector2<f32> {
+        &self.pos
+    }
+    fn get_pos_mut(&mut self) -> &mut cgmath::Vector2<f32> {
+        &mut self.pos
+    }
+    fn get_angle(&self) -> Radians {
+        self.angle
+    }
+    fn set_angle(&mut self, angle: Radians) {
+        self.angle = angle;
+    }
+}
+
+impl NodeObject for Capsule{
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    fn get_connections(&self) -> &[usize] {
+        &self.nodes[..]
+    }
+}
+
+impl CollisionObject for Capsule{
+    fn get_col_type(&self) -> CollisionType {
+        CollisionType::Capsule(self)
+    }
+}
@@ -0,0 +1,111 @@
+//continuous collision detection
+//
+//bodies flagged as bullet are swept along their velocity against all other bodies
+//the time of impact is found by conservative advancement:
+//the body may move by the distance to the other shape without touching it,
+//so it is advanced by that distance until both shapes are close enough
+//only the translation is swept, the rotation of the step is applied beforehand
+//the same sweep casts shapes against the world, e.g. for character controllers
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::{gjk::{ConvexProxy, core_distance, distance}, physic_obj_traits::CollisionType, geometry::EPSILON};
+
+//bodies are stopped this far in front of the shape they hit
+pub const CCD_TARGET : f32 = 0.001;
+const CCD_TOLERANCE : f32 = 0.00025;
+const CCD_MAX_ITERATIONS : usize = 20;
+
+fn center(points: &[Vec2]) -> Vec2{
+    points.iter().fold(Vec2::new(0., 0.), |acc, p| acc + p) / points.len() as f32
+}
+
+//distance of the cores minus the radii, negative while the radii overlap
+fn swept_distance(a: &ConvexProxy, offset: Vec2, b: &ConvexProxy) -> f32{
+    core_distance(&a.translated(offset), b).map_or(f32::MAX, |d| d - a.radius - b.radius)
+}
+
+//fraction of the translation after which shape a gets within CCD_TARGET of shape b
+//None, if a does not reach b
+//shapes touching at the start only stop, if a keeps moving into b
+pub fn time_of_impact(a: &ConvexProxy, b: &ConvexProxy, translation: Vec2) -> Option<f32>{
+    let speed = translation.magnitude();
+    if speed < CCD_TOLERANCE{
+        return None;
+    }
+    let mut t = 0.;
+    let mut d = swept_distance(a, Vec2::new(0., 0.), b);
+    if d <= -CCD_TARGET{
+        //deep overlap, e.g. after a rotation, the distance has no direction
+        let approaching = translation.dot(center(&b.points) - center(&a.points)) > 0.;
+        return if approaching {Some(0.)} else {None};
+    }
+    if d <= CCD_TARGET{
+        //sliding along b moves a little closer, pushing into it moves much closer
+        let moved = CCD_TARGET.min(speed);
+        let approaching = swept_distance(a, translation * (moved / speed), b) < d - 0.5 * moved;
+        return if approaching {Some(0.)} else {None};
+    }
+    for _ in 0..CCD_MAX_ITERATIONS{
+        t += (d - CCD_TARGET) / speed;
+        if t >= 1.{
+            return None;
+        }
+        d = swept_distance(a, translation * t, b);
+        if d <= CCD_TARGET + CCD_TOLERANCE{
+            break;
+        }
+    }
+    //out of iterations t is still a safe position in front of b
+    Some(t)
+}
+
+pub struct CastHit{
+    //fraction of the translation moved before the hit
+    pub fraction: f32,
+    //on the surface of the shape that was hit
+    pub point: Vec2,
+    //surface normal of the shape that was hit, pointing towards the moving shape
+    pub normal: Vec2,
+}
+
+//first hit of shape moved by translation against a collision shape
+//one sided edges are only hit from their solid side, e.g. one way platforms
+pub fn cast_shape(shape: &ConvexProxy, translation: Vec2, other: &CollisionType) -> Option<CastHit>{
+    let proxies = match other{
+        CollisionType::Line(l) if l.one_sided => {
+            let c = center(&shape.points);
+            (0..l.segment_count()).filter_map(|i| {
+                let seg = l.segment(i);
+                let n = seg.normal();
+                //moving away from the solid side or starting behind it
+                if translation.dot(n) >= 0. || (c - seg.v1).dot(n) < 0.{
+                    return None;
+                }
+                Some(ConvexProxy::new(vec![seg.v1, seg.v2], 0.))
+            }).collect()
+        },
+        other => ConvexProxy::from_col_type(other),
+    };
+    proxies.iter().filter_map(|p| {
+        //touching at the start, a hit if the translation points into p
+        //the bullets use the coarser test of time_of_impact, which lets shallow slopes pass
+        let start = distance(shape, p)?;
+        if start.distance <= CCD_TARGET + CCD_TOLERANCE{
+            if let Some(n) = start.normal(){
+                return (translation.dot(n) > EPSILON).then_some((0., p));
+            }
+        }
+        let fraction = time_of_impact(shape, p, translation)?;
+        Some((fraction, p))
+    }).min_by(|(t1, _), (t2, _)| t1.total_cmp(t2)).map(|(fraction, p)| {
+        let moved = shape.translated(translation * fraction);
+        let output = distance(&moved, p);
+        let fallback = -translation.normalize();
+        let normal = output.and_then(|o| o.normal()).map_or(fallback, |n| -n);
+        let point = output.map_or(moved.points[0], |o| o.point_b);
+        CastHit { fraction, point, normal }
+    })
+}
@@ -1,37 +1,76 @@
 //chain body
 //
+//rope simulated with position based dynamics (verlet integration)
+//every segment is a distance constraint, solved multiple times per step
 
 
 use cgmath::InnerSpace;
 
+use crate::{gen_vec::GenVec, primitives_2d::utils::{Vec2, Mat2}};
 
-static GRAVITY_FACTOR : f32 = 0.0001;
+use super::{physics_world::PhyObjPointer, physic_obj_traits::{CollisionType, Contact},
+    geometry::{segment_normal, closest_point_on_segment, closest_points_between_segments},
+    col_relations::{capsule_circle_contact, capsule_capsule_contact, capsule_polygon_contact, capsule_polygon_contact_with_normals, capsule_chain_contact}};
 
 pub struct Chain{
     pub points : Vec<Point>,
     pub point_mass : f32,
     pub segment_length : f32,
     pub width : f32,
-    pub gravity_dir : cgmath::Vector2<f32>,
+    //number of constraint solver passes per simulation step
+    pub iterations : usize,
+    //0.0: rope folds freely; 1.0: rope resists any bending
+    pub bending_stiffness : f32,
+    //share of the velocity that is kept each step
+    pub damping : f32,
+    //segments are treated as capsules with a radius of width/2
+    pub self_collision : bool,
+    pub body_collision : bool,
+    //share of the sliding movement removed on contact with a body
+    pub friction : f32,
+    attachments : Vec<ChainAttachment>,
 }
 
 pub struct Point{
-    pub x: f32,
-    pub y: f32,
-    vel: cgmath::Vector2<f32>,
-    force: cgmath::Vector2<f32>,
+    pub pos: Vec2,
+    pub(crate) prev_pos: Vec2,
+    pub pinned: bool,
 }
 
+//connects a chain point to a body of the World
+//local_anchor is relative to the body position and rotates with the body
+pub struct ChainAttachment{
+    pub point_index: usize,
+    pub body_id: usize,
+    pub local_anchor: Vec2,
+}
+
+pub(crate) const MIN_DIST : f32 = 0.000001;
+
 impl Point {
-    fn new(x: f32, y: f32) -> Self{
-        Self { x, y,
-            vel: cgmath::Vector2::new(0., 0.),
-            force: cgmath::Vector2::new(0., 0.)
+    pub fn new(x: f32, y: f32) -> Self{
+        Self {
+            pos: Vec2::new(x, y),
+            prev_pos: Vec2::new(x, y),
+            pinned: false,
         }
     }
 
-    fn as_f32_arr(&self) -> [f32; 2]{
-        [self.x, self.y]
+    pub fn get_vel(&self) -> Vec2{
+        self.pos - self.prev_pos
+    }
+
+    pub fn as_f32_arr(&self) -> [f32; 2]{
+        [self.pos.x, self.pos.y]
+    }
+
+    pub(crate) fn inv_mass(&self, point_mass: f32) -> f32{
+        if self.pinned || point_mass <= 0.{
+            0.
+        }
+        else{
+            1. / point_mass
+        }
     }
 }
 
@@ -43,8 +82,27 @@ impl From<[f32; 2]> for Point{
 
 impl Chain {
 
-    pub fn new() -> Self {
-        todo!();
+    //straight rope from start to end divided into equally long segments
+    pub fn new(
+        start: [f32; 2],
+        end: [f32; 2],
+        segments: usize,
+        point_mass: f32,
+        width: f32
+    ) -> Self {
+        let segments = segments.max(1);
+        let start = Vec2::new(start[0], start[1]);
+        let end = Vec2::new(end[0], end[1]);
+        let step = (end - start) / segments as f32;
+
+        let points = (0..=segments).map(
+            |i| {
+                let p = start + step * i as f32;
+                Point::new(p.x, p.y)
+            }
+        ).collect();
+
+        Self::with_points(points, point_mass, step.magnitude(), width)
     }
 
     pub fn from_coords(
@@ -59,189 +117,320 @@ impl Chain {
             }
         ).collect();
 
+        Self::with_points(points, point_mass, segment_length, width)
+    }
+
+    fn with_points(
+        points : Vec<Point>,
+        point_mass: f32,
+        segment_length: f32,
+        width: f32
+    ) -> Self {
         Self{
             points,
             point_mass,
             segment_length,
             width,
-            gravity_dir: cgmath::Vector2::new(0., -1.),
+            iterations: 10,
+            bending_stiffness: 0.,
+            damping: 0.99,
+            self_collision: true,
+            body_collision: true,
+            friction: 0.3,
+            attachments: vec![],
         }
     }
 
-    pub fn simulation_step(&mut self){
-        self.simulate_forces();
-        //self.collision_check();
-        self.simulate_velocity();
-        self.simulate_movement();
+    pub fn pin(&mut self, point_index: usize){
+        self.points[point_index].pinned = true;
     }
 
-    pub fn simulate_forces(&mut self){
-        self.apply_gravity();
-        self.pull_points_together();
+    pub fn unpin(&mut self, point_index: usize){
+        self.points[point_index].pinned = false;
     }
 
-    pub fn simulate_velocity(&mut self){
-        for p in self.points.iter_mut(){
-            if p.y + p.vel.y < -0.99 || 0.99 < p.y + p.vel.y {
-                p.force.y *= 0.0;
-                p.vel.y *= 0.0;
-                p.y = if p.y < 0. {-0.98} else {0.98};
-            }
-            if p.x + p.vel.x < -0.99 || 0.99 < p.x + p.vel.x {
-                p.force.x *= 0.0;
-                p.vel.x *= 0.0;
-                p.x = if p.x < 0. {-0.98} else {0.98};
-            }
-            p.vel += p.force;
-        }
+    //a point can only be attached to one body at a time
+    pub fn attach_to_body(&mut self, point_index: usize, body_id: usize, local_anchor: Vec2){
+        self.detach(point_index);
+        self.attachments.push(ChainAttachment { point_index, body_id, local_anchor });
     }
 
-    pub fn simulate_movement(&mut self){
-        for p in self.points.iter_mut(){
-            p.x += p.vel.x;
-            p.y += p.vel.y;
-        }
+    pub fn detach(&mut self, point_index: usize){
+        self.attachments.retain(|a| a.point_index != point_index);
     }
 
-    fn apply_gravity(&mut self){
-        for p in self.points.iter_mut(){
-            self.gravity_dir.normalize();
-            p.force = self.gravity_dir * (self.point_mass*GRAVITY_FACTOR);
-        }
+    //all points attached to the body, e.g. when it is removed from the world
+    pub fn detach_body(&mut self, body_id: usize){
+        self.attachments.retain(|a| a.body_id != body_id);
     }
 
-    fn pull_points_together(&mut self){
-        if self.points.len() >= 2{
-            self.set_point_pull_forces(0, 1);
-        for i in 1..self.points.len()-1{
-            self.set_point_pull_forces(i, i+1)
-        }
-        let len = self.points.len();
-        self.set_point_pull_forces(len-2, len-1);
+    pub fn get_attachments(&self) -> &[ChainAttachment]{
+        &self.attachments[..]
+    }
+
+    //gravity is the velocity change per step, as used for the bodies of World
+    pub fn simulate_movement(&mut self, gravity: Vec2){
+        for p in self.points.iter_mut(){
+            if p.pinned{
+                p.prev_pos = p.pos;
+                continue;
+            }
+            let vel = p.get_vel() * self.damping;
+            p.prev_pos = p.pos;
+            p.pos += vel + gravity;
         }
     }
 
-    fn set_point_pull_forces(&mut self, p1_id: usize, p2_id: usize){
-            let p1 = &self.points[p1_id];
-            let p2 = &self.points[p2_id];
-            let (dx, dy) = (p2.x-p1.x, p2.y-p1.y);
-            let dist = (dx*dx + dy*dy).sqrt();
-            let h_delta_dist = (dist - self.segment_length)*0.5;
-            let p1_to_p2 =
-                cgmath::Vector2::new(dx, dy).normalize() * h_delta_dist;
-            let p2_to_p1 =
-                cgmath::Vector2::new(dx*-1., dy*-1.).normalize() * h_delta_dist;
-            self.points[p1_id].force += p1_to_p2;
-            self.points[p2_id].force += p2_to_p1;
-    }
-
-    fn collision_check(&mut self){
-        for i in 0..self.points.len()-2{
-            for j in i+2..self.points.len()-1{
-                let mut intersection = Intersects::None;
-                {
-                let p1 = &self.points[i];
-                let p1 = [p1.x+p1.force.x, p1.y+p1.force.y];
-                let p2 = &self.points[i+1];
-                let p2 = [p2.x+p2.force.x , p2.x+p2.force.y ];
-                let p3 = &self.points[j];
-                let p3 = [p3.x+p3.force.x, p3.y+p3.force.y ];
-                let p4 = &self.points[j+1];
-                let p4 = [p4.x+p4.force.x , p4.y+p4.force.y ];
-                intersection = Self::get_lines_intersection_point(
-                    p1, p2,
-                    p3, p4
-                );
+    //attached bodies are pulled by the rope as well,
+    //their share of the correction depends on their mass
+    pub fn solve_constraints(
+        &mut self,
+        bodies: &mut GenVec<PhyObjPointer>,
+        width_bound: (f32, f32),
+        height_bound: (f32, f32)
+    ){
+        //(anchor position, inverse mass of body, accumulated body correction)
+        let mut anchors : Vec<Option<(Vec2, f32, Vec2)>> = self.attachments.iter().map(
+            |a| {
+                bodies.iter().find(|b| b.get_id() == a.body_id).map(
+                    |b| {
+                        let rot = Mat2::from_angle(b.get_angle());
+                        let inv_mass = b.get_inv_mass();
+                        (b.get_pos() + rot * a.local_anchor, inv_mass, Vec2::new(0., 0.))
+                    }
+                )
+            }
+        ).collect();
+        //corrections of bodies the rope collided with, by body id
+        let mut body_corrections : Vec<(usize, Vec2)> = vec![];
+
+        for _ in 0..self.iterations{
+            for (a, anchor) in self.attachments.iter().zip(anchors.iter_mut()){
+                if let Some((anchor_pos, body_inv_mass, correction)) = anchor{
+                    let w = self.inv_mass(a.point_index);
+                    let w_sum = w + *body_inv_mass;
+                    if w_sum <= 0.{continue;}
+                    let delta = *anchor_pos - self.points[a.point_index].pos;
+                    self.points[a.point_index].pos += delta * (w / w_sum);
+                    let body_delta = delta * (-*body_inv_mass / w_sum);
+                    *anchor_pos += body_delta;
+                    *correction += body_delta;
                 }
+            }
 
-                match intersection{
-                    Intersects::None => {
-                        println!("No Collision");
-                    },
-                    Intersects::Infinite => {
-                        println!("Infinite Points of collision");
-                    },
-                    Intersects::Single(inter_x, inter_y) => {
-                        println!("iter-i: {i}; iter-j: {j}; collision point: x: {inter_x}; y {inter_y}");
-                     //   if !(inter_x == self.points[i+1].x && inter_y == self.points[i+1].y
-                       //   || inter_x == self.points[j].x && inter_y == self.points[j].y){
-                        self.points[i].force.x -= self.points[i].vel.x;
-                        self.points[i].force.y -= self.points[i].vel.y;
-                        self.points[i+1].force.x -= self.points[i].vel.x;
-                        self.points[i+1].force.y -= self.points[i].vel.y;
-                        self.points[j].force.x -= self.points[i].vel.x;
-                        self.points[j].force.y -= self.points[i].vel.y;
-                        self.points[j+1].force.x -= self.points[i].vel.x;
-                        self.points[j+1].force.y -= self.points[i].vel.y;
-
-
-                        //self.points[i+1].vel.x = 0.0;
-                        //self.points[i+1].vel.y = 0.0;
-                        /*self.points[j].vel *= 0.0;
-                        self.points[j+1].vel *= 0.0;*/
-                        /*self.points[i].force *= 0.0;
-                        self.points[i+1].force *= 0.0;
-                        self.points[j].force *= 0.0;
-                        self.points[j+1].force *= 0.0;*/
-                         // }
+            for i in 0..self.points.len().saturating_sub(1){
+                self.solve_distance(i, i+1, self.segment_length, 1., false);
+            }
 
-                    }
+            if self.bending_stiffness > 0.{
+                for i in 0..self.points.len().saturating_sub(2){
+                    self.solve_distance(i, i+2, 2. * self.segment_length, self.bending_stiffness, true);
                 }
+            }
 
+            if self.self_collision{
+                self.solve_self_collisions();
+            }
+            if self.body_collision{
+                self.solve_body_collisions(bodies, &mut body_corrections);
             }
 
+            keep_in_bounds(&mut self.points, self.width * 0.5, width_bound, height_bound);
         }
 
-        match Self::get_lines_intersection_point([-0.9, 0.9], [0.9, -0.9], [-0.9, -0.9], [0.9, 0.9]){
-            Intersects::Single(x, y) => {
-            println!("test col at: x: {x}; y: {y}");
-            },
-            Intersects::Infinite => {println!("Infinite Collisions")},
-            Intersects::None => {println!("None")},
+        apply_body_corrections(bodies, body_corrections);
+
+        for (a, anchor) in self.attachments.iter().zip(anchors.iter()){
+            if let Some((_, _, correction)) = anchor{
+                if let Some(b) = bodies.iter_mut().find(|b| b.get_id() == a.body_id){
+                    if !b.is_dynamic(){continue;}
+                    *b.get_pos_mut() += *correction;
+                    *b.get_vel_mut() += *correction;
+                }
+            }
         }
+    }
 
+    fn inv_mass(&self, point_index: usize) -> f32{
+        self.points[point_index].inv_mass(self.point_mass)
     }
 
+    //only_compress: constraint only pushes points apart, used for bending
+    fn solve_distance(&mut self, i: usize, j: usize, rest: f32, stiffness: f32, only_compress: bool){
+        let w1 = self.inv_mass(i);
+        let w2 = self.inv_mass(j);
+        let w_sum = w1 + w2;
+        if w_sum <= 0.{return;}
+
+        let delta = self.points[j].pos - self.points[i].pos;
+        let dist = delta.magnitude();
+        if dist < MIN_DIST || (only_compress && dist >= rest){return;}
 
-    fn get_lines_intersection_point(p1: [f32; 2], p2: [f32; 2], p3: [f32; 2], p4: [f32; 2], ) -> Intersects{
-        // f(x) = y = m*x + b
-        // b = y - m*x
-        // m = (y - b)/x
-        // y = m*x + (y - m)
-        // m = (y2 - y1) / (x2 - x1)
-
-        let mut m1 = (p2[1] - p1[1]) / (p2[0] - p1[0]);
-        m1 = if m1.is_normal(){m1}else{0.};
-        let b1 = p1[1] - m1 * p1[0];
-        let mut m2 = (p4[1] - p3[1]) / (p4[0] - p3[0]);
-        m2 = if m2.is_normal(){m2}else{0.};
-        let b2 = p3[1] - m2 * p3[0];
-        //calculate intersection of the two lines
-        let mut corner_x = (b2 - b1) / (m1 - m2);
-        corner_x = if corner_x.is_normal(){corner_x}else{0.};
-        let corner_y = m1*corner_x + b1;
-        if (
-            (p1[0] < p2[0] && (corner_x <= p1[0] && p2[0] <= corner_x))
-            || (p1[0] > p2[0] && (corner_x >= p1[0] && p2[0] >= corner_x))
-           &&
-           (p1[1] < p2[1] && (corner_y <= p1[1] && p2[1] <= corner_y))
-          || (p1[1] > p1[1] && (corner_y >= p1[1] && p2[1] >= corner_y))
-          )
-            ||
-            (b1 != b2 && m1 == m2)
-        {
-                return Intersects::None;
+        let diff = (dist - rest) / (dist * w_sum) * stiffness;
+        self.points[i].pos += delta * (w1 * diff);
+        self.points[j].pos -= delta * (w2 * diff);
+    }
+
+    //inverse mass of the point at parameter t of segment (i, i+1)
+    fn segment_inv_mass(&self, i: usize, t: f32) -> f32{
+        (1.-t).powi(2) * self.inv_mass(i) + t.powi(2) * self.inv_mass(i+1)
+    }
+
+    //moves segment (i, i+1) so that its point at parameter t moves by offset
+    fn move_segment(&mut self, i: usize, t: f32, offset: Vec2){
+        let w = self.segment_inv_mass(i, t);
+        if w <= 0.{return;}
+        let w0 = (1.-t) * self.inv_mass(i) / w;
+        let w1 = t * self.inv_mass(i+1) / w;
+        self.points[i].pos += offset * w0;
+        self.points[i+1].pos += offset * w1;
+    }
+
+    //reduces the movement of segment (i, i+1) along the contact surface
+    fn apply_friction(&mut self, i: usize, t: f32, n: Vec2){
+        let tangent = Vec2::new(-n.y, n.x);
+        for (k, share) in [(i, 1.-t), (i+1, t)]{
+            if self.points[k].pinned{continue;}
+            let slide = self.points[k].get_vel().dot(tangent);
+            self.points[k].prev_pos += tangent * (slide * self.friction * share);
         }
-        else if b1 == b2 && m1 ==  m2{
-            return Intersects::Infinite;
+    }
+
+    fn solve_self_collisions(&mut self){
+        let min_dist = self.width;
+        let segments = self.points.len().saturating_sub(1);
+        for i in 0..segments{
+            //direct neighbours share a point and always touch
+            for j in i+2..segments{
+                let (s, t, c1, c2) = closest_points_between_segments(
+                    self.points[i].pos, self.points[i+1].pos,
+                    self.points[j].pos, self.points[j+1].pos
+                );
+                let delta = c2 - c1;
+                let dist = delta.magnitude();
+                if dist >= min_dist{continue;}
+
+                let n = if dist > MIN_DIST {delta / dist} else {
+                    segment_normal(self.points[i].pos, self.points[i+1].pos)
+                };
+                let w1 = self.segment_inv_mass(i, s);
+                let w2 = self.segment_inv_mass(j, t);
+                let w_sum = w1 + w2;
+                if w_sum <= 0.{continue;}
+                let pen = min_dist - dist;
+                self.move_segment(i, s, n * (-pen * w1 / w_sum));
+                self.move_segment(j, t, n * (pen * w2 / w_sum));
+            }
+        }
+    }
+
+    fn solve_body_collisions(
+        &mut self,
+        bodies: &GenVec<PhyObjPointer>,
+        body_corrections: &mut Vec<(usize, Vec2)>
+    ){
+        let radius = self.width * 0.5;
+        //the rope would always touch the bodies it is attached to
+        let attached : Vec<usize> = self.attachments.iter().map(|a| a.body_id).collect();
+        let bodies = bodies.iter().filter(|b| !attached.contains(&b.get_id()));
+        solve_body_collisions(bodies, body_corrections, |body, mut body_offset| {
+            let body_inv_mass = body.get_inv_mass();
+            for i in 0..self.points.len().saturating_sub(1){
+                let a = self.points[i].pos;
+                let b = self.points[i+1].pos;
+                //n points from the body to the segment
+                if let Some(contact) = capsule_body_contact(a, b, radius, &body.get_col_type(), body_offset){
+                    let (t, _) = closest_point_on_segment(contact.point, a, b);
+                    let n = -contact.normal;
+                    let pen = contact.depth;
+                    let w = self.segment_inv_mass(i, t);
+                    let w_sum = w + body_inv_mass;
+                    if w_sum <= 0.{continue;}
+                    self.move_segment(i, t, n * (pen * w / w_sum));
+                    body_offset -= n * (pen * body_inv_mass / w_sum);
+                    self.apply_friction(i, t, n);
+                }
+            }
+            body_offset
+        });
+    }
+
+}
+
+//points of chains and soft bodies pushed out of the bodies of the World
+//collide moves the points out of a body shifted by its correction so far and returns the new correction,
+//corrections of movable bodies are kept by body id until apply_body_corrections
+pub(crate) fn solve_body_collisions<'a>(
+    bodies: impl Iterator<Item = &'a PhyObjPointer>,
+    body_corrections: &mut Vec<(usize, Vec2)>,
+    mut collide: impl FnMut(&PhyObjPointer, Vec2) -> Vec2
+){
+    for body in bodies{
+        let correction_index = body_corrections.iter().position(|(id, _)| *id == body.get_id());
+        let body_offset = match correction_index {
+            Some(ci) => body_corrections[ci].1,
+            None => Vec2::new(0., 0.),
+        };
+        let body_offset = collide(body, body_offset);
+
+        if body.get_inv_mass() > 0.{
+            match correction_index {
+                Some(ci) => body_corrections[ci].1 = body_offset,
+                None => body_corrections.push((body.get_id(), body_offset)),
+            }
         }
-        Intersects::Single(corner_x, corner_y)
     }
+}
 
+//the collision corrections count as velocity as well
+pub(crate) fn apply_body_corrections(bodies: &mut GenVec<PhyObjPointer>, body_corrections: Vec<(usize, Vec2)>){
+    for (id, correction) in body_corrections{
+        if let Some(b) = bodies.iter_mut().find(|b| b.get_id() == id){
+            *b.get_pos_mut() += correction;
+            *b.get_vel_mut() += correction;
+        }
+    }
 }
 
-pub enum Intersects{
-    Single(f32, f32),
-    Infinite,
-    None,
+//margin keeps the collision radius of the points inside the bounds, pinned points stay where they are
+pub(crate) fn keep_in_bounds(points: &mut [Point], margin: f32, width_bound: (f32, f32), height_bound: (f32, f32)){
+    for p in points.iter_mut(){
+        if p.pinned{continue;}
+        p.pos.x = p.pos.x.clamp(width_bound.0 + margin, width_bound.1 - margin);
+        p.pos.y = p.pos.y.clamp(height_bound.0 + margin, height_bound.1 - margin);
+    }
+}
+
+//contact of a capsule with a body moved by body_offset
+//the normal points from the capsule to the body
+//segments of zero length give the contacts of particles
+pub(crate) fn capsule_body_contact(a: Vec2, b: Vec2, radius: f32, body: &CollisionType, body_offset: Vec2) -> Option<Contact>{
+    match body{
+        CollisionType::Circle(c) => {
+            capsule_circle_contact(a, b, radius, c.pos + body_offset, c.r)
+        },
+        CollisionType::Convex(c) => {
+            let world = c.world();
+            let verts : Vec<Vec2> = world.vertices.iter().map(|v| v + body_offset).collect();
+            capsule_polygon_contact_with_normals(a, b, radius, &verts, &world.normals)
+        },
+        CollisionType::Rectangle(r) => {
+            let verts : Vec<Vec2> = r.world_vertices().iter().map(|v| v + body_offset).collect();
+            capsule_polygon_contact(a, b, radius, &verts)
+        },
+        CollisionType::Compound(c) => {
+            c.world_parts().iter().filter_map(|part| {
+                let world = part.world();
+                let verts : Vec<Vec2> = world.vertices.iter().map(|v| v + body_offset).collect();
+                capsule_polygon_contact_with_normals(a, b, radius, &verts, &world.normals)
+            }).max_by(|c1, c2| c1.depth.total_cmp(&c2.depth))
+        },
+        CollisionType::Capsule(c) => {
+            let (ca, cb) = c.segment();
+            capsule_capsule_contact(a, b, radius, ca + body_offset, cb + body_offset, c.r)
+        },
+        //one sided terrain and its ghost vertices apply to ropes and particles as well
+        CollisionType::Line(l) => capsule_chain_contact(a, b, radius, l),
+        _ => None,
+    }
 }
@@ -0,0 +1,212 @@
+//character controller
+//
+//kinematic capsule moved by the game instead of by forces
+//every move is cast against the bodies of the World, the capsule stops in front
+//of what it hits and slides along it with the rest of the movement (collide and slide)
+//the movement is split into a part across and a part along up:
+//across it climbs slopes up to max_slope and steps up ledges up to step_height,
+//steeper surfaces are walls, along up it lands on the ground and does not slide down slopes
+//after the move the ground below is probed, a grounded character stays on the ground
+//when walking down slopes or steps (snapping)
+//one sided edge chains are one way platforms, they are only hit from above
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::{physics_world::World, gjk::ConvexProxy, ccd::CCD_TARGET};
+
+//movements shorter than this are dropped
+const MIN_MOVE : f32 = 0.000001;
+
+//what the character touched during its last move
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CharacterState{
+    pub grounded: bool,
+    pub ground_normal: Option<Vec2>,
+    //id of the body the character stands on, e.g. to ride moving platforms
+    pub ground_body: Option<usize>,
+    //normal of a wall blocking the movement, pointing towards the character
+    pub wall_normal: Option<Vec2>,
+    pub ceiling: bool,
+}
+
+pub struct CharacterController{
+    //center of the capsule
+    pub pos: Vec2,
+    pub radius: f32,
+    //half length of the segment between the two half circles
+    pub half_height: f32,
+    //unit direction the character stands upright in, against the gravity
+    pub up: Vec2,
+    //steepest walkable slope in rad
+    pub max_slope: f32,
+    //highest ledge the character walks onto without jumping
+    pub step_height: f32,
+    //distance the character is pulled down to stay on the ground
+    pub snap_distance: f32,
+    //cast and slide passes per move
+    pub max_slides: usize,
+    //bodies the capsule passes through, e.g. a body following the character
+    pub ignore: Vec<usize>,
+    state: CharacterState,
+}
+
+impl CharacterController{
+    pub fn new(x: f32, y: f32, radius: f32, half_height: f32) -> Self{
+        Self {
+            pos: Vec2::new(x, y),
+            radius,
+            half_height,
+            up: Vec2::new(0., 1.),
+            max_slope: std::f32::consts::FRAC_PI_4,
+            step_height: radius * 0.5,
+            snap_distance: radius * 0.5,
+            max_slides: 4,
+            ignore: vec![],
+            state: CharacterState::default(),
+        }
+    }
+
+    pub fn state(&self) -> &CharacterState{
+        &self.state
+    }
+
+    pub fn is_grounded(&self) -> bool{
+        self.state.grounded
+    }
+
+    pub fn touching_wall(&self) -> bool{
+        self.state.wall_normal.is_some()
+    }
+
+    pub fn proxy_at(&self, pos: Vec2) -> ConvexProxy{
+        let half = self.up * self.half_height;
+        ConvexProxy::new(vec![pos - half, pos + half], self.radius)
+    }
+
+    //surfaces facing up at most max_slope away from up
+    pub fn is_walkable(&self, normal: Vec2) -> bool{
+        normal.dot(self.up) >= self.max_slope.cos() - 0.0001
+    }
+
+    //moves the character by displacement as far as the world allows
+    //returns the movement done
+    pub fn move_by(&mut self, world: &World, displacement: Vec2) -> Vec2{
+        let start = self.pos;
+        let was_grounded = self.state.grounded;
+        self.state = CharacterState::default();
+
+        let vertical = self.up * displacement.dot(self.up);
+        let horizontal = displacement - vertical;
+        self.move_across(world, horizontal, was_grounded);
+        self.move_along_up(world, vertical);
+
+        //grounded characters walking down slopes and steps keep the ground
+        let snap = if was_grounded && displacement.dot(self.up) <= 0. {self.snap_distance} else {2. * CCD_TARGET};
+        self.probe_ground(world, snap);
+        self.pos - start
+    }
+
+    //single cast, returns the reached position and the hit body with the surface normal
+    fn sweep(&self, world: &World, from: Vec2, translation: Vec2) -> (Vec2, Option<(usize, Vec2)>){
+        if translation.magnitude2() < MIN_MOVE * MIN_MOVE{
+            return (from, None);
+        }
+        match world.cast_shape(&self.proxy_at(from), translation, &self.ignore){
+            Some((id, hit)) => (from + translation * hit.fraction, Some((id, hit.normal))),
+            None => (from + translation, None),
+        }
+    }
+
+    //walkable surfaces are climbed, others block like walls
+    fn move_across(&mut self, world: &World, horizontal: Vec2, was_grounded: bool){
+        let start = self.pos;
+        let mut remaining = horizontal;
+        let mut blocked = None;
+        for _ in 0..self.max_slides{
+            let (pos, hit) = self.sweep(world, self.pos, remaining);
+            let moved = pos - self.pos;
+            self.pos = pos;
+            let Some((_, normal)) = hit else {break;};
+            remaining -= moved;
+            //walls are only slid along sideways, not climbed
+            let n = if self.is_walkable(normal) {normal} else {
+                blocked = Some(normal);
+                let across = normal - self.up * normal.dot(self.up);
+                if across.magnitude2() < MIN_MOVE {break;}
+                across.normalize()
+            };
+            let into = remaining.dot(n);
+            if into < 0.{
+                remaining -= n * into;
+            }
+            if remaining.magnitude2() < MIN_MOVE * MIN_MOVE{break;}
+        }
+
+        let Some(wall) = blocked else {return;};
+        if was_grounded && self.step_up(world, start, horizontal){
+            return;
+        }
+        self.state.wall_normal = Some(wall);
+    }
+
+    //lifts the character by step_height, moves across and puts it down again
+    //kept if it lands on walkable ground further than it got without stepping
+    fn step_up(&mut self, world: &World, start: Vec2, horizontal: Vec2) -> bool{
+        if self.step_height <= 0. || horizontal.magnitude2() < MIN_MOVE * MIN_MOVE{
+            return false;
+        }
+        let dir = horizontal.normalize();
+        let (raised, _) = self.sweep(world, start, self.up * self.step_height);
+        let (across, _) = self.sweep(world, raised, horizontal);
+        let (landed, ground) = self.sweep(world, across, -self.up * (raised - start).dot(self.up));
+        let Some((id, normal)) = ground else {return false;};
+        if !self.is_walkable(normal) || (landed - start).dot(dir) <= (self.pos - start).dot(dir) + MIN_MOVE{
+            return false;
+        }
+        self.pos = landed;
+        self.state.grounded = true;
+        self.state.ground_normal = Some(normal);
+        self.state.ground_body = Some(id);
+        true
+    }
+
+    //falling onto walkable ground stops, steep ground is slid down
+    fn move_along_up(&mut self, world: &World, vertical: Vec2){
+        let mut remaining = vertical;
+        for _ in 0..self.max_slides{
+            let (pos, hit) = self.sweep(world, self.pos, remaining);
+            let moved = pos - self.pos;
+            self.pos = pos;
+            let Some((id, normal)) = hit else {break;};
+            remaining -= moved;
+            if self.is_walkable(normal){
+                self.state.grounded = true;
+                self.state.ground_normal = Some(normal);
+                self.state.ground_body = Some(id);
+                break;
+            }
+            if normal.dot(self.up) < 0.{
+                self.state.ceiling = true;
+            }
+            let into = remaining.dot(normal);
+            if into < 0.{
+                remaining -= normal * into;
+            }
+            if remaining.magnitude2() < MIN_MOVE * MIN_MOVE{break;}
+        }
+    }
+
+    //walkable ground within distance below the character, it is moved onto it
+    fn probe_ground(&mut self, world: &World, distance: f32){
+        if self.state.grounded{return;}
+        let (pos, hit) = self.sweep(world, self.pos, -self.up * distance);
+        let Some((id, normal)) = hit else {return;};
+        if !self.is_walkable(normal){return;}
+        self.pos = pos;
+        self.state.grounded = true;
+        self.state.ground_normal = Some(normal);
+        self.state.ground_body = Some(id);
+    }
+}
@@ -18,6 +18,10 @@ pub struct Circle{
     angle: cgmath::Rad<f32>,
     ang_vel: cgmath::Rad<f32>,
     inertia: f32,
+    //fast circles swept against the other bodies to not pass through them
+    pub bullet: bool,
+    pub body_type: BodyType,
+    pub gravity_scale: f32,
 }
 
 impl Circle {
@@ -32,6 +36,9 @@ impl Circle {
             angle : cgmath::Rad(0.),
             ang_vel: cgmath::Rad(0.),
             inertia: 1.,
+            bullet: false,
+            body_type: BodyType::Dynamic,
+            gravity_scale: 1.,
         }
     }
 
@@ -48,7 +55,17 @@ impl Circle {
 
 }
 
-impl PhysicsObject for Circle{}
+impl PhysicsObject for Circle{
+    fn body_type(&self)-> BodyType {
+        self.body_type
+    }
+    fn gravity_scale(&self)-> f32 {
+        self.gravity_scale
+    }
+    fn is_bullet(&self)-> bool {
+        self.bullet
+    }
+}
 
 impl ForceObject for Circle{
     fn get_mass(&self) -> f32 {
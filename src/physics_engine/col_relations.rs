@@ -6,11 +6,17 @@ use std::ops::Neg;
 
 use cgmath::InnerSpace;
 
-use crate::primitives_2d::utils::{Vec2, Mat2};
-
-use super::{line_body::{Intersects, self},
-    circle_body::Circle,
+use crate::primitives_2d::utils::Vec2;
+
+use super::{circle_body::Circle,
+    geometry::{closest_point_on_line, closest_point_on_segment, closest_points_between_segments,
+        segment_normal, EPSILON},
+    line_body::{EdgeChain, EdgeSegment},
+    rect_body::Rect,
+    capsule_body::Capsule,
     physic_obj_traits::*,
+    gjk::{self, ConvexProxy},
+    polygon,
     convex_body::Convex2D, physics_world::TempPhyObjData};
 
 
@@ -22,6 +28,19 @@ impl CollisionRelation<Circle, Circle> for Circle{
             + (other.pos.y-object.pos.y).powi(2);
         dist_sq <= (object.r + other.r).powi(2)
     }
+
+    fn get_contact(object: &Circle, other: &Circle) -> Option<Contact> {
+        let d = other.pos - object.pos;
+        let r_sum = object.r + other.r;
+        if d.magnitude2() > r_sum.powi(2){
+            return None;
+        }
+        let dist = d.magnitude();
+        //circles at the same position are pushed apart along y
+        let normal = if dist > EPSILON {d / dist} else {Vec2::new(0., 1.)};
+        Some(Contact { normal, depth: r_sum - dist, point: object.pos + normal * object.r })
+    }
+
     fn pos_reset_to(object: &Circle, other: &Circle) -> Option<(TempPhyObjData, TempPhyObjData)> {
 
         let t_circle1 = TempPhyObjData{
@@ -76,87 +95,66 @@ impl CollisionRelation<Circle, Circle> for Circle{
     }
 }
 
-fn get_closest_point_to_line(
-    px: f32, py: f32,
-    x1: f32, y1: f32,
-    x2: f32, y2: f32
-) -> (f32, f32){
-    let dx = x1 - x2;
-    let dy = y1 - y2;
-    let len_sq = dx.powi(2) + dy.powi(2);
-    let dot = ( ((px-x1)*(x2-x1)) + ((py-y1)*(y2-y1)) ) / len_sq;
-    let closest_x = x1 + (dot * (x2-x1));
-    let closest_y = y1 + (dot * (y2-y1));
-    (closest_x, closest_y)
+//exact circle against convex polygon test
+//the face with the largest separation of the center decides the region:
+//center inside of the polygon, in front of the face or in front of one of its vertices
+//normal points from the circle to the polygon
+pub fn circle_polygon_contact(center: Vec2, r: f32, verts: &[Vec2]) -> Option<Contact>{
+    circle_polygon_contact_with_normals(center, r, verts, &polygon::outward_normals(verts))
 }
 
-fn point_in_line_bounds(
-    px: f32, py: f32,
-    mut x1: f32, mut y1: f32,
-    mut x2: f32, mut y2: f32
-) -> bool{
-    if x1 > x2 { std::mem::swap(&mut x1, &mut x2); }
-    if y1 > y2 { std::mem::swap(&mut y1, &mut y2); }
-    const ERR : f32 = 0.0001;
-    (x1-ERR <= px && px <= x2+ERR) && (y1-ERR <= py && py <= y2+ERR)
-}
-
-
-//TODO: check for circle being inside of Convex
-//maybe not needed, if velocity is bounded
-
-impl CollisionRelation<Circle, Convex2D> for Circle{
-    fn check_col(object: &Circle, other: &Convex2D) -> bool {
-        let rot_mat = Mat2::from_angle(other.get_angle());
-        let (mut p_vx, mut p_vy) =
-            other.transformed_vertex(&rot_mat, 0);
-
-        for i in 1..other.vertices.len(){
-            let (vx, vy) =
-                other.transformed_vertex(&rot_mat, i);
-            let (cl_x, cl_y) = get_closest_point_to_line(
-                object.pos.x, object.pos.y,
-                p_vx, p_vy, vx, vy
-            );
-           if !point_in_line_bounds(cl_x, cl_y, p_vx, p_vy, vx, vy){
-              p_vx = vx;
-              p_vy = vy;
-              continue;
-            }
-
-            let dx : f32 = cl_x - object.pos.x;
-            let dy : f32 = cl_y - object.pos.y;
-
-            let dist_sq = dx.powi(2) + dy.powi(2);
-
-            if dist_sq <= object.r.powi(2){
-                return true;
-            }
-
-            p_vx = vx;
-            p_vy = vy;
+//same as circle_polygon_contact with the outward edge normals given, e.g. cached by Convex2D
+pub fn circle_polygon_contact_with_normals(center: Vec2, r: f32, verts: &[Vec2], normals: &[Vec2]) -> Option<Contact>{
+    if verts.len() < 3{
+        return None;
+    }
+    let len = verts.len();
+
+    let mut face = 0;
+    let mut separation = f32::MIN;
+    for i in 0..len{
+        let n = normals[i];
+        let s = (center - verts[i]).dot(n);
+        if s > r{
+            return None;
         }
-        let (vx, vy) =
-            other.transformed_vertex(&rot_mat, 0);
-
-            let (cl_x, cl_y) = get_closest_point_to_line(
-                object.pos.x, object.pos.y,
-                p_vx, p_vy, vx, vy
-            );
-           if !point_in_line_bounds(cl_x, cl_y, p_vx, p_vy, vx, vy){
-              return false;
-            }
+        if s > separation{
+            separation = s;
+            face = i;
+        }
+    }
 
-            let dx : f32 = cl_x - object.pos.x;
-            let dy : f32 = cl_y - object.pos.y;
+    let v1 = verts[face];
+    let v2 = verts[(face+1)%len];
+    let n = normals[face];
 
-            let dist_sq = dx.powi(2) + dy.powi(2);
+    //center inside of the polygon, pushed out through the nearest face
+    if separation < EPSILON{
+        return Some(Contact { normal: -n, depth: r - separation, point: center - n * separation });
+    }
 
-            if dist_sq <= object.r.powi(2){
-                return true;
-            }
+    let vertex_contact = |v: Vec2| {
+        let d = v - center;
+        let dist = d.magnitude();
+        if dist > r{
+            return None;
+        }
+        let normal = if dist > EPSILON {d / dist} else {-n};
+        Some(Contact { normal, depth: r - dist, point: v })
+    };
+    if (center - v1).dot(v2 - v1) <= 0.{
+        return vertex_contact(v1);
+    }
+    if (center - v2).dot(v1 - v2) <= 0.{
+        return vertex_contact(v2);
+    }
+    Some(Contact { normal: -n, depth: r - separation, point: center - n * separation })
+}
 
-        false
+impl CollisionRelation<Circle, Convex2D> for Circle{
+    fn get_contact(object: &Circle, other: &Convex2D) -> Option<Contact> {
+        let world = other.world();
+        circle_polygon_contact_with_normals(object.pos, object.r, &world.vertices, &world.normals)
     }
 
     fn pos_reset_to(object: &Circle, other: &Convex2D) -> Option<(TempPhyObjData, TempPhyObjData)> {
@@ -224,6 +222,14 @@ impl CollisionRelation<Convex2D, Convex2D> for Convex2D{
         convex_convex_collision(object, other)
     }
 
+    fn get_contact(object: &Convex2D, other: &Convex2D) -> Option<Contact> {
+        let (world1, world2) = (object.world(), other.world());
+        if !world1.aabb.overlaps(&world2.aabb){
+            return None;
+        }
+        polygon_polygon_contact_with_normals(&world1.vertices, &world1.normals, &world2.vertices, &world2.normals)
+    }
+
     fn pos_reset_to(object: &Convex2D, other: &Convex2D) -> Option<(TempPhyObjData, TempPhyObjData)> {
         let t_circle1 = TempPhyObjData{
             pos: object.pos - object.get_vel(),
@@ -248,163 +254,444 @@ impl CollisionRelation<Convex2D, Convex2D> for Convex2D{
 // v: vertex to check for Collision
 // (v-a) * n > 0
 // -> if true, then v is in front of edge
-fn sat_col_check(convex1 : &Convex2D, convex2 : &Convex2D) -> bool{
-    if convex1.vertices.len() < 2 || convex2.vertices.len() < 2{
+pub fn sat_col_check(convex1 : &Convex2D, convex2 : &Convex2D) -> bool{
+    if convex1.vertices().len() < 2 || convex2.vertices().len() < 2{
         return false;
     }
-    let rot_mat1 = Mat2::from_angle(convex1.get_angle());
-    let rot_mat2 = Mat2::from_angle(convex2.get_angle());
-
-    for i in 0..convex1.vertices.len()-1{
-        let a : Vec2 = convex1.transformed_vertex(&rot_mat1, i).into();
-        let b : Vec2 = convex1.transformed_vertex(&rot_mat1, i+1).into();
-        let n : Vec2 = Vec2::new((b.y-a.y)*-1., b.x-a.x).normalize();
+    let (world1, world2) = (convex1.world(), convex2.world());
 
-        let mut verts_in_front_of_edge = true;
-
-        for j in 0..convex2.vertices.len(){
-            let v : Vec2 = convex2.transformed_vertex(&rot_mat2, j).into();
-            if !((v-a).dot(n) > 0.){
-                verts_in_front_of_edge = false;
-            }
-        }
+    for (a, n) in world1.vertices.iter().zip(world1.normals.iter()){
+        let verts_in_front_of_edge = world2.vertices.iter().all(|v| (v-a).dot(*n) > 0.);
         if verts_in_front_of_edge{
             return false;
         } // no collision possible
     }
+    true
+}
+
+
+//overlap test with the capped gjk distance query
+pub fn gjk_col_check(convex1: &Convex2D, convex2 : &Convex2D) -> bool{
+    let proxies1 = ConvexProxy::from_col_type(&convex1.get_col_type());
+    let proxies2 = ConvexProxy::from_col_type(&convex2.get_col_type());
+    match (proxies1.first(), proxies2.first()){
+        (Some(p1), Some(p2)) => gjk::distance(p1, p2).is_some_and(|d| d.overlap()),
+        _ => false,
+    }
+}
+
+fn convex_convex_collision(convex1 : &Convex2D, convex2 : &Convex2D) -> bool{
+    //println!("farthest points: {:?}, {:?}", c1_fp, c2_fp);
+    //sat_col_check(convex1, convex2)
+    gjk_col_check(convex1, convex2)
+}
+
+
 
-    let a : Vec2 = convex1.transformed_vertex(&rot_mat1, convex1.vertices.len()-1).into();
-    let b : Vec2 = convex1.transformed_vertex(&rot_mat1, 0).into();
-    let n : Vec2 = Vec2::new((b.y-a.y)*-1., b.x-a.x).normalize();
-    let mut verts_in_front_of_edge = true;
-    for i in 0..convex2.vertices.len(){
-        let v : Vec2 = convex2.transformed_vertex(&rot_mat2, i).into();
-        if !((v-a).dot(n) > 0.){
-            verts_in_front_of_edge = false;
+
+
+//edge chains
+//every segment is tested, the deepest contact is used
+
+fn deepest_contact(contacts: impl Iterator<Item=Contact>) -> Option<Contact>{
+    contacts.fold(None, |deepest: Option<Contact>, c| {
+        match deepest {
+            Some(d) if d.depth >= c.depth => Some(d),
+            _ => Some(c),
         }
+    })
+}
+
+fn circle_edge_contact(center: Vec2, r: f32, edge: &EdgeSegment, one_sided: bool) -> Option<Contact>{
+    let n = edge.normal();
+    if one_sided && (center - edge.v1).dot(n) < 0.{
+        return None;
     }
-    if verts_in_front_of_edge{
-        return false;
-    } // no collision possible
-    true
+
+    let e = edge.v2 - edge.v1;
+    let u = (center - edge.v1).dot(e);
+    let w = (edge.v2 - center).dot(e);
+
+    //closest point is one of the vertices or on the face
+    //vertices shared with a neighbour only collide, if the circle is not in the region of the neighbour
+    let closest = if w <= 0.{
+        if let Some(v3) = edge.v3{
+            if (center - edge.v2).dot(v3 - edge.v2) > 0.{
+                return None;
+            }
+        }
+        edge.v2
+    }
+    else if u <= 0.{
+        if let Some(v0) = edge.v0{
+            if (edge.v1 - center).dot(edge.v1 - v0) > 0.{
+                return None;
+            }
+        }
+        edge.v1
+    }
+    else{
+        closest_point_on_line(center, edge.v1, edge.v2).1
+    };
+
+    let d = center - closest;
+    let dist = d.magnitude();
+    if dist > r{
+        return None;
+    }
+    let push = if dist > EPSILON {d / dist} else {n};
+    Some(Contact { normal: -push, depth: r - dist, point: closest })
 }
 
+//separating axis test of the segment against the polygon
+fn polygon_edge_contact(verts: &[Vec2], normals: &[Vec2], edge: &EdgeSegment, one_sided: bool) -> Option<Contact>{
+    if verts.len() < 3{
+        return None;
+    }
+    let n = edge.normal();
+    let center = verts.iter().fold(Vec2::new(0., 0.), |acc, v| acc + v) / verts.len() as f32;
+    if one_sided && (center - edge.v1).dot(n) < 0.{
+        return None;
+    }
+
+    //edge normal axis, polygon may lie on both sides for two sided edges
+    let mut edge_axis = n;
+    let mut edge_sep = verts.iter().map(|v| (v - edge.v1).dot(n)).fold(f32::MAX, f32::min);
+    if !one_sided{
+        let back_sep = verts.iter().map(|v| (edge.v1 - v).dot(n)).fold(f32::MAX, f32::min);
+        if back_sep > edge_sep{
+            edge_sep = back_sep;
+            edge_axis = -n;
+        }
+    }
+    if edge_sep > 0.{
+        return None;
+    }
 
-//finds farthest vertex in vertices in direction of d
-fn get_support_point(convex: &Convex2D, d: &Vec2) -> Vec2{
-    let mut highest = -f32::MAX;
-    let mut support = (0., 0.);
-    let rot_mat = Mat2::from_angle(convex.get_angle());
-    for i in 0..convex.vertices.len(){
-        let v = convex.transformed_vertex(&rot_mat, i);
-        let dot = v.0*d.x + v.1*d.y;
-        if dot > highest{
-            highest = dot;
-            support = v;
+    //polygon face axes
+    let mut poly_sep = -f32::MAX;
+    let mut poly_axis = n;
+    for (a, &m) in verts.iter().zip(normals.iter()){
+        let sep = (edge.v1 - a).dot(m).min((edge.v2 - a).dot(m));
+        if sep > 0.{
+            return None;
+        }
+        if sep > poly_sep{
+            poly_sep = sep;
+            poly_axis = m;
         }
     }
-    support.into()
+
+    //polygon faces are only used, if they do not push the polygon into the terrain
+    //or snag it on a joint between two segments
+    const AXIS_TOLERANCE : f32 = 0.001;
+    if poly_sep > edge_sep + AXIS_TOLERANCE
+        && (!one_sided || edge.allows_normal(-poly_axis))
+    {
+        let deepest = if (edge.v1 - verts[0]).dot(poly_axis) < (edge.v2 - verts[0]).dot(poly_axis) {edge.v1} else {edge.v2};
+        return Some(Contact { normal: poly_axis, depth: -poly_sep, point: deepest });
+    }
+
+    let deepest = verts.iter().fold(verts[0], |d, v| if (v - edge.v1).dot(edge_axis) < (d - edge.v1).dot(edge_axis) {*v} else {d});
+    Some(Contact { normal: -edge_axis, depth: -edge_sep, point: deepest })
 }
 
-//searchest for the farthest vertex in direction d for convex1
-//and -d for convex
-//then calculates the resulting minkowski difference from the to points
-fn get_support_in_minkowski_diff(
-    convex1: &Convex2D, convex2 : &Convex2D, d: &Vec2
-) -> Vec2{
-    let c1_fp = get_support_point(&convex1, d);
-    let c2_fp = get_support_point(&convex2, &(d * -1.));
-    c1_fp - c2_fp
+impl CollisionRelation<Circle, EdgeChain> for Circle{
+    fn get_contact(object: &Circle, other: &EdgeChain) -> Option<Contact> {
+        deepest_contact(
+            (0..other.segment_count()).filter_map(
+                |i| circle_edge_contact(object.pos, object.r, &other.segment(i), other.one_sided)
+            )
+        )
+    }
 }
 
+impl CollisionRelation<Convex2D, EdgeChain> for Convex2D{
+    fn get_contact(object: &Convex2D, other: &EdgeChain) -> Option<Contact> {
+        let world = object.world();
+        deepest_contact(
+            (0..other.segment_count()).filter_map(
+                |i| polygon_edge_contact(&world.vertices, &world.normals, &other.segment(i), other.one_sided)
+            )
+        )
+    }
+}
 
-//is not really needed, you dont want to calculate the whole minkowski difference
-fn minkowski_difference(convex1: &Convex2D, convex2 : &Convex2D) -> Vec<(f32, f32)>{
-    let mut resulting_shape : Vec<(f32, f32)> =
-        Vec::with_capacity(convex1.vertices.len()*convex2.vertices.len());
 
-    let rot_mat1 = Mat2::from_angle(convex1.get_angle());
-    let rot_mat2 = Mat2::from_angle(convex2.get_angle());
+//rectangles and capsules
+//normals of all contacts point from the first to the second shape
 
-    for i in 0..convex1.vertices.len(){
-        let (c1_vert_x, c1_vert_y) = convex1.transformed_vertex(&rot_mat1, i);
+fn polygon_center(verts: &[Vec2]) -> Vec2{
+    verts.iter().fold(Vec2::new(0., 0.), |acc, v| acc + v) / verts.len() as f32
+}
 
-        for j in 0..convex2.vertices.len(){
-            let (c2_vert_x, c2_vert_y) = convex2.transformed_vertex(&rot_mat2, j);
-            resulting_shape.push((c1_vert_x - c2_vert_x, c1_vert_y - c2_vert_y));
+//returns None, if a face of a separates the polygons
+//else (separation, outward face normal of a, deepest vertex of b) of the least penetrating face
+fn max_separation(a: &[Vec2], normals_a: &[Vec2], b: &[Vec2]) -> Option<(f32, Vec2, Vec2)>{
+    let mut best : Option<(f32, Vec2, Vec2)> = None;
+    for (&va, &m) in a.iter().zip(normals_a.iter()){
+        let mut sep = f32::MAX;
+        let mut deepest = b[0];
+        for vb in b.iter(){
+            let d = (vb - va).dot(m);
+            if d < sep{
+                sep = d;
+                deepest = *vb;
+            }
+        }
+        if sep > 0.{
+            return None;
+        }
+        match best {
+            Some((best_sep, _, _)) if best_sep >= sep => {},
+            _ => best = Some((sep, m, deepest)),
         }
     }
+    best
+}
+
+//separating axis test of two convex polygons
+pub fn polygon_polygon_contact(verts1: &[Vec2], verts2: &[Vec2]) -> Option<Contact>{
+    polygon_polygon_contact_with_normals(
+        verts1, &polygon::outward_normals(verts1),
+        verts2, &polygon::outward_normals(verts2)
+    )
+}
 
-    resulting_shape
+//same as polygon_polygon_contact with the outward edge normals given, e.g. cached by Convex2D
+pub fn polygon_polygon_contact_with_normals(
+    verts1: &[Vec2], normals1: &[Vec2],
+    verts2: &[Vec2], normals2: &[Vec2]
+) -> Option<Contact>{
+    if verts1.len() < 3 || verts2.len() < 3{
+        return None;
+    }
+    let (sep1, n1, p1) = max_separation(verts1, normals1, verts2)?;
+    let (sep2, n2, p2) = max_separation(verts2, normals2, verts1)?;
+    //faces of the first polygon are preferred to keep the normal stable
+    const AXIS_TOLERANCE : f32 = 0.0005;
+    if sep1 + AXIS_TOLERANCE >= sep2{
+        Some(Contact { normal: n1, depth: -sep1, point: p1 })
+    }
+    else{
+        Some(Contact { normal: -n2, depth: -sep2, point: p2 })
+    }
 }
 
+//closest point of the box to the circle center in box space,
+//a center inside of the box is pushed out through the nearest face
+fn circle_rect_contact(center: Vec2, r: f32, rect: &Rect) -> Option<Contact>{
+    let local = rect.to_local(center);
+    let h = rect.half_extents();
+    let clamped = Vec2::new(local.x.clamp(-h.x, h.x), local.y.clamp(-h.y, h.y));
+    let (ax, ay) = rect.axes();
+
+    if clamped != local{
+        let d = local - clamped;
+        let dist = d.magnitude();
+        if dist > r{
+            return None;
+        }
+        let n = -d / dist;
+        return Some(Contact {
+            normal: ax * n.x + ay * n.y,
+            depth: r - dist,
+            point: rect.to_world(clamped),
+        });
+    }
 
-fn triple_product(a: Vec2, b: Vec2, c: Vec2) -> Vec2{
-    b*c.dot(a) - a*c.dot(b)
+    let dx = h.x - local.x.abs();
+    let dy = h.y - local.y.abs();
+    let (face_normal, depth, point) = if dx < dy {
+        let sx = if local.x < 0. {-1.} else {1.};
+        (Vec2::new(sx, 0.), r + dx, Vec2::new(sx * h.x, local.y))
+    }
+    else {
+        let sy = if local.y < 0. {-1.} else {1.};
+        (Vec2::new(0., sy), r + dy, Vec2::new(local.x, sy * h.y))
+    };
+    Some(Contact {
+        normal: -(ax * face_normal.x + ay * face_normal.y),
+        depth,
+        point: rect.to_world(point),
+    })
 }
 
-fn simplex_contains_origin(simplex: &mut Vec<Vec2>, d: &mut Vec2) -> bool{
+pub fn capsule_circle_contact(a: Vec2, b: Vec2, radius: f32, center: Vec2, r: f32) -> Option<Contact>{
+    let (_, closest) = closest_point_on_segment(center, a, b);
+    let d = center - closest;
+    let dist = d.magnitude();
+    if dist >= radius + r{
+        return None;
+    }
+    let n = if dist > EPSILON {d / dist} else {segment_normal(a, b)};
+    Some(Contact { normal: n, depth: radius + r - dist, point: closest + n * radius })
+}
 
-    let a = simplex.last().unwrap();
-    let ao = a*(-1.);
-    if simplex.len() == 3{
-        let b = simplex[0];
-        let c = simplex[1];
+pub fn capsule_capsule_contact(a1: Vec2, b1: Vec2, r1: f32, a2: Vec2, b2: Vec2, r2: f32) -> Option<Contact>{
+    let (_, _, c1, c2) = closest_points_between_segments(a1, b1, a2, b2);
+    let d = c2 - c1;
+    let dist = d.magnitude();
+    if dist >= r1 + r2{
+        return None;
+    }
+    let n = if dist > EPSILON {d / dist} else {segment_normal(a1, b1)};
+    Some(Contact { normal: n, depth: r1 + r2 - dist, point: c1 + n * r1 })
+}
 
-        let ab = b - a;
-        let ac = c - a;
+//separating axis test of a capsule against a convex polygon
+//axes: polygon face normals, segment normal
+//and the axis from the closest polygon vertex to the segment
+pub fn capsule_polygon_contact(a: Vec2, b: Vec2, radius: f32, verts: &[Vec2]) -> Option<Contact>{
+    capsule_polygon_contact_with_normals(a, b, radius, verts, &polygon::outward_normals(verts))
+}
 
-        let ab_perp = triple_product(ac, ab, ab);
-        let ac_perp = triple_product(ab, ac, ac);
+//same as capsule_polygon_contact with the outward edge normals given, e.g. cached by Convex2D
+pub fn capsule_polygon_contact_with_normals(a: Vec2, b: Vec2, radius: f32, verts: &[Vec2], normals: &[Vec2]) -> Option<Contact>{
+    if verts.len() < 2{
+        return None;
+    }
+    let mut axes : Vec<Vec2> = Vec::with_capacity(verts.len()+2);
+    axes.extend_from_slice(normals);
+    axes.push(segment_normal(a, b));
+
+    let closest = verts.iter().map(|v| (*v, closest_point_on_segment(*v, a, b).1))
+        .min_by(|(v1, c1), (v2, c2)| (c1 - v1).magnitude2().total_cmp(&(c2 - v2).magnitude2()));
+    if let Some((v, c)) = closest{
+        if (c - v).magnitude2() > EPSILON{
+            axes.push((c - v).normalize());
+        }
+    }
 
-        if ab_perp.dot(ao) > 0.{
-            simplex.remove(1);
-            *d = ab_perp;
+    let mut best_axis = axes[0];
+    let mut best_overlap = f32::MAX;
+    for axis in axes.iter(){
+        let (pa, pb) = (a.dot(*axis), b.dot(*axis));
+        let seg_min = pa.min(pb) - radius;
+        let seg_max = pa.max(pb) + radius;
+        let (poly_min, poly_max) = verts.iter().fold((f32::MAX, -f32::MAX),
+            |(lo, hi), v| (lo.min(v.dot(*axis)), hi.max(v.dot(*axis)))
+        );
+        let overlap = (seg_max - poly_min).min(poly_max - seg_min);
+        if overlap <= 0.{
+            return None;
         }
-        else{
-            if ac_perp.dot(ao) > 0.{
-                simplex.remove(0);
-                *d = ac_perp;
-            }
-            else{return true;}
+        if overlap < best_overlap{
+            best_overlap = overlap;
+            best_axis = *axis;
         }
     }
-    else{
-        let b = simplex[0];
-        let ab = b - a;
-        let ab_perp = triple_product(ab, ao, ab);
-        *d = ab_perp;
+
+    if (polygon_center(verts) - (a + b) * 0.5).dot(best_axis) < 0.{
+        best_axis = -best_axis;
     }
-    false
+
+    //the end reaching deepest into the polygon touches it
+    let (pa, pb) = (a.dot(best_axis), b.dot(best_axis));
+    let touching = if (pa - pb).abs() < radius {(a + b) * 0.5} else if pa > pb {a} else {b};
+    Some(Contact { normal: best_axis, depth: best_overlap, point: touching + best_axis * radius })
 }
 
-fn gjk_col_check(convex1: &Convex2D, convex2 : &Convex2D) -> bool{
-    let mut simplex : Vec<Vec2> = Vec::with_capacity(3);
-    let mut d = convex2.pos - convex1.pos;
-    simplex.push(get_support_in_minkowski_diff(convex1, convex2, &d));
-    d = d * -1.;
+fn capsule_edge_contact(a: Vec2, b: Vec2, radius: f32, edge: &EdgeSegment, one_sided: bool) -> Option<Contact>{
+    let n = edge.normal();
+    if one_sided && ((a + b) * 0.5 - edge.v1).dot(n) < 0.{
+        return None;
+    }
+    let (_, _, c_cap, c_edge) = closest_points_between_segments(a, b, edge.v1, edge.v2);
+    let d = c_cap - c_edge;
+    let dist = d.magnitude();
+    if dist >= radius{
+        return None;
+    }
+    let push = if dist > EPSILON {d / dist} else {n};
+    if !one_sided || edge.allows_normal(push){
+        return Some(Contact { normal: -push, depth: radius - dist, point: c_edge });
+    }
+
+    //pushing along the joint would snag, use the face normal instead
+    let lowest = if (a - edge.v1).dot(n) < (b - edge.v1).dot(n) {a} else {b};
+    let depth = radius - (lowest - edge.v1).dot(n);
+    if depth <= 0.{
+        return None;
+    }
+    Some(Contact { normal: -n, depth, point: lowest - n * radius })
+}
 
-    loop{
-        simplex.push(get_support_in_minkowski_diff(convex1, convex2, &d));
+//deepest contact of a capsule with the segments of the chain, the normal points from the capsule to the chain
+//segments of zero length give the contacts of circles
+pub fn capsule_chain_contact(a: Vec2, b: Vec2, radius: f32, chain: &EdgeChain) -> Option<Contact>{
+    deepest_contact(
+        (0..chain.segment_count()).filter_map(
+            |i| capsule_edge_contact(a, b, radius, &chain.segment(i), chain.one_sided)
+        )
+    )
+}
 
-        if let Some(last_v) = simplex.last(){
-            if last_v.dot(d) <= 0.{
-                return false;
-            }
-            else{
-                if simplex_contains_origin(&mut simplex, &mut d) {
-                    return true;
-                }
-            }
-        }
+impl CollisionRelation<Rect, Circle> for Rect{
+    fn get_contact(object: &Rect, other: &Circle) -> Option<Contact> {
+        circle_rect_contact(other.pos, other.r, object).map(Contact::flipped)
     }
 }
 
-fn convex_convex_collision(convex1 : &Convex2D, convex2 : &Convex2D) -> bool{
-    //println!("farthest points: {:?}, {:?}", c1_fp, c2_fp);
-    //sat_col_check(convex1, convex2)
-    gjk_col_check(convex1, convex2)
+impl CollisionRelation<Rect, Convex2D> for Rect{
+    fn get_contact(object: &Rect, other: &Convex2D) -> Option<Contact> {
+        let world = other.world();
+        let verts = object.world_vertices();
+        polygon_polygon_contact_with_normals(&verts, &polygon::outward_normals(&verts), &world.vertices, &world.normals)
+    }
+}
+
+impl CollisionRelation<Rect, Rect> for Rect{
+    fn get_contact(object: &Rect, other: &Rect) -> Option<Contact> {
+        polygon_polygon_contact(&object.world_vertices(), &other.world_vertices())
+    }
+}
+
+impl CollisionRelation<Rect, EdgeChain> for Rect{
+    fn get_contact(object: &Rect, other: &EdgeChain) -> Option<Contact> {
+        let verts = object.world_vertices();
+        let normals = polygon::outward_normals(&verts);
+        deepest_contact(
+            (0..other.segment_count()).filter_map(
+                |i| polygon_edge_contact(&verts, &normals, &other.segment(i), other.one_sided)
+            )
+        )
+    }
+}
+
+impl CollisionRelation<Capsule, Circle> for Capsule{
+    fn get_contact(object: &Capsule, other: &Circle) -> Option<Contact> {
+        let (a, b) = object.segment();
+        capsule_circle_contact(a, b, object.r, other.pos, other.r)
+    }
 }
 
+impl CollisionRelation<Capsule, Convex2D> for Capsule{
+    fn get_contact(object: &Capsule, other: &Convex2D) -> Option<Contact> {
+        let world = other.world();
+        let (a, b) = object.segment();
+        capsule_polygon_contact_with_normals(a, b, object.r, &world.vertices, &world.normals)
+    }
+}
+
+impl CollisionRelation<Capsule, Rect> for Capsule{
+    fn get_contact(object: &Capsule, other: &Rect) -> Option<Contact> {
+        let (a, b) = object.segment();
+        capsule_polygon_contact(a, b, object.r, &other.world_vertices())
+    }
+}
 
+impl CollisionRelation<Capsule, Capsule> for Capsule{
+    fn get_contact(object: &Capsule, other: &Capsule) -> Option<Contact> {
+        let (a1, b1) = object.segment();
+        let (a2, b2) = other.segment();
+        capsule_capsule_contact(a1, b1, object.r, a2, b2, other.r)
+    }
+}
 
+impl CollisionRelation<Capsule, EdgeChain> for Capsule{
+    fn get_contact(object: &Capsule, other: &EdgeChain) -> Option<Contact> {
+        let (a, b) = object.segment();
+        capsule_chain_contact(a, b, object.r, other)
+    }
+}
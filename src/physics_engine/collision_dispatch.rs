@@ -0,0 +1,174 @@
+//collision dispatch
+//
+//table of contact functions keyed by the pair of shape kinds
+//a pair only has to be registered in one order,
+//the other order is answered with the flipped contact
+//user defined shapes use CollisionType::Custom and register their own functions
+//compounds without own functions collide through their convex parts
+//bounding boxes of whole bodies are compared by the broad phase of the World,
+//compounds skip the parts whose boxes do not overlap the other shape
+
+use std::collections::HashMap;
+
+use super::{circle_body::Circle, convex_body::Convex2D,
+    rect_body::Rect, capsule_body::Capsule, geometry::Aabb,
+    physic_obj_traits::{CollisionType, CollisionRelation, Contact}};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ShapeKind{
+    Circle,
+    Line,
+    Rectangle,
+    Capsule,
+    Convex,
+    Compound,
+    //user defined shapes, the number tells them apart
+    Custom(u32),
+}
+
+//contact normal points from the first shape to the second one
+pub type ContactFn = fn(&CollisionType, &CollisionType) -> Option<Contact>;
+
+pub struct CollisionDispatcher{
+    table: HashMap<(ShapeKind, ShapeKind), ContactFn>,
+}
+
+impl CollisionType<'_>{
+    pub fn kind(&self) -> Option<ShapeKind>{
+        match self{
+            CollisionType::NoCollision => None,
+            CollisionType::Circle(_) => Some(ShapeKind::Circle),
+            CollisionType::Line(_) => Some(ShapeKind::Line),
+            CollisionType::Rectangle(_) => Some(ShapeKind::Rectangle),
+            CollisionType::Capsule(_) => Some(ShapeKind::Capsule),
+            CollisionType::Convex(_) => Some(ShapeKind::Convex),
+            CollisionType::Compound(_) => Some(ShapeKind::Compound),
+            CollisionType::Custom { kind, .. } => Some(ShapeKind::Custom(*kind)),
+        }
+    }
+
+    //None for shapes without a cheap bounding box, they are always tested
+    pub fn aabb(&self) -> Option<Aabb>{
+        match self{
+            CollisionType::Circle(c) => Some(Aabb::around_circle(c.pos, c.r)),
+            CollisionType::Line(l) => {
+                let points : Vec<_> = (0..l.points.len()).map(|i| l.transformed_point(i)).collect();
+                (!points.is_empty()).then(|| Aabb::from_points(&points))
+            },
+            CollisionType::Convex(c) => Some(c.aabb()),
+            CollisionType::Compound(c) => Some(c.aabb()),
+            CollisionType::Rectangle(r) => Some(Aabb::from_points(&r.world_vertices())),
+            CollisionType::Capsule(c) => {
+                let (a, b) = c.segment();
+                Some(Aabb::around_circle(a, c.r).union(&Aabb::around_circle(b, c.r)))
+            },
+            _ => None,
+        }
+    }
+}
+
+//contact function calling the CollisionRelation of two built in shapes
+macro_rules! relation_fn {
+    ($name: ident, $object: ident($object_type: ty), $other: ident) => {
+        fn $name(a: &CollisionType, b: &CollisionType) -> Option<Contact>{
+            match (a, b){
+                (CollisionType::$object(a), CollisionType::$other(b)) => <$object_type>::get_contact(*a, *b),
+                _ => None,
+            }
+        }
+    };
+}
+
+relation_fn!(circle_circle, Circle(Circle), Circle);
+relation_fn!(circle_convex, Circle(Circle), Convex);
+relation_fn!(circle_line, Circle(Circle), Line);
+relation_fn!(convex_convex, Convex(Convex2D), Convex);
+relation_fn!(convex_line, Convex(Convex2D), Line);
+relation_fn!(rect_circle, Rectangle(Rect), Circle);
+relation_fn!(rect_convex, Rectangle(Rect), Convex);
+relation_fn!(rect_rect, Rectangle(Rect), Rectangle);
+relation_fn!(rect_line, Rectangle(Rect), Line);
+relation_fn!(capsule_circle, Capsule(Capsule), Circle);
+relation_fn!(capsule_convex, Capsule(Capsule), Convex);
+relation_fn!(capsule_rect, Capsule(Capsule), Rectangle);
+relation_fn!(capsule_capsule, Capsule(Capsule), Capsule);
+relation_fn!(capsule_line, Capsule(Capsule), Line);
+
+impl CollisionDispatcher{
+    //dispatcher knowing all built in shape pairs
+    pub fn new() -> Self{
+        let mut dispatcher = Self::empty();
+        dispatcher.register(ShapeKind::Circle, ShapeKind::Circle, circle_circle);
+        dispatcher.register(ShapeKind::Circle, ShapeKind::Convex, circle_convex);
+        dispatcher.register(ShapeKind::Circle, ShapeKind::Line, circle_line);
+        dispatcher.register(ShapeKind::Convex, ShapeKind::Convex, convex_convex);
+        dispatcher.register(ShapeKind::Convex, ShapeKind::Line, convex_line);
+        dispatcher.register(ShapeKind::Rectangle, ShapeKind::Circle, rect_circle);
+        dispatcher.register(ShapeKind::Rectangle, ShapeKind::Convex, rect_convex);
+        dispatcher.register(ShapeKind::Rectangle, ShapeKind::Rectangle, rect_rect);
+        dispatcher.register(ShapeKind::Rectangle, ShapeKind::Line, rect_line);
+        dispatcher.register(ShapeKind::Capsule, ShapeKind::Circle, capsule_circle);
+        dispatcher.register(ShapeKind::Capsule, ShapeKind::Convex, capsule_convex);
+        dispatcher.register(ShapeKind::Capsule, ShapeKind::Rectangle, capsule_rect);
+        dispatcher.register(ShapeKind::Capsule, ShapeKind::Capsule, capsule_capsule);
+        dispatcher.register(ShapeKind::Capsule, ShapeKind::Line, capsule_line);
+        dispatcher
+    }
+
+    pub fn empty() -> Self{
+        Self { table: HashMap::new() }
+    }
+
+    //replaces a function registered for the same pair in the same order
+    pub fn register(&mut self, a: ShapeKind, b: ShapeKind, contact_fn: ContactFn){
+        self.table.insert((a, b), contact_fn);
+    }
+
+    pub fn unregister(&mut self, a: ShapeKind, b: ShapeKind){
+        self.table.remove(&(a, b));
+        self.table.remove(&(b, a));
+    }
+
+    //true, if the pair is registered in either order
+    //or the compound is handled by its convex parts
+    pub fn handles(&self, a: ShapeKind, b: ShapeKind) -> bool{
+        let part = |k| if k == ShapeKind::Compound {ShapeKind::Convex} else {k};
+        let registered = |a, b| self.table.contains_key(&(a, b)) || self.table.contains_key(&(b, a));
+        registered(a, b) || registered(part(a), part(b))
+    }
+
+    //normal of the contact points from a to b
+    pub fn get_contact(&self, a: &CollisionType, b: &CollisionType) -> Option<Contact>{
+        let (ka, kb) = (a.kind()?, b.kind()?);
+        if let Some(contact_fn) = self.table.get(&(ka, kb)){
+            return contact_fn(a, b);
+        }
+        if let Some(contact_fn) = self.table.get(&(kb, ka)){
+            return contact_fn(b, a).map(Contact::flipped);
+        }
+        self.compound_contact(a, b)
+    }
+
+    //deepest contact of the parts of a compound
+    fn compound_contact(&self, a: &CollisionType, b: &CollisionType) -> Option<Contact>{
+        let deepest = |c1: &Contact, c2: &Contact| c1.depth.total_cmp(&c2.depth);
+        let near = |part: &Convex2D, other: &CollisionType| other.aabb().is_none_or(|b| part.aabb().overlaps(&b));
+        if let CollisionType::Compound(c) = a{
+            return c.world_parts().iter().filter(|part| near(part, b)).filter_map(
+                |part| self.get_contact(&CollisionType::Convex(part), b)
+            ).max_by(deepest);
+        }
+        if let CollisionType::Compound(c) = b{
+            return c.world_parts().iter().filter(|part| near(part, a)).filter_map(
+                |part| self.get_contact(a, &CollisionType::Convex(part))
+            ).max_by(deepest);
+        }
+        None
+    }
+}
+
+impl Default for CollisionDispatcher{
+    fn default() -> Self{
+        Self::new()
+    }
+}
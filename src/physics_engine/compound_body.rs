@@ -0,0 +1,238 @@
+//compound body
+//
+//rigid body made of several convex parts, e.g. a decomposed concave outline
+//part vertices are relative to the body position like the ones of Convex2D
+//the dispatcher collides every part and keeps the deepest contact
+//the parts are kept as convex bodies at the pose of the compound,
+//updated with the cache of the world like Convex2D, parts_mut rebuilds them on the next update
+
+use std::borrow::Cow;
+
+use crate::primitives_2d::utils::{Vec2, Radians};
+
+use super::{physic_obj_traits::*, convex_body::Convex2D, geometry::Aabb, polygon::{self, PolygonError}};
+
+pub struct Compound{
+    pub body_type : BodyType,
+    pub gravity_scale : f32,
+    pub bullet : bool,
+    pub pos: Vec2,
+    //convex parts, counter clockwise
+    parts: Vec<Vec<(f32, f32)>>,
+    //outline the parts were made of, empty for bodies built from parts
+    pub outline: Vec<(f32, f32)>,
+    m : f32,
+    force: Vec2,
+    torque: f32,
+    vel: Vec2,
+    angle: cgmath::Rad<f32>,
+    ang_vel: cgmath::Rad<f32>,
+    inertia: f32,
+    id : usize,
+    nodes : Vec<usize>,
+    world: Vec<Convex2D>,
+    //parts changed since the world parts were built
+    dirty: bool,
+}
+
+impl Compound{
+    //every part has to be a valid convex polygon
+    pub fn new(x: f32, y: f32, parts: Vec<Vec<(f32, f32)>>, m: f32) -> Result<Self, PolygonError>{
+        let parts = parts.iter().map(|part| {
+            let verts : Vec<Vec2> = part.iter().map(|&v| v.into()).collect();
+            polygon::validate_convex(&verts).map(|verts| verts.into_iter().map(Into::into).collect())
+        }).collect::<Result<Vec<Vec<(f32, f32)>>, PolygonError>>()?;
+        if parts.is_empty(){
+            return Err(PolygonError::TooFewVertices(0));
+        }
+
+        let mut compound = Self {
+            body_type: BodyType::Dynamic,
+            gravity_scale: 1.,
+            bullet: false,
+            pos: Vec2::new(x, y),
+            parts,
+            outline: vec![],
+            m,
+            force: Vec2::new(0., 0.),
+            torque: 0.,
+            vel: Vec2::new(0., 0.),
+            angle: cgmath::Rad(0.),
+            ang_vel: cgmath::Rad(0.),
+            inertia: 1.,
+            id: 0,
+            nodes: vec![],
+            world: vec![],
+            dirty: false,
+        };
+        compound.inertia = compound.calc_inertia();
+        compound.world = compound.compute_world_parts();
+        Ok(compound)
+    }
+
+    //splits a simple concave outline into convex parts
+    //the body is moved to the center of mass, the outline stays where it is
+    pub fn from_polygon(x: f32, y: f32, outline: Vec<(f32, f32)>, m: f32) -> Result<Self, PolygonError>{
+        let verts : Vec<Vec2> = outline.into_iter().map(Vec2::from).collect();
+        let verts = polygon::simplify(&verts)?;
+        let center = polygon::centroid(&verts);
+        let local = |v: &Vec2| -> (f32, f32) {(v - center).into()};
+
+        let parts = polygon::decompose(&verts)?.iter().map(|part| part.iter().map(local).collect()).collect();
+        let mut compound = Self::new(x + center.x, y + center.y, parts, m)?;
+        compound.outline = verts.iter().map(local).collect();
+        Ok(compound)
+    }
+
+    pub fn parts(&self) -> &[Vec<(f32, f32)>]{
+        &self.parts
+    }
+
+    //the inertia is kept, see calc_inertia
+    pub fn parts_mut(&mut self) -> &mut Vec<Vec<(f32, f32)>>{
+        self.dirty = true;
+        &mut self.parts
+    }
+
+    fn part_areas(&self) -> Vec<f32>{
+        self.parts.iter().map(|part| {
+            let verts : Vec<Vec2> = part.iter().map(|&v| v.into()).collect();
+            polygon::signed_area(&verts)
+        }).collect()
+    }
+
+    //the mass is shared by the parts by their area
+    //every part uses the inertia of Convex2D
+    pub fn calc_inertia(&self) -> f32{
+        let areas = self.part_areas();
+        let total : f32 = areas.iter().sum();
+        self.parts.iter().zip(areas).map(|(part, area)| {
+            let sigma_dist_sq : f32 = part.iter().map(|(x, y)| x.powi(2) + y.powi(2)).sum();
+            self.m * area / total * sigma_dist_sq
+        }).sum()
+    }
+
+    //parts as convex bodies at the position and angle of the compound
+    pub fn compute_world_parts(&self) -> Vec<Convex2D>{
+        let areas = self.part_areas();
+        let total : f32 = areas.iter().sum();
+        self.parts.iter().zip(areas).map(|(part, area)| {
+            let mut convex = Convex2D::new(self.pos.x, self.pos.y, part.clone(), self.m * area / total);
+            convex.body_type = self.body_type;
+            convex.set_angle(self.angle);
+            convex
+        }).collect()
+    }
+
+    pub fn update_cache(&mut self){
+        if self.dirty{
+            self.world = self.compute_world_parts();
+            self.dirty = false;
+            return;
+        }
+        for part in self.world.iter_mut(){
+            part.pos = self.pos;
+            part.set_angle(self.angle);
+            part.update_cache();
+        }
+    }
+
+    fn cache_is_valid(&self) -> bool{
+        !self.dirty
+            && self.world.iter().all(|part| part.pos == self.pos && part.get_angle() == self.angle && part.cache_is_valid())
+    }
+
+    //cached parts, or computed again for a body moved since the last update
+    pub fn world_parts(&self) -> Cow<'_, [Convex2D]>{
+        if self.cache_is_valid() {Cow::Borrowed(&self.world)} else {Cow::Owned(self.compute_world_parts())}
+    }
+
+    pub fn aabb(&self) -> Aabb{
+        let parts = self.world_parts();
+        parts.iter().skip(1).fold(parts[0].aabb(), |aabb, part| aabb.union(&part.aabb()))
+    }
+}
+
+impl PhysicsObject for Compound{
+    fn body_type(&self)-> BodyType {
+        self.body_type
+    }
+    fn gravity_scale(&self)-> f32 {
+        self.gravity_scale
+    }
+    fn is_bullet(&self)-> bool {
+        self.bullet
+    }
+    fn update_cache(&mut self) {
+        Compound::update_cache(self);
+    }
+}
+
+impl ForceObject for Compound{
+    fn get_mass(&self) -> f32 {
+        self.m
+    }
+    fn get_force(&self) -> &Vec2 {
+        &self.force
+    }
+    fn get_force_mut(&mut self) -> &mut Vec2 {
+        &mut self.force
+    }
+    fn get_vel(&self) -> &Vec2 {
+        &self.vel
+    }
+    fn get_vel_mut(&mut self) -> &mut Vec2 {
+        &mut self.vel
+    }
+    fn get_torque(&self) -> f32 {
+        self.torque
+    }
+    fn set_torque(&mut self, torque: f32) {
+        self.torque = torque;
+    }
+    fn get_inertia(&self) -> f32 {
+        self.inertia
+    }
+    fn set_inertia(&mut self, inertia: f32) {
+        self.inertia = inertia;
+    }
+    fn get_angular_accel(&self) -> Radians {
+        self.ang_vel
+    }
+    fn set_angular_accel(&mut self, angle : Radians) {
+        self.ang_vel = angle;
+    }
+}
+
+impl TransposeObject for Compound{
+    fn get_pos(&self) -> &cgmath::Vector2<f32> {
+        &self.pos
+    }
+    fn get_pos_mut(&mut self) -> &mut cgmath::Vector2<f32> {
+        &mut self.pos
+    }
+    fn get_angle(&self) -> Radians {
+        self.angle
+    }
+    fn set_angle(&mut self, angle: Radians) {
+        self.angle = angle;
+    }
+}
+
+impl NodeObject for Compound{
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    fn get_connections(&self) -> &[usize] {
+        &self.nodes[..]
+    }
+}
+
+impl CollisionObject for Compound{
+    fn get_col_type(&self) -> CollisionType {
+        CollisionType::Compound(self)
+    }
+}
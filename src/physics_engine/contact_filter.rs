@@ -0,0 +1,99 @@
+//contact filter
+//
+//contacts can be disabled for a step before the world resolves them
+//the world asks the one way rule first and then the pre solve hook set by the user,
+//a disabled contact neither pushes the bodies apart nor counts as touching
+//one way platforms let bodies through unless they land on the side of their direction:
+//bodies hitting them from below or from the side, or moving away from that side,
+//pass through and keep passing until they stop overlapping the platform,
+//so a body jumping up through a platform is not pulled onto it halfway
+//platforms should be thicker than a step of the fastest body, deeper contacts flip the normal
+
+use std::collections::HashSet;
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::{physic_obj_traits::{PhysicsObject, CollisionType}, gjk::ConvexProxy, ccd::CCD_TARGET};
+
+//relative velocity along the platform direction above which bodies move away from it
+pub const ONE_WAY_SLOP : f32 = 0.00001;
+
+//contact about to be resolved
+pub struct PreSolveContact<'a>{
+    pub body_a: &'a dyn PhysicsObject,
+    pub body_b: &'a dyn PhysicsObject,
+    //points from body_a to body_b
+    pub normal: Vec2,
+    pub depth: f32,
+    pub point: Vec2,
+    //velocity of body_b relative to body_a at the contact point, per step
+    pub relative_vel: Vec2,
+}
+
+//returns false to disable the contact for this step
+pub type PreSolveFn = Box<dyn FnMut(&PreSolveContact) -> bool>;
+
+impl<'a> PreSolveContact<'a>{
+    pub fn new(body_a: &'a dyn PhysicsObject, body_b: &'a dyn PhysicsObject, normal: Vec2, depth: f32, point: Vec2) -> Self{
+        let relative_vel = point_vel(body_b, point) - point_vel(body_a, point);
+        Self { body_a, body_b, normal, depth, point, relative_vel }
+    }
+
+    //the other body and the normal pointing towards it, seen from the body with id
+    pub fn seen_from(&self, id: usize) -> Option<(&'a dyn PhysicsObject, Vec2)>{
+        if self.body_a.get_id() == id{
+            Some((self.body_b, self.normal))
+        }
+        else if self.body_b.get_id() == id{
+            Some((self.body_a, -self.normal))
+        }
+        else{
+            None
+        }
+    }
+}
+
+//velocity of the point of the body at world position point
+pub fn point_vel(body: &dyn PhysicsObject, point: Vec2) -> Vec2{
+    let ang_vel = if body.is_dynamic() {body.get_torque() * body.get_inv_inertia()} else {body.get_angular_accel().0};
+    let r = point - body.get_pos();
+    body.get_vel() + Vec2::new(-ang_vel * r.y, ang_vel * r.x)
+}
+
+//(platform id, body id) of a body passing through a one way platform, None if the contact is solid
+//passing holds the pairs that passed through during the last step
+pub fn one_way_pass(contact: &PreSolveContact, passing: &HashSet<(usize, usize)>) -> Option<(usize, usize)>{
+    let sides = [
+        (contact.body_a, contact.body_b, contact.normal, contact.relative_vel),
+        (contact.body_b, contact.body_a, -contact.normal, -contact.relative_vel),
+    ];
+    sides.into_iter().find_map(|(platform, body, n, vel)| {
+        let dir = platform.one_way()?;
+        let pair = (platform.get_id(), body.get_id());
+        let passes = passing.contains(&pair) || n.dot(dir) <= 0. || vel.dot(dir) > ONE_WAY_SLOP;
+        passes.then_some(pair)
+    })
+}
+
+//shapes moving along translation pass through a one way platform, if they move away from its side
+//or reach below its highest point, e.g. for shape casts and bullets
+//edge chains only check the movement, the casts skip the back of one sided edges
+pub fn passes_one_way(platform: &dyn PhysicsObject, shapes: &[ConvexProxy], translation: Vec2) -> bool{
+    let Some(dir) = platform.one_way() else {return false;};
+    if translation.dot(dir) >= 0.{
+        return true;
+    }
+    let col_type = platform.get_col_type();
+    if matches!(col_type, CollisionType::Line(_)){
+        return false;
+    }
+    let top = ConvexProxy::from_col_type(&col_type).iter()
+        .flat_map(|p| p.points.iter().map(move |v| v.dot(dir) + p.radius))
+        .fold(f32::MIN, f32::max);
+    let lowest = shapes.iter()
+        .flat_map(|p| p.points.iter().map(move |v| v.dot(dir) - p.radius))
+        .fold(f32::MAX, f32::min);
+    lowest < top - CCD_TARGET
+}
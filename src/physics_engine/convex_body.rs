@@ -2,17 +2,39 @@
 //
 
 
+use std::borrow::Cow;
+
 use crate::primitives_2d::utils::{Vec2, Radians, Mat2};
 
-use super::physic_obj_traits::*;
+use super::{physic_obj_traits::*, geometry::Aabb, polygon::{self, PolygonError}, fracture::Breakable};
+
 
+//vertices are stored around the origin of the body,
+//their world space positions, edge normals and bounding box are cached
+//the world updates the cache once per step after moving the bodies,
+//bodies moved in between compute the data again until the next update
+//vertices_mut marks the cache as outdated, even if the pose stays the same
 
-//ATTENTION: Vertices do not get translated
-//and will keep their initial value at the origin point
-//this needs to be of than handling collisions
+//world space data of the vertices at the pose it was computed for
+#[derive(Clone, Debug)]
+pub struct ConvexCache{
+    pos: Vec2,
+    angle: cgmath::Rad<f32>,
+    pub vertices: Vec<Vec2>,
+    //outward normal of the edge from vertex i to i+1
+    pub normals: Vec<Vec2>,
+    pub aabb: Aabb,
+}
 
+#[derive(Clone)]
 pub struct Convex2D{
-    pub static_body : bool,
+    pub body_type : BodyType,
+    pub gravity_scale : f32,
+    pub bullet : bool,
+    //None for bodies that do not break
+    pub breakable : Option<Breakable>,
+    //direction bodies land on the platform from, relative to the body, None for solid bodies
+    pub one_way : Option<Vec2>,
     pub pos: Vec2,
     m : f32,
     force: Vec2,
@@ -20,16 +42,24 @@ pub struct Convex2D{
     vel: Vec2,
     angle: cgmath::Rad<f32>,
     ang_vel: cgmath::Rad<f32>,
-    pub vertices: Vec<(f32, f32)>,
+    vertices: Vec<(f32, f32)>,
     inertia: f32,
     id : usize,
     nodes : Vec<usize>,
+    cache: ConvexCache,
+    //vertices changed since the cache was computed
+    dirty: bool,
 }
 
 impl Convex2D{
+    //vertices are used as given, see try_new for validated polygons
     pub fn new(x: f32, y: f32, vertices: Vec<(f32, f32)>, m : f32) -> Self{
         let mut convex = Self {
-            static_body: false,
+            body_type: BodyType::Dynamic,
+            gravity_scale: 1.,
+            bullet: false,
+            breakable: None,
+            one_way: None,
             pos: Vec2::new(x, y),
             force: Vec2::new(0., 0.),
             vel: Vec2::new(0., 0.),
@@ -41,13 +71,35 @@ impl Convex2D{
             inertia: 1.,
             id: 0,
             nodes : vec![],
+            cache: ConvexCache {
+                pos: Vec2::new(x, y), angle: cgmath::Rad(0.),
+                vertices: vec![], normals: vec![],
+                aabb: Aabb { min: Vec2::new(x, y), max: Vec2::new(x, y) },
+            },
+            dirty: true,
         };
 
         convex.inertia = convex.calc_inertia();
+        convex.update_cache();
 
         convex
     }
 
+    //winding, duplicate and collinear vertices are repaired,
+    //concave or degenerate polygons are rejected
+    pub fn try_new(x: f32, y: f32, vertices: Vec<(f32, f32)>, m : f32) -> Result<Self, PolygonError>{
+        let verts : Vec<Vec2> = vertices.into_iter().map(Vec2::from).collect();
+        let verts = polygon::validate_convex(&verts)?;
+        Ok(Self::new(x, y, verts.into_iter().map(Into::into).collect(), m))
+    }
+
+    //convex hull of the points, e.g. for point clouds or concave outlines
+    pub fn from_hull(x: f32, y: f32, points: Vec<(f32, f32)>, m : f32) -> Result<Self, PolygonError>{
+        let points : Vec<Vec2> = points.into_iter().map(Vec2::from).collect();
+        let verts = polygon::hull_polygon(&points)?;
+        Ok(Self::new(x, y, verts.into_iter().map(Into::into).collect(), m))
+    }
+
     pub fn calc_inertia(&self) -> f32{
         /*
          *
@@ -86,18 +138,70 @@ impl Convex2D{
         cgmath::Rad(self.torque / self.inertia)
     }
 
-    //TODO: think about if rotation matrix should be stored in struct
+    pub fn vertices(&self) -> &[(f32, f32)]{
+        &self.vertices
+    }
+
+    //the inertia is kept, see calc_inertia
+    pub fn vertices_mut(&mut self) -> &mut Vec<(f32, f32)>{
+        self.dirty = true;
+        &mut self.vertices
+    }
 
     pub fn transformed_vertex(&self, rot: &Mat2, vertex_index: usize) -> (f32, f32){
         let (x, y) = self.vertices[vertex_index];
         let v = Vec2::new(x, y);
         (rot * v + self.pos).into()
     }
+
+    //world space data at the current pose
+    pub fn compute_cache(&self) -> ConvexCache{
+        let rot = Mat2::from_angle(self.angle);
+        let vertices : Vec<Vec2> = (0..self.vertices.len()).map(|i| self.transformed_vertex(&rot, i).into()).collect();
+        let normals = polygon::outward_normals(&vertices);
+        let aabb = Aabb::from_points(&vertices);
+        ConvexCache { pos: self.pos, angle: self.angle, vertices, normals, aabb }
+    }
+
+    pub fn update_cache(&mut self){
+        if !self.cache_is_valid(){
+            self.cache = self.compute_cache();
+            self.dirty = false;
+        }
+    }
+
+    pub fn cache_is_valid(&self) -> bool{
+        !self.dirty && self.cache.pos == self.pos && self.cache.angle == self.angle
+    }
+
+    //cached data, or computed again for a body moved since the last update
+    pub fn world(&self) -> Cow<'_, ConvexCache>{
+        if self.cache_is_valid() {Cow::Borrowed(&self.cache)} else {Cow::Owned(self.compute_cache())}
+    }
+
+    pub fn aabb(&self) -> Aabb{
+        self.world().aabb
+    }
 }
 
 impl PhysicsObject for Convex2D{
-    fn is_static(&self)-> bool {
-        self.static_body
+    fn body_type(&self)-> BodyType {
+        self.body_type
+    }
+    fn gravity_scale(&self)-> f32 {
+        self.gravity_scale
+    }
+    fn is_bullet(&self)-> bool {
+        self.bullet
+    }
+    fn update_cache(&mut self) {
+        Convex2D::update_cache(self);
+    }
+    fn breakable(&self)-> Option<Breakable> {
+        self.breakable
+    }
+    fn one_way(&self)-> Option<Vec2> {
+        self.one_way.map(|d| Mat2::from_angle(self.angle) * d)
     }
 }
 
@@ -0,0 +1,199 @@
+//debug draw
+//
+//the world describes itself to a DebugDraw as segments, circles and dots,
+//e.g. to draw it over the game with primitives_2d::draw_debug or to check it in tests
+//the flags of the DebugDraw select what the world emits,
+//bodies are colored by their type and sleeping bodies are grey while sleep_state is set
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::{Vec2, Mat2};
+
+use super::{physic_obj_traits::*, chain_body::Chain, soft_body::SoftBody, geometry::Aabb};
+
+pub const DYNAMIC_COLOR : [f32; 4] = [0.9, 0.7, 0.3, 1.];
+pub const SLEEPING_COLOR : [f32; 4] = [0.5, 0.5, 0.5, 1.];
+pub const STATIC_COLOR : [f32; 4] = [0.5, 0.9, 0.5, 1.];
+pub const KINEMATIC_COLOR : [f32; 4] = [0.5, 0.5, 0.9, 1.];
+pub const AABB_COLOR : [f32; 4] = [0.9, 0.3, 0.9, 1.];
+pub const CONTACT_COLOR : [f32; 4] = [0.9, 0.2, 0.2, 1.];
+pub const JOINT_COLOR : [f32; 4] = [0.3, 0.8, 0.8, 1.];
+pub const CENTER_COLOR : [f32; 4] = [1., 1., 1., 1.];
+pub const VELOCITY_COLOR : [f32; 4] = [0.6, 1.0, 0.734, 1.0];
+
+//radius of the dots for contacts, anchors and centers
+pub const DOT_RADIUS : f32 = 0.006;
+//length of the contact normals
+pub const NORMAL_LENGTH : f32 = 0.04;
+//segments of the outline of a circle
+pub const CIRCLE_SEGMENTS : usize = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DebugDrawFlags{
+    pub shapes: bool,
+    pub aabbs: bool,
+    //contact points and normals of the last step
+    pub contacts: bool,
+    //chain attachments and pinned chain points
+    pub joints: bool,
+    pub center_of_mass: bool,
+    pub velocities: bool,
+    pub sleep_state: bool,
+}
+
+impl DebugDrawFlags{
+    pub fn all() -> Self{
+        Self { shapes: true, aabbs: true, contacts: true, joints: true, center_of_mass: true, velocities: true, sleep_state: true }
+    }
+
+    pub fn none() -> Self{
+        Self { shapes: false, aabbs: false, contacts: false, joints: false, center_of_mass: false, velocities: false, sleep_state: false }
+    }
+}
+
+impl Default for DebugDrawFlags{
+    fn default() -> Self{
+        Self { shapes: true, joints: true, velocities: true, sleep_state: true, ..Self::none() }
+    }
+}
+
+pub trait DebugDraw{
+    fn flags(&self) -> DebugDrawFlags;
+    fn draw_segment(&mut self, a: Vec2, b: Vec2, color: [f32; 4]);
+    //filled circle
+    fn draw_dot(&mut self, center: Vec2, radius: f32, color: [f32; 4]);
+    //velocities are per step, they are drawn this many times longer
+    fn velocity_scale(&self) -> f32 {1.}
+
+    //closed outline
+    fn draw_polygon(&mut self, points: &[Vec2], color: [f32; 4]){
+        let n = points.len();
+        if n < 2 {return;}
+        for i in 0..n{
+            self.draw_segment(points[i], points[(i+1)%n], color);
+        }
+    }
+
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: [f32; 4]){
+        let points : Vec<Vec2> = (0..CIRCLE_SEGMENTS).map(|i| {
+            let a = std::f32::consts::TAU * i as f32 / CIRCLE_SEGMENTS as f32;
+            center + Vec2::new(a.cos(), a.sin()) * radius
+        }).collect();
+        self.draw_polygon(&points, color);
+    }
+
+    fn draw_aabb(&mut self, aabb: &Aabb, color: [f32; 4]){
+        let (min, max) = (aabb.min, aabb.max);
+        self.draw_polygon(&[min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)], color);
+    }
+}
+
+pub fn body_color(body: &dyn PhysicsObject, awake: bool, flags: &DebugDrawFlags) -> [f32; 4]{
+    match body.body_type(){
+        BodyType::Static => STATIC_COLOR,
+        BodyType::Kinematic => KINEMATIC_COLOR,
+        BodyType::Dynamic if flags.sleep_state && !awake => SLEEPING_COLOR,
+        BodyType::Dynamic => DYNAMIC_COLOR,
+    }
+}
+
+//shapes, boxes, centers and velocities of a body, as selected by the flags
+pub fn draw_body(draw: &mut dyn DebugDraw, body: &dyn PhysicsObject, awake: bool){
+    let flags = draw.flags();
+    let col_type = body.get_col_type();
+    if flags.shapes{
+        draw_shape(draw, &col_type, body.get_angle().0, body_color(body, awake, &flags));
+    }
+    if flags.aabbs{
+        if let Some(aabb) = col_type.aabb(){
+            draw.draw_aabb(&aabb, AABB_COLOR);
+        }
+    }
+    if flags.center_of_mass && body.is_dynamic(){
+        draw.draw_dot(*body.get_pos(), DOT_RADIUS, CENTER_COLOR);
+    }
+    if flags.velocities && !body.is_static() && body.get_vel().magnitude2() > 0.{
+        let pos = *body.get_pos();
+        let scale = draw.velocity_scale();
+        draw.draw_segment(pos, pos + body.get_vel() * scale, VELOCITY_COLOR);
+    }
+}
+
+//circles get a radius line showing their rotation
+pub fn draw_shape(draw: &mut dyn DebugDraw, col_type: &CollisionType, angle: f32, color: [f32; 4]){
+    match col_type{
+        CollisionType::Circle(c) => {
+            draw.draw_circle(c.pos, c.r, color);
+            draw.draw_segment(c.pos, c.pos + Vec2::new(angle.cos(), angle.sin()) * c.r, color);
+        },
+        CollisionType::Line(l) => {
+            for i in 0..l.segment_count(){
+                let seg = l.segment(i);
+                draw.draw_segment(seg.v1, seg.v2, color);
+            }
+        },
+        CollisionType::Rectangle(r) => draw.draw_polygon(&r.world_vertices(), color),
+        CollisionType::Capsule(c) => {
+            let (a, b) = c.segment();
+            let side = Mat2::from_angle(cgmath::Rad(angle)).y * c.r;
+            draw.draw_segment(a + side, b + side, color);
+            draw.draw_segment(a - side, b - side, color);
+            draw.draw_circle(a, c.r, color);
+            draw.draw_circle(b, c.r, color);
+        },
+        CollisionType::Convex(c) => draw.draw_polygon(&c.world().vertices, color),
+        CollisionType::Compound(c) => {
+            for part in c.world_parts().iter(){
+                draw.draw_polygon(&part.world().vertices, color);
+            }
+        },
+        CollisionType::Custom { .. } => {
+            if let Some(aabb) = col_type.aabb(){
+                draw.draw_aabb(&aabb, color);
+            }
+        },
+        CollisionType::NoCollision => {},
+    }
+}
+
+//dot and normal, the normal points from the first body to the second one
+pub fn draw_contact(draw: &mut dyn DebugDraw, contact: &Contact){
+    draw.draw_dot(contact.point, DOT_RADIUS, CONTACT_COLOR);
+    draw.draw_segment(contact.point, contact.point + contact.normal * NORMAL_LENGTH, CONTACT_COLOR);
+}
+
+//chain segments as shapes, pinned points and attachments as joints
+//body_anchor gives the world position of the anchor of an attachment
+pub fn draw_chain(draw: &mut dyn DebugDraw, chain: &Chain, body_anchor: impl Fn(usize, Vec2) -> Option<Vec2>){
+    let flags = draw.flags();
+    if flags.shapes{
+        for w in chain.points.windows(2){
+            draw.draw_segment(w[0].pos, w[1].pos, DYNAMIC_COLOR);
+        }
+    }
+    if !flags.joints{return;}
+    for p in chain.points.iter().filter(|p| p.pinned){
+        draw.draw_dot(p.pos, DOT_RADIUS, JOINT_COLOR);
+    }
+    for a in chain.get_attachments(){
+        let point = chain.points[a.point_index].pos;
+        let Some(anchor) = body_anchor(a.body_id, a.local_anchor) else {continue;};
+        draw.draw_segment(point, anchor, JOINT_COLOR);
+        draw.draw_dot(anchor, DOT_RADIUS, JOINT_COLOR);
+    }
+}
+
+pub fn draw_soft_body(draw: &mut dyn DebugDraw, soft_body: &SoftBody){
+    let flags = draw.flags();
+    if flags.shapes{
+        draw.draw_polygon(&soft_body.outline_points(), DYNAMIC_COLOR);
+    }
+    if flags.joints{
+        for p in soft_body.points.iter().filter(|p| p.pinned){
+            draw.draw_dot(p.pos, DOT_RADIUS, JOINT_COLOR);
+        }
+    }
+    if flags.center_of_mass{
+        draw.draw_dot(soft_body.center(), DOT_RADIUS, CENTER_COLOR);
+    }
+}
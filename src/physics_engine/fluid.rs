@@ -0,0 +1,124 @@
+//fluids
+//
+//water volumes of the world, convex regions with a density
+//bodies are clipped against the region, the fluid pushes the submerged part up
+//against the gravity at the submerged centroid, so bodies lighter than the fluid
+//float and tilted ones turn upright
+//the drag slows down the submerged part relative to the flow of the fluid
+//circles are clipped as polygons of the same area
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::{physic_obj_traits::*, geometry::{Aabb, cross}, polygon::{self, PolygonError}};
+
+//vertices of the polygons standing in for circles
+pub const CIRCLE_SEGMENTS : usize = 16;
+//share of the relative velocity and of the angular velocity
+//a fully submerged body loses per step
+pub const LINEAR_DRAG : f32 = 0.05;
+pub const ANGULAR_DRAG : f32 = 0.05;
+
+pub struct Fluid{
+    //convex, counter clockwise
+    pub region: Vec<Vec2>,
+    //mass per area, bodies with a lower density float
+    pub density: f32,
+    pub linear_drag: f32,
+    pub angular_drag: f32,
+    //velocity of the fluid per step, e.g. a river
+    pub flow: Vec2,
+}
+
+pub struct Submerged{
+    pub area: f32,
+    pub centroid: Vec2,
+    //submerged share of the area of the body
+    pub fraction: f32,
+}
+
+impl Fluid{
+    pub fn new(region: Vec<Vec2>, density: f32) -> Result<Self, PolygonError>{
+        Ok(Self {
+            region: polygon::validate_convex(&region)?,
+            density,
+            linear_drag: LINEAR_DRAG,
+            angular_drag: ANGULAR_DRAG,
+            flow: Vec2::new(0., 0.),
+        })
+    }
+
+    pub fn from_aabb(aabb: Aabb, density: f32) -> Self{
+        let (min, max) = (aabb.min, aabb.max);
+        Self {
+            region: vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)],
+            density,
+            linear_drag: LINEAR_DRAG,
+            angular_drag: ANGULAR_DRAG,
+            flow: Vec2::new(0., 0.),
+        }
+    }
+
+    //None for bodies outside of the fluid and shapes without an area
+    pub fn submerged(&self, shape: &CollisionType) -> Option<Submerged>{
+        let polygons = body_polygons(shape);
+        let total : f32 = polygons.iter().map(|p| polygon::signed_area(p).abs()).sum();
+        let mut area = 0.;
+        let mut moment = Vec2::new(0., 0.);
+        for p in polygons.iter(){
+            let clipped = polygon::clip_convex(p, &self.region);
+            if clipped.len() < 3{continue;}
+            let part = polygon::signed_area(&clipped).abs();
+            if part <= 0.{continue;}
+            area += part;
+            moment += polygon::centroid(&clipped) * part;
+        }
+        if area <= 0. || total <= 0.{
+            return None;
+        }
+        Some(Submerged { area, centroid: moment / area, fraction: (area / total).min(1.) })
+    }
+
+    //gravity is the acceleration of the world at the body, without its gravity scale
+    pub fn apply(&self, body: &mut dyn PhysicsObject, gravity: Vec2){
+        let Some(submerged) = self.submerged(&body.get_col_type()) else {return;};
+        let r = submerged.centroid - body.get_pos();
+        let ang_vel = body.get_angular_accel().0;
+        let point_vel = body.get_vel() + Vec2::new(-ang_vel * r.y, ang_vel * r.x);
+
+        let buoyancy = -gravity * (self.density * submerged.area);
+        let drag = (self.flow - point_vel) * (body.get_mass() * self.linear_drag * submerged.fraction);
+        let force = buoyancy + drag;
+        *body.get_force_mut() += force;
+
+        let torque = body.get_torque() * (1. - (self.angular_drag * submerged.fraction).min(1.)) + cross(r, force);
+        body.set_torque(torque);
+    }
+}
+
+//convex polygons covering the body in world coordinates
+fn body_polygons(shape: &CollisionType) -> Vec<Vec<Vec2>>{
+    match shape{
+        CollisionType::Circle(c) => vec![circle_polygon(c.pos, c.r)],
+        CollisionType::Rectangle(r) => vec![r.world_vertices()],
+        CollisionType::Convex(c) => vec![c.world().vertices.clone()],
+        CollisionType::Compound(c) => c.world_parts().iter().map(|part| part.world().vertices.clone()).collect(),
+        _ => vec![],
+    }
+}
+
+//regular polygon with the area of the circle
+fn circle_polygon(center: Vec2, r: f32) -> Vec<Vec2>{
+    let step = std::f32::consts::TAU / CIRCLE_SEGMENTS as f32;
+    let polygon_area = 0.5 * CIRCLE_SEGMENTS as f32 * step.sin();
+    let radius = r * (std::f32::consts::PI / polygon_area).sqrt();
+    (0..CIRCLE_SEGMENTS).map(|i| {
+        let a = step * i as f32;
+        center + Vec2::new(a.cos(), a.sin()) * radius
+    }).collect()
+}
+
+//area of a body as used by the fluids, e.g. to pick a density
+pub fn body_area(shape: &CollisionType) -> f32{
+    body_polygons(shape).iter().map(|p| polygon::signed_area(p).abs()).sum()
+}
+
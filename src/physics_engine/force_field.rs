@@ -0,0 +1,119 @@
+//force fields
+//
+//forces the world applies to dynamic bodies inside a region
+//gravity fields add to the gravity of the world, both are scaled by the gravity scale of each body
+//a body is inside a region, if its position is inside the region shape
+//all strengths are per step like the velocities, attractors and vortexes
+//act on every mass alike like the gravity, wind pushes light bodies further
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::{geometry::Aabb, polygon};
+
+pub enum Region{
+    Everywhere,
+    Circle{center: Vec2, r: f32},
+    Aabb(Aabb),
+    //simple polygon, may be concave
+    Polygon(Vec<Vec2>),
+}
+
+impl Region{
+    pub fn contains(&self, p: Vec2) -> bool{
+        match self{
+            Region::Everywhere => true,
+            Region::Circle { center, r } => (p - center).magnitude2() <= r * r,
+            Region::Aabb(aabb) => aabb.contains(p),
+            Region::Polygon(verts) => polygon::contains_point(verts, p),
+        }
+    }
+}
+
+//strength of attractors and vortexes over the distance to their center
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Falloff{
+    Constant,
+    //from full strength at the center to zero at the radius
+    Linear{radius: f32},
+    //full strength at the min distance and below
+    InverseSquare{min_distance: f32},
+}
+
+impl Falloff{
+    pub fn factor(&self, distance: f32) -> f32{
+        match *self{
+            Falloff::Constant => 1.,
+            Falloff::Linear { radius } => (1. - distance / radius).max(0.),
+            Falloff::InverseSquare { min_distance } => (min_distance / distance.max(min_distance)).powi(2),
+        }
+    }
+}
+
+pub enum FieldKind{
+    //acceleration, scaled by the gravity scale of the body
+    Gravity{accel: Vec2},
+    //acceleration towards the center, negative strengths repel
+    Point{center: Vec2, strength: f32, falloff: Falloff},
+    //force in the direction of the wind
+    Wind{force: Vec2},
+    //lift is the share of the gravity taken away, bodies float with a lift above 1
+    //drag is the share of the velocity lost per step
+    Buoyancy{lift: f32, drag: f32},
+    //acceleration around the center, counter clockwise for positive strengths
+    Vortex{center: Vec2, strength: f32, falloff: Falloff},
+}
+
+pub struct ForceField{
+    pub region: Region,
+    pub kind: FieldKind,
+    pub enabled: bool,
+}
+
+impl ForceField{
+    pub fn new(region: Region, kind: FieldKind) -> Self{
+        Self { region, kind, enabled: true }
+    }
+
+    pub fn gravity(accel: Vec2) -> Self{
+        Self::new(Region::Everywhere, FieldKind::Gravity { accel })
+    }
+
+    pub fn acts_on(&self, pos: Vec2) -> bool{
+        self.enabled && self.region.contains(pos)
+    }
+
+    //gravitational acceleration at pos before the gravity scale of a body
+    pub fn gravity_at(&self, pos: Vec2) -> Vec2{
+        match self.kind{
+            FieldKind::Gravity { accel } if self.acts_on(pos) => accel,
+            _ => Vec2::new(0., 0.),
+        }
+    }
+
+    //force on a body at pos, gravity is the scaled gravity acceleration of all fields at pos
+    pub fn force_on(&self, pos: Vec2, vel: Vec2, mass: f32, gravity: Vec2) -> Vec2{
+        if !self.acts_on(pos){
+            return Vec2::new(0., 0.);
+        }
+        match self.kind{
+            //summed up with the other gravity fields beforehand
+            FieldKind::Gravity { .. } => Vec2::new(0., 0.),
+            FieldKind::Point { center, strength, falloff } => {
+                let d = center - pos;
+                let dist = d.magnitude();
+                if dist <= 0.{return Vec2::new(0., 0.);}
+                d / dist * (mass * strength * falloff.factor(dist))
+            },
+            FieldKind::Wind { force } => force,
+            FieldKind::Buoyancy { lift, drag } => -gravity * (mass * lift) - vel * (mass * drag),
+            FieldKind::Vortex { center, strength, falloff } => {
+                let d = pos - center;
+                let dist = d.magnitude();
+                if dist <= 0.{return Vec2::new(0., 0.);}
+                Vec2::new(-d.y, d.x) / dist * (mass * strength * falloff.factor(dist))
+            },
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//fracture
+//
+//breakable bodies and slicing
+//outlines of convex bodies and rectangles are cut by lines into convex pieces,
+//every piece becomes a Convex2D with the share of the mass of its area
+//and moves like the point of the original body at its centroid,
+//pieces with more vertices than a Convex2D may have are halved first
+//bodies break when a single contact impulse reaches their threshold,
+//the cracks run through the contact point, the first one along the impact
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::{Vec2, Mat2};
+
+use super::{physic_obj_traits::*, convex_body::Convex2D, geometry::{segments_intersection, Intersects}, polygon};
+
+//smaller pieces are dropped, e.g. slivers at the corners
+pub const MIN_PIECE_AREA : f32 = 0.00001;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Breakable{
+    //contact impulse breaking the body
+    pub threshold: f32,
+    //cracks through the impact point, n cracks give up to 2n pieces
+    pub cracks: usize,
+}
+
+impl Breakable{
+    pub fn new(threshold: f32) -> Self{
+        Self { threshold, cracks: 2 }
+    }
+}
+
+//world space outline of the shapes that can be cut
+pub fn body_outline(shape: &CollisionType) -> Option<Vec<Vec2>>{
+    match shape{
+        CollisionType::Convex(c) => Some(c.world().vertices.clone()),
+        CollisionType::Rectangle(r) => Some(r.world_vertices()),
+        _ => None,
+    }
+}
+
+//pieces of the outline cut by lines through point,
+//dir is the direction of the first crack, the others follow at equal angles
+pub fn crack_polygon(outline: &[Vec2], point: Vec2, dir: Vec2, cracks: usize) -> Vec<Vec<Vec2>>{
+    let dir = if dir.magnitude2() > 0. {dir.normalize()} else {Vec2::new(1., 0.)};
+    let mut pieces = vec![outline.to_vec()];
+    for k in 0..cracks{
+        let rot = Mat2::from_angle(cgmath::Rad(std::f32::consts::PI * k as f32 / cracks as f32));
+        let d = rot * dir;
+        pieces = pieces.iter().flat_map(|p| {
+            let (left, right) = polygon::split_convex(p, point - d, point + d);
+            [left, right]
+        }).filter(|p| !p.is_empty()).collect();
+    }
+    pieces
+}
+
+//both halves of the outline, None if the segment does not cut all the way through
+pub fn slice_polygon(outline: &[Vec2], from: Vec2, to: Vec2) -> Option<(Vec<Vec2>, Vec<Vec2>)>{
+    if polygon::contains_point(outline, from) || polygon::contains_point(outline, to){
+        return None;
+    }
+    let n = outline.len();
+    let crosses = (0..n).any(
+        |i| !matches!(segments_intersection(from, to, outline[i], outline[(i+1)%n]), Intersects::None)
+    );
+    if !crosses{
+        return None;
+    }
+    let (left, right) = polygon::split_convex(outline, from, to);
+    if polygon::signed_area(&left).abs() < MIN_PIECE_AREA || polygon::signed_area(&right).abs() < MIN_PIECE_AREA{
+        return None;
+    }
+    Some((left, right))
+}
+
+//convex polygon cut in half until every part fits into a Convex2D,
+//cutting a corner off adds a vertex to the rest of the polygon
+fn within_vertex_limit(outline: &[Vec2]) -> Vec<Vec<Vec2>>{
+    if outline.len() <= polygon::MAX_POLYGON_VERTICES{
+        return vec![outline.to_vec()];
+    }
+    //through opposite vertices, both halves get fewer vertices
+    let (left, right) = polygon::split_convex(outline, outline[0], outline[outline.len()/2]);
+    [left, right].iter().filter(|p| !p.is_empty()).flat_map(|p| within_vertex_limit(p)).collect()
+}
+
+//bodies for the world space polygons cut from body, which is not changed
+//pieces keep the body type, gravity scale and bullet flag of a Convex2D
+//empty if a piece is no valid polygon, the body has to stay whole then or its mass would get lost
+pub fn pieces_of(body: &dyn PhysicsObject, polygons: &[Vec<Vec2>]) -> Vec<Convex2D>{
+    let Some(outline) = body_outline(&body.get_col_type()) else {return vec![];};
+    let total_area = polygon::signed_area(&outline).abs();
+    if total_area <= 0.{
+        return vec![];
+    }
+    let bullet = match body.get_col_type(){
+        CollisionType::Convex(c) => c.bullet,
+        _ => false,
+    };
+    let ang_vel = if body.is_dynamic() {body.get_torque() * body.get_inv_inertia()} else {body.get_angular_accel().0};
+
+    let polygons : Vec<Vec<Vec2>> = polygons.iter().flat_map(|p| within_vertex_limit(p)).collect();
+    polygons.iter().filter(|p| polygon::signed_area(p).abs() >= MIN_PIECE_AREA).map(|p| {
+        let area = polygon::signed_area(p).abs();
+        let center = polygon::centroid(p);
+        let local = p.iter().map(|v| (v - center).into()).collect();
+        let mut piece = Convex2D::try_new(center.x, center.y, local, body.get_mass() * area / total_area)?;
+        piece.body_type = body.body_type();
+        piece.gravity_scale = body.gravity_scale();
+        piece.bullet = bullet;
+
+        let r = center - body.get_pos();
+        *piece.get_vel_mut() = body.get_vel() + Vec2::new(-ang_vel * r.y, ang_vel * r.x);
+        if piece.is_dynamic(){
+            let inertia = piece.get_inertia();
+            piece.set_torque(ang_vel * inertia);
+        }
+        piece.set_angular_accel(cgmath::Rad(ang_vel));
+        Ok(piece)
+    }).collect::<Result<Vec<_>, polygon::PolygonError>>().unwrap_or_default()
+}
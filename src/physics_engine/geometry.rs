@@ -0,0 +1,221 @@
+//geometry utilities
+//
+//segment and ray queries shared by the bodies and collision relations
+//all segments are given by their two end points
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+//below this length segments are treated as points
+//and lines as parallel
+pub const EPSILON : f32 = 0.000001;
+
+pub enum Intersects{
+    //segments cross or touch in exactly one point
+    Single(Vec2),
+    //collinear segments share the part between the two points
+    Overlap(Vec2, Vec2),
+    None,
+}
+
+//axis aligned bounding box
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb{
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb{
+    pub fn from_points(points: &[Vec2]) -> Self{
+        let min = points.iter().fold(Vec2::new(f32::MAX, f32::MAX), |m, p| Vec2::new(m.x.min(p.x), m.y.min(p.y)));
+        let max = points.iter().fold(Vec2::new(-f32::MAX, -f32::MAX), |m, p| Vec2::new(m.x.max(p.x), m.y.max(p.y)));
+        Self { min, max }
+    }
+
+    pub fn around_circle(center: Vec2, r: f32) -> Self{
+        Self { min: center - Vec2::new(r, r), max: center + Vec2::new(r, r) }
+    }
+
+    pub fn union(&self, other: &Aabb) -> Self{
+        Self {
+            min: Vec2::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Vec2::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    //points on the border are inside
+    pub fn contains(&self, p: Vec2) -> bool{
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+
+    //boxes touching at their border overlap
+    pub fn overlaps(&self, other: &Aabb) -> bool{
+        self.min.x <= other.max.x && other.min.x <= self.max.x
+            && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+}
+
+pub struct RayHit{
+    //distance along the ray in multiples of its direction
+    pub t: f32,
+    pub point: Vec2,
+    //normal of the hit segment, facing the ray origin
+    pub normal: Vec2,
+}
+
+//z component of the 3D cross product
+pub fn cross(a: Vec2, b: Vec2) -> f32{
+    a.x*b.y - a.y*b.x
+}
+
+//left normal of the direction a -> b, unit length
+pub fn segment_normal(a: Vec2, b: Vec2) -> Vec2{
+    let d = b - a;
+    if d.magnitude2() > EPSILON {Vec2::new(-d.y, d.x).normalize()} else {Vec2::new(0., 1.)}
+}
+
+//projection of p onto the infinite line through a and b
+//returns parameter t (0 at a, 1 at b) and the projected point
+pub fn closest_point_on_line(p: Vec2, a: Vec2, b: Vec2) -> (f32, Vec2){
+    let ab = b - a;
+    let len_sq = ab.magnitude2();
+    if len_sq < EPSILON{
+        return (0., a);
+    }
+    let t = (p - a).dot(ab) / len_sq;
+    (t, a + ab * t)
+}
+
+//returns parameter t on segment ab and the closest point
+pub fn closest_point_on_segment(p: Vec2, a: Vec2, b: Vec2) -> (f32, Vec2){
+    let (t, _) = closest_point_on_line(p, a, b);
+    let t = t.clamp(0., 1.);
+    (t, a + (b - a) * t)
+}
+
+pub fn point_segment_distance(p: Vec2, a: Vec2, b: Vec2) -> f32{
+    let (_, closest) = closest_point_on_segment(p, a, b);
+    (p - closest).magnitude()
+}
+
+//returns parameters s, t and the closest points c1 on p1q1, c2 on p2q2
+//for parallel segments one of the closest pairs is returned
+pub fn closest_points_between_segments(p1: Vec2, q1: Vec2, p2: Vec2, q2: Vec2) -> (f32, f32, Vec2, Vec2){
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.magnitude2();
+    let e = d2.magnitude2();
+    let f = d2.dot(r);
+
+    let (s, t) = if a < EPSILON && e < EPSILON {
+        (0., 0.)
+    }
+    else if a < EPSILON {
+        (0., (f / e).clamp(0., 1.))
+    }
+    else {
+        let c = d1.dot(r);
+        if e < EPSILON {
+            ((-c / a).clamp(0., 1.), 0.)
+        }
+        else {
+            let b = d1.dot(d2);
+            let denom = a*e - b*b;
+            let mut s = if denom > EPSILON {((b*f - c*e) / denom).clamp(0., 1.)} else {0.};
+            let mut t = (b*s + f) / e;
+            if t < 0. {
+                t = 0.;
+                s = (-c / a).clamp(0., 1.);
+            }
+            else if t > 1. {
+                t = 1.;
+                s = ((b - c) / a).clamp(0., 1.);
+            }
+            (s, t)
+        }
+    };
+    (s, t, p1 + d1 * s, p2 + d2 * t)
+}
+
+pub fn segments_distance(p1: Vec2, q1: Vec2, p2: Vec2, q2: Vec2) -> f32{
+    if let Intersects::None = segments_intersection(p1, q1, p2, q2){
+        let (_, _, c1, c2) = closest_points_between_segments(p1, q1, p2, q2);
+        (c2 - c1).magnitude()
+    }
+    else{
+        0.
+    }
+}
+
+//parametric intersection p1 + r*t = p3 + s*u
+//works for vertical and degenerated segments
+pub fn segments_intersection(p1: Vec2, p2: Vec2, p3: Vec2, p4: Vec2) -> Intersects{
+    let r = p2 - p1;
+    let s = p4 - p3;
+    let r_len_sq = r.magnitude2();
+    let s_len_sq = s.magnitude2();
+
+    if r_len_sq < EPSILON && s_len_sq < EPSILON{
+        return if (p3 - p1).magnitude2() < EPSILON {Intersects::Single(p1)} else {Intersects::None};
+    }
+    if r_len_sq < EPSILON{
+        return if point_segment_distance(p1, p3, p4) < EPSILON {Intersects::Single(p1)} else {Intersects::None};
+    }
+    if s_len_sq < EPSILON{
+        return if point_segment_distance(p3, p1, p2) < EPSILON {Intersects::Single(p3)} else {Intersects::None};
+    }
+
+    let qp = p3 - p1;
+    let denom = cross(r, s);
+
+    if denom.abs() / (r_len_sq * s_len_sq).sqrt() < EPSILON{
+        //parallel; distance of p3 to the line through p1 and p2
+        if cross(qp, r).abs() / r_len_sq.sqrt() >= EPSILON{
+            return Intersects::None;
+        }
+        let t0 = qp.dot(r) / r_len_sq;
+        let t1 = t0 + s.dot(r) / r_len_sq;
+        let lo = t0.min(t1).max(0.);
+        let hi = t0.max(t1).min(1.);
+        let tolerance = EPSILON / r_len_sq.sqrt();
+        if lo > hi + tolerance{
+            return Intersects::None;
+        }
+        if hi - lo <= tolerance{
+            return Intersects::Single(p1 + r * lo.min(hi));
+        }
+        return Intersects::Overlap(p1 + r * lo, p1 + r * hi);
+    }
+
+    let t = cross(qp, s) / denom;
+    let u = cross(qp, r) / denom;
+    let t_tolerance = EPSILON / r_len_sq.sqrt();
+    let u_tolerance = EPSILON / s_len_sq.sqrt();
+    if t < -t_tolerance || t > 1. + t_tolerance || u < -u_tolerance || u > 1. + u_tolerance{
+        return Intersects::None;
+    }
+    Intersects::Single(p1 + r * t.clamp(0., 1.))
+}
+
+//casts the ray origin + dir*t with 0 <= t <= max_t against segment ab
+//rays running along the segment do not hit it
+pub fn ray_segment_cast(origin: Vec2, dir: Vec2, max_t: f32, a: Vec2, b: Vec2) -> Option<RayHit>{
+    let e = b - a;
+    let denom = cross(dir, e);
+    if denom.abs() < EPSILON * EPSILON{
+        return None;
+    }
+    let ao = a - origin;
+    let t = cross(ao, e) / denom;
+    let u = cross(ao, dir) / denom;
+    if !(0. ..=max_t).contains(&t) || !(0. ..=1.).contains(&u){
+        return None;
+    }
+    let mut normal = segment_normal(a, b);
+    if normal.dot(dir) > 0.{
+        normal = -normal;
+    }
+    Some(RayHit { t, point: origin + dir * t, normal })
+}
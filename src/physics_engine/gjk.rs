@@ -0,0 +1,311 @@
+//gjk distance
+//
+//distance and closest points between two convex shapes
+//every shape is the convex hull of its points inflated by a radius,
+//circles are a single point, capsules a segment
+//the simplex keeps the barycentric weights of its vertices,
+//so the closest points on both shapes follow from the closest point to the origin
+
+use std::cell::Cell;
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::{geometry::{cross, EPSILON}, physic_obj_traits::*};
+
+pub const GJK_MAX_ITERATIONS : usize = 32;
+
+thread_local!{
+    //runs of gjk and their iterations on this thread, for the stats of the world
+    static COUNTERS : Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+//(runs, iterations) since the last call
+pub fn take_counters() -> (usize, usize){
+    COUNTERS.with(|c| c.replace((0, 0)))
+}
+
+#[derive(Clone, Debug)]
+pub struct ConvexProxy{
+    pub points: Vec<Vec2>,
+    pub radius: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DistanceOutput{
+    //0 if the shapes touch or overlap
+    pub distance: f32,
+    //closest point on the surface of shape a and b
+    pub point_a: Vec2,
+    pub point_b: Vec2,
+    pub iterations: usize,
+}
+
+impl DistanceOutput{
+    pub fn overlap(&self) -> bool{
+        self.distance <= 0.
+    }
+
+    //unit direction from shape a to shape b, None for overlapping shapes
+    pub fn normal(&self) -> Option<Vec2>{
+        let d = self.point_b - self.point_a;
+        if d.magnitude2() > EPSILON * EPSILON {Some(d.normalize())} else {None}
+    }
+}
+
+impl ConvexProxy{
+    pub fn new(points: Vec<Vec2>, radius: f32) -> Self{
+        Self { points, radius }
+    }
+
+    pub fn point(p: Vec2, radius: f32) -> Self{
+        Self { points: vec![p], radius }
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Self{
+        Self { points: self.points.iter().map(|p| p + offset).collect(), radius: self.radius }
+    }
+
+    //convex pieces of a collision shape in world space
+    //edge chains are split into their segments
+    pub fn from_col_type(col_type: &CollisionType) -> Vec<Self>{
+        match col_type{
+            CollisionType::Circle(c) => vec![Self::point(c.pos, c.r)],
+            CollisionType::Convex(c) => vec![Self::new(c.world().vertices.clone(), 0.)],
+            CollisionType::Compound(c) => c.world_parts().iter().flat_map(
+                |part| Self::from_col_type(&CollisionType::Convex(part))
+            ).collect(),
+            CollisionType::Rectangle(r) => vec![Self::new(r.world_vertices(), 0.)],
+            CollisionType::Capsule(c) => {
+                let (a, b) = c.segment();
+                vec![Self::new(vec![a, b], c.r)]
+            },
+            CollisionType::Line(l) => (0..l.segment_count()).map(|i| {
+                let seg = l.segment(i);
+                Self::new(vec![seg.v1, seg.v2], 0.)
+            }).collect(),
+            _ => vec![],
+        }
+    }
+
+    fn support(&self, d: Vec2) -> usize{
+        let mut best = 0;
+        let mut best_dot = self.points[0].dot(d);
+        for (i, p) in self.points.iter().enumerate().skip(1){
+            let dot = p.dot(d);
+            if dot > best_dot{
+                best = i;
+                best_dot = dot;
+            }
+        }
+        best
+    }
+}
+
+#[derive(Clone, Copy)]
+struct SimplexVertex{
+    wa: Vec2,
+    wb: Vec2,
+    //wa - wb, point of the minkowski difference
+    w: Vec2,
+    //barycentric weight
+    a: f32,
+    index_a: usize,
+    index_b: usize,
+}
+
+impl SimplexVertex{
+    fn new(proxy_a: &ConvexProxy, index_a: usize, proxy_b: &ConvexProxy, index_b: usize) -> Self{
+        let wa = proxy_a.points[index_a];
+        let wb = proxy_b.points[index_b];
+        Self { wa, wb, w: wa - wb, a: 1., index_a, index_b }
+    }
+}
+
+//reduces the segment to the part closest to the origin
+fn solve2(s: &mut Vec<SimplexVertex>){
+    let (w1, w2) = (s[0].w, s[1].w);
+    let e12 = w2 - w1;
+
+    let d12_2 = -w1.dot(e12);
+    if d12_2 <= 0.{
+        s[0].a = 1.;
+        s.truncate(1);
+        return;
+    }
+    let d12_1 = w2.dot(e12);
+    if d12_1 <= 0.{
+        s[1].a = 1.;
+        s.remove(0);
+        return;
+    }
+    let inv = 1. / (d12_1 + d12_2);
+    s[0].a = d12_1 * inv;
+    s[1].a = d12_2 * inv;
+}
+
+//voronoi regions of the triangle: vertices, edges or the inside
+fn solve3(s: &mut Vec<SimplexVertex>){
+    let (w1, w2, w3) = (s[0].w, s[1].w, s[2].w);
+
+    let e12 = w2 - w1;
+    let d12_1 = w2.dot(e12);
+    let d12_2 = -w1.dot(e12);
+
+    let e13 = w3 - w1;
+    let d13_1 = w3.dot(e13);
+    let d13_2 = -w1.dot(e13);
+
+    let e23 = w3 - w2;
+    let d23_1 = w3.dot(e23);
+    let d23_2 = -w2.dot(e23);
+
+    let n123 = cross(e12, e13);
+    let d123_1 = n123 * cross(w2, w3);
+    let d123_2 = n123 * cross(w3, w1);
+    let d123_3 = n123 * cross(w1, w2);
+
+    let keep = |s: &mut Vec<SimplexVertex>, indices: &[usize], weights: &[f32]|{
+        let kept : Vec<SimplexVertex> = indices.iter().zip(weights).map(|(&i, &a)| SimplexVertex { a, ..s[i] }).collect();
+        *s = kept;
+    };
+
+    if d12_2 <= 0. && d13_2 <= 0.{
+        keep(s, &[0], &[1.]);
+    }
+    else if d12_1 > 0. && d12_2 > 0. && d123_3 <= 0.{
+        let inv = 1. / (d12_1 + d12_2);
+        keep(s, &[0, 1], &[d12_1 * inv, d12_2 * inv]);
+    }
+    else if d13_1 > 0. && d13_2 > 0. && d123_2 <= 0.{
+        let inv = 1. / (d13_1 + d13_2);
+        keep(s, &[0, 2], &[d13_1 * inv, d13_2 * inv]);
+    }
+    else if d12_1 <= 0. && d23_2 <= 0.{
+        keep(s, &[1], &[1.]);
+    }
+    else if d13_1 <= 0. && d23_1 <= 0.{
+        keep(s, &[2], &[1.]);
+    }
+    else if d23_1 > 0. && d23_2 > 0. && d123_1 <= 0.{
+        let inv = 1. / (d23_1 + d23_2);
+        keep(s, &[1, 2], &[d23_1 * inv, d23_2 * inv]);
+    }
+    else{
+        let sum = d123_1 + d123_2 + d123_3;
+        if sum.abs() <= EPSILON * EPSILON{
+            //collinear points, the origin lies on the line through them
+            s.truncate(2);
+            solve2(s);
+            return;
+        }
+        let inv = 1. / sum;
+        s[0].a = d123_1 * inv;
+        s[1].a = d123_2 * inv;
+        s[2].a = d123_3 * inv;
+    }
+}
+
+//direction from the simplex towards the origin
+fn search_direction(s: &[SimplexVertex]) -> Vec2{
+    match s.len(){
+        1 => -s[0].w,
+        _ => {
+            let e12 = s[1].w - s[0].w;
+            if cross(e12, -s[0].w) > 0. {Vec2::new(-e12.y, e12.x)} else {Vec2::new(e12.y, -e12.x)}
+        },
+    }
+}
+
+//closest points of both shapes without their radius
+fn witness_points(s: &[SimplexVertex]) -> (Vec2, Vec2){
+    s.iter().fold((Vec2::new(0., 0.), Vec2::new(0., 0.)), |(pa, pb), v| (pa + v.wa * v.a, pb + v.wb * v.a))
+}
+
+//simplex closest to the origin and the number of iterations
+//None for shapes without points
+fn gjk(proxy_a: &ConvexProxy, proxy_b: &ConvexProxy) -> Option<(Vec<SimplexVertex>, usize)>{
+    if proxy_a.points.is_empty() || proxy_b.points.is_empty(){
+        return None;
+    }
+
+    let mut simplex = vec![SimplexVertex::new(proxy_a, 0, proxy_b, 0)];
+    let mut iterations = 0;
+
+    loop{
+        //support points of the last simplex, the same points again means no progress
+        let saved : Vec<(usize, usize)> = simplex.iter().map(|v| (v.index_a, v.index_b)).collect();
+
+        match simplex.len(){
+            2 => solve2(&mut simplex),
+            3 => solve3(&mut simplex),
+            _ => {},
+        }
+        //origin inside of the triangle, the cores overlap
+        if simplex.len() == 3 || iterations >= GJK_MAX_ITERATIONS{
+            break;
+        }
+
+        let d = search_direction(&simplex);
+        //origin on the simplex, the cores touch
+        if d.magnitude2() < EPSILON * EPSILON{
+            break;
+        }
+
+        let index_a = proxy_a.support(d);
+        let index_b = proxy_b.support(-d);
+        iterations += 1;
+        if saved.contains(&(index_a, index_b)){
+            break;
+        }
+        simplex.push(SimplexVertex::new(proxy_a, index_a, proxy_b, index_b));
+    }
+    COUNTERS.with(|c| {
+        let (runs, total) = c.get();
+        c.set((runs + 1, total + iterations));
+    });
+    Some((simplex, iterations))
+}
+
+//distance between the convex hulls of the points, radii are ignored
+pub fn core_distance(proxy_a: &ConvexProxy, proxy_b: &ConvexProxy) -> Option<f32>{
+    let (simplex, _) = gjk(proxy_a, proxy_b)?;
+    if simplex.len() == 3{
+        return Some(0.);
+    }
+    let (point_a, point_b) = witness_points(&simplex);
+    Some((point_b - point_a).magnitude())
+}
+
+//None for shapes without points
+pub fn distance(proxy_a: &ConvexProxy, proxy_b: &ConvexProxy) -> Option<DistanceOutput>{
+    let (simplex, iterations) = gjk(proxy_a, proxy_b)?;
+
+    let (mut point_a, mut point_b) = witness_points(&simplex);
+    let mut dist = if simplex.len() == 3 {0.} else {(point_b - point_a).magnitude()};
+
+    let radii = proxy_a.radius + proxy_b.radius;
+    if dist > radii && dist > EPSILON{
+        let n = (point_b - point_a) / dist;
+        point_a += n * proxy_a.radius;
+        point_b -= n * proxy_b.radius;
+        dist -= radii;
+    }
+    else{
+        let p = (point_a + point_b) * 0.5;
+        point_a = p;
+        point_b = p;
+        dist = 0.;
+    }
+
+    Some(DistanceOutput { distance: dist, point_a, point_b, iterations })
+}
+
+//distance between two collision shapes, edge chains use their nearest segment
+pub fn shape_distance(a: &CollisionType, b: &CollisionType) -> Option<DistanceOutput>{
+    let proxies_b = ConvexProxy::from_col_type(b);
+    ConvexProxy::from_col_type(a).iter().flat_map(
+        |pa| proxies_b.iter().filter_map(move |pb| distance(pa, pb))
+    ).min_by(|d1, d2| d1.distance.total_cmp(&d2.distance))
+}
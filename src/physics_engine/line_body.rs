@@ -1,49 +1,210 @@
 //line body
 //
+//static polyline of segments for level terrain
+//with one_sided set only the left side of the drawing direction is solid,
+//so terrain drawn from left to right collides from above
+//with one_way set it is a platform bodies jump through from below, see contact_filter
+//neighbouring points act as ghost vertices,
+//bodies sliding over a joint between two segments do not get caught on it
 
-struct Line{}
-
-impl Line{
-}
-
-
-pub fn get_lines_intersection_point(p1: [f32; 2], p2: [f32; 2], p3: [f32; 2], p4: [f32; 2], ) -> Intersects{
-        // f(x) = y = m*x + b
-        // b = y - m*x
-        // m = (y - b)/x
-        // y = m*x + (y - m)
-        // m = (y2 - y1) / (x2 - x1)
-
-        let mut m1 = (p2[1] - p1[1]) / (p2[0] - p1[0]);
-        m1 = if m1.is_normal(){m1}else{0.};
-        let b1 = p1[1] - m1 * p1[0];
-        let mut m2 = (p4[1] - p3[1]) / (p4[0] - p3[0]);
-        m2 = if m2.is_normal(){m2}else{0.};
-        let b2 = p3[1] - m2 * p3[0];
-        //calculate intersection of the two lines
-        let mut corner_x = (b2 - b1) / (m1 - m2);
-        corner_x = if corner_x.is_normal(){corner_x}else{0.};
-        let corner_y = m1*corner_x + b1;
-        if (
-            (p1[0] < p2[0] && (corner_x <= p1[0] && p2[0] <= corner_x))
-            || (p1[0] > p2[0] && (corner_x >= p1[0] && p2[0] >= corner_x))
-           &&
-           (p1[1] < p2[1] && (corner_y <= p1[1] && p2[1] <= corner_y))
-          || (p1[1] > p1[1] && (corner_y >= p1[1] && p2[1] >= corner_y))
-          )
-            ||
-            (b1 != b2 && m1 == m2)
-        {
-                return Intersects::None;
-        }
-        else if b1 == b2 && m1 ==  m2{
-            return Intersects::Infinite;
-        }
-        Intersects::Single(corner_x, corner_y)
-}
-
-pub enum Intersects{
-    Single(f32, f32),
-    Infinite,
-    None,
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::{Vec2, Mat2, Radians};
+
+use super::physic_obj_traits::*;
+
+pub struct EdgeChain{
+    pub pos: Vec2,
+    //relative to pos
+    pub points: Vec<Vec2>,
+    pub one_sided: bool,
+    //direction bodies land on the platform from, relative to the chain, None for solid terrain
+    pub one_way: Option<Vec2>,
+    force: Vec2,
+    vel: Vec2,
+    angle: Radians,
+    id: usize,
+    nodes: Vec<usize>,
+}
+
+//segment from v1 to v2 with its neighbouring points
+pub struct EdgeSegment{
+    pub v0: Option<Vec2>,
+    pub v1: Vec2,
+    pub v2: Vec2,
+    pub v3: Option<Vec2>,
+}
+
+impl EdgeChain{
+    pub fn new(x: f32, y: f32, points: Vec<Vec2>) -> Self{
+        Self {
+            pos: Vec2::new(x, y),
+            points,
+            one_sided: true,
+            one_way: None,
+            force: Vec2::new(0., 0.),
+            vel: Vec2::new(0., 0.),
+            angle: cgmath::Rad(0.),
+            id: 0,
+            nodes: vec![],
+        }
+    }
+
+    //same point lists as used by draw_line::LineMesh
+    pub fn from_coords(points: Vec<[f32; 2]>) -> Self{
+        Self::new(0., 0., points.iter().map(|p| Vec2::new(p[0], p[1])).collect())
+    }
+
+    pub fn as_f32_arr_points(&self) -> Vec<[f32; 2]>{
+        (0..self.points.len()).map(|i| self.transformed_point(i).into()).collect()
+    }
+
+    pub fn transformed_point(&self, index: usize) -> Vec2{
+        Mat2::from_angle(self.angle) * self.points[index] + self.pos
+    }
+
+    pub fn segment_count(&self) -> usize{
+        self.points.len().saturating_sub(1)
+    }
+
+    pub fn segment(&self, index: usize) -> EdgeSegment{
+        EdgeSegment {
+            v0: if index > 0 {Some(self.transformed_point(index-1))} else {None},
+            v1: self.transformed_point(index),
+            v2: self.transformed_point(index+1),
+            v3: if index+2 < self.points.len() {Some(self.transformed_point(index+2))} else {None},
+        }
+    }
+}
+
+impl EdgeSegment{
+    //solid side of a one sided edge
+    pub fn normal(&self) -> Vec2{
+        super::geometry::segment_normal(self.v1, self.v2)
+    }
+
+    //a corner is convex, if the terrain bends away from the solid side
+    pub fn convex_at_v1(&self) -> bool{
+        match self.v0 {
+            Some(v0) => super::geometry::cross(self.v1 - v0, self.v2 - self.v1) < 0.,
+            None => true,
+        }
+    }
+
+    pub fn convex_at_v2(&self) -> bool{
+        match self.v3 {
+            Some(v3) => super::geometry::cross(self.v2 - self.v1, v3 - self.v2) < 0.,
+            None => true,
+        }
+    }
+
+    //normals a body may be pushed along at this segment
+    //face normal or anything between the normals of a convex corner
+    pub fn allows_normal(&self, push: Vec2) -> bool{
+        let n = self.normal();
+        if push.dot(n) >= 1. - 0.0001{
+            return true;
+        }
+        if let Some(v0) = self.v0{
+            if self.convex_at_v1() && in_cone(push, super::geometry::segment_normal(v0, self.v1), n){
+                return true;
+            }
+        }
+        else if push.dot(n) > 0.{
+            return true;
+        }
+        if let Some(v3) = self.v3{
+            if self.convex_at_v2() && in_cone(push, n, super::geometry::segment_normal(self.v2, v3)){
+                return true;
+            }
+        }
+        else if push.dot(n) > 0.{
+            return true;
+        }
+        false
+    }
+}
+
+//true, if d lies between the directions a and b (counter clockwise from a to b)
+fn in_cone(d: Vec2, a: Vec2, b: Vec2) -> bool{
+    let cross = super::geometry::cross;
+    if cross(a, b) >= 0.{
+        cross(a, d) >= 0. && cross(d, b) >= 0.
+    }
+    else{
+        cross(a, d) <= 0. && cross(d, b) <= 0.
+    }
+}
+
+impl PhysicsObject for EdgeChain{
+    fn body_type(&self)-> BodyType {
+        BodyType::Static
+    }
+    fn one_way(&self)-> Option<Vec2> {
+        self.one_way.map(|d| Mat2::from_angle(self.angle) * d)
+    }
+}
+
+//edge chains are static, they have no mass of their own
+impl ForceObject for EdgeChain{
+    fn get_mass(&self) -> f32 {
+        0.
+    }
+    fn get_force(&self) -> &Vec2 {
+        &self.force
+    }
+    fn get_force_mut(&mut self) -> &mut Vec2 {
+        &mut self.force
+    }
+    fn get_vel(&self) -> &Vec2 {
+        &self.vel
+    }
+    fn get_vel_mut(&mut self) -> &mut Vec2 {
+        &mut self.vel
+    }
+    fn get_torque(&self) -> f32 {
+        0.
+    }
+    fn set_torque(&mut self, _torque: f32) {}
+    fn get_inertia(&self) -> f32 {
+        0.
+    }
+    fn set_inertia(&mut self, _inertia: f32) {}
+    fn get_angular_accel(&self) -> Radians {
+        cgmath::Rad(0.)
+    }
+    fn set_angular_accel(&mut self, _angle : Radians) {}
+}
+
+impl TransposeObject for EdgeChain{
+    fn get_pos(&self) -> &cgmath::Vector2<f32> {
+        &self.pos
+    }
+    fn get_pos_mut(&mut self) -> &mut cgmath::Vector2<f32> {
+        &mut self.pos
+    }
+    fn get_angle(&self) -> Radians {
+        self.angle
+    }
+    fn set_angle(&mut self, angle: Radians) {
+        self.angle = angle;
+    }
+}
+
+impl NodeObject for EdgeChain{
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    fn get_connections(&self) -> &[usize] {
+        &self.nodes[..]
+    }
+}
+
+impl CollisionObject for EdgeChain{
+    fn get_col_type(&self) -> CollisionType {
+        CollisionType::Line(self)
+    }
 }
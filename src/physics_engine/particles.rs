@@ -0,0 +1,295 @@
+//particles
+//
+//lightweight points for sparks, dust and debris
+//particles only feel the gravity fields and never push bodies,
+//with collide set they bounce off the static shapes of the World
+//the static shapes are gathered once per step with their bounding boxes,
+//a particle only tests the shapes whose box it moves through
+//fast particles move in substeps no longer than their size and
+//stop in front of the terrain edges a longer substep would cross,
+//so they do not pass through thin terrain
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::{physic_obj_traits::CollisionType, geometry::{Aabb, Intersects, segments_intersection},
+    line_body::EdgeChain, chain_body::capsule_body_contact};
+
+//substeps of the fastest particles
+pub const MAX_SUBSTEPS : usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Particle{
+    pub pos: Vec2,
+    //per step, as used for the bodies
+    pub vel: Vec2,
+    //steps left until the particle disappears
+    pub life: f32,
+    pub lifetime: f32,
+    //the particle fades from color to end_color and from size to end_size
+    //the size is the radius it is drawn and collides with
+    pub color: [f32; 4],
+    pub end_color: [f32; 4],
+    pub size: f32,
+    pub end_size: f32,
+}
+
+impl Particle{
+    //0.0 when spawned, 1.0 at the end of its life
+    pub fn age(&self) -> f32{
+        if self.lifetime <= 0. {1.} else {(1. - self.life / self.lifetime).clamp(0., 1.)}
+    }
+
+    pub fn current_color(&self) -> [f32; 4]{
+        let t = self.age();
+        std::array::from_fn(|i| self.color[i] + (self.end_color[i] - self.color[i]) * t)
+    }
+
+    pub fn current_size(&self) -> f32{
+        self.size + (self.end_size - self.size) * self.age()
+    }
+}
+
+pub struct Emitter{
+    pub pos: Vec2,
+    //angle of the mean direction in rad, 0.0 points along x
+    pub direction: f32,
+    //full opening angle of the cone the particles leave in
+    pub spread: f32,
+    //particles per step, rates below 1 spawn a particle every few steps
+    pub rate: f32,
+    //ranges the values of every particle are picked from
+    pub speed: (f32, f32),
+    pub lifetime: (f32, f32),
+    pub color: [f32; 4],
+    pub end_color: [f32; 4],
+    pub size: f32,
+    pub end_size: f32,
+    pub enabled: bool,
+    //part of a particle not spawned yet
+    pending: f32,
+}
+
+impl Emitter{
+    pub fn new(pos: Vec2, direction: f32, spread: f32, rate: f32) -> Self{
+        Self {
+            pos,
+            direction,
+            spread,
+            rate,
+            speed: (0.005, 0.01),
+            lifetime: (60., 120.),
+            color: [1., 1., 1., 1.],
+            end_color: [1., 1., 1., 1.],
+            size: 0.005,
+            end_size: 0.005,
+            enabled: true,
+            pending: 0.,
+        }
+    }
+
+    fn spawn(&self, rng: &mut Rng) -> Particle{
+        let angle = self.direction + (rng.next_f32() - 0.5) * self.spread;
+        let speed = rng.range(self.speed);
+        let lifetime = rng.range(self.lifetime);
+        Particle {
+            pos: self.pos,
+            vel: Vec2::new(angle.cos(), angle.sin()) * speed,
+            life: lifetime,
+            lifetime,
+            color: self.color,
+            end_color: self.end_color,
+            size: self.size,
+            end_size: self.end_size,
+        }
+    }
+}
+
+//static shape with its bounding box, None boxes are always tested
+pub struct Obstacle<'a>{
+    pub aabb: Option<Aabb>,
+    pub shape: CollisionType<'a>,
+}
+
+impl<'a> Obstacle<'a>{
+    pub fn new(shape: CollisionType<'a>) -> Self{
+        Self { aabb: shape.aabb(), shape }
+    }
+}
+
+pub struct ParticleSystem{
+    pub particles: Vec<Particle>,
+    pub emitters: Vec<Emitter>,
+    //emitters stop spawning while the system is full
+    pub max_particles: usize,
+    pub gravity_scale: f32,
+    //share of the velocity that is kept each step
+    pub damping: f32,
+    pub collide: bool,
+    //share of the velocity along the normal kept after a bounce
+    pub restitution: f32,
+    //share of the sliding velocity removed on contact
+    pub friction: f32,
+    rng: Rng,
+}
+
+impl ParticleSystem{
+    pub fn new(max_particles: usize) -> Self{
+        Self {
+            particles: Vec::with_capacity(max_particles),
+            emitters: vec![],
+            max_particles,
+            gravity_scale: 1.,
+            damping: 0.99,
+            collide: true,
+            restitution: 0.4,
+            friction: 0.2,
+            rng: Rng::new(0x2545_f491),
+        }
+    }
+
+    //same seed, same particles
+    pub fn with_seed(mut self, seed: u32) -> Self{
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    //returns index of emitter in emitters
+    pub fn add_emitter(&mut self, emitter: Emitter) -> usize{
+        self.emitters.push(emitter);
+        self.emitters.len()-1
+    }
+
+    //spawns count particles at once, e.g. debris of an explosion
+    pub fn burst(&mut self, emitter: usize, count: usize){
+        let count = count.min(self.max_particles.saturating_sub(self.particles.len()));
+        for _ in 0..count{
+            let particle = self.emitters[emitter].spawn(&mut self.rng);
+            self.particles.push(particle);
+        }
+    }
+
+    pub fn emit(&mut self){
+        for e in self.emitters.iter_mut(){
+            if !e.enabled{continue;}
+            e.pending += e.rate;
+            while e.pending >= 1.{
+                e.pending -= 1.;
+                if self.particles.len() >= self.max_particles{continue;}
+                self.particles.push(e.spawn(&mut self.rng));
+            }
+        }
+    }
+
+    //gravity gives the acceleration per step at a position, as for the bodies of the World
+    //particles outside of the bounds disappear
+    pub fn simulate(
+        &mut self,
+        gravity: impl Fn(Vec2) -> Vec2,
+        obstacles: &[Obstacle],
+        width_bound: (f32, f32),
+        height_bound: (f32, f32)
+    ){
+        self.emit();
+
+        let mut i = 0;
+        while i < self.particles.len(){
+            let p = &mut self.particles[i];
+            p.life -= 1.;
+            let inside = width_bound.0 <= p.pos.x && p.pos.x <= width_bound.1
+                && height_bound.0 <= p.pos.y && p.pos.y <= height_bound.1;
+            if p.life <= 0. || !inside{
+                self.particles.swap_remove(i);
+                continue;
+            }
+
+            p.vel = p.vel * self.damping + gravity(p.pos) * self.gravity_scale;
+            if self.collide && !obstacles.is_empty(){
+                move_colliding(p, obstacles, self.restitution, self.friction);
+            }
+            else{
+                p.pos += p.vel;
+            }
+            i += 1;
+        }
+    }
+}
+
+fn move_colliding(p: &mut Particle, obstacles: &[Obstacle], restitution: f32, friction: f32){
+    let r = p.current_size().max(0.0001);
+    let from = p.pos;
+    let to = p.pos + p.vel;
+    let swept = Aabb::from_points(&[from, to]);
+    let swept = Aabb { min: swept.min - Vec2::new(r, r), max: swept.max + Vec2::new(r, r) };
+    let near : Vec<&Obstacle> = obstacles.iter().filter(|o| o.aabb.is_none_or(|b| b.overlaps(&swept))).collect();
+    if near.is_empty(){
+        p.pos = to;
+        return;
+    }
+
+    let substeps = ((p.vel.magnitude() / r).ceil() as usize).clamp(1, MAX_SUBSTEPS);
+    for _ in 0..substeps{
+        let step = p.vel / substeps as f32;
+        //substeps of the fastest particles can be longer than their size, they stop in front of the first edge they cross
+        let crossing = near.iter().filter_map(|o| match &o.shape{
+            CollisionType::Line(l) => edge_crossing(p.pos, p.pos + step, l),
+            _ => None,
+        }).min_by(f32::total_cmp);
+        let t = match crossing{
+            Some(t) => (t - 0.5 * r / step.magnitude()).max(0.),
+            None => 1.,
+        };
+        p.pos += step * t;
+        for o in near.iter(){
+            //the normal points from the particle to the shape
+            let Some(contact) = capsule_body_contact(p.pos, p.pos, r, &o.shape, Vec2::new(0., 0.)) else {continue;};
+            let n = -contact.normal;
+            p.pos += n * contact.depth;
+            let vn = p.vel.dot(n);
+            if vn < 0.{
+                let tangent = p.vel - n * vn;
+                p.vel = tangent * (1. - friction) - n * (vn * restitution);
+            }
+        }
+    }
+}
+
+//share of the way from from to to where it first crosses the chain,
+//one sided chains are only crossed from their solid side
+fn edge_crossing(from: Vec2, to: Vec2, chain: &EdgeChain) -> Option<f32>{
+    let d = to - from;
+    (0..chain.segment_count()).filter_map(|i| {
+        let seg = chain.segment(i);
+        if chain.one_sided && (from - seg.v1).dot(seg.normal()) < 0.{
+            return None;
+        }
+        match segments_intersection(from, to, seg.v1, seg.v2){
+            Intersects::Single(x) => Some((x - from).dot(d) / d.magnitude2()),
+            _ => None,
+        }
+    }).min_by(f32::total_cmp)
+}
+
+//xorshift, particles do not need more than that
+struct Rng{
+    state: u32,
+}
+
+impl Rng{
+    fn new(seed: u32) -> Self{
+        Self { state: seed.max(1) }
+    }
+
+    //in [0, 1)
+    fn next_f32(&mut self) -> f32{
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn range(&mut self, (min, max): (f32, f32)) -> f32{
+        min + (max - min) * self.next_f32()
+    }
+}
@@ -1,13 +1,47 @@
 //phyobj traits
 //
 
-use super::{circle_body::Circle, convex_body::Convex2D, physics_world::TempPhyObjData};
+use super::{circle_body::Circle, convex_body::Convex2D, line_body::EdgeChain, rect_body::Rect, capsule_body::Capsule, compound_body::Compound,
+    physics_world::TempPhyObjData, fracture::Breakable};
 
 type Vec2 = cgmath::Vector2<f32>;
 type Radians = cgmath::Rad<f32>;
 
+//static bodies never move
+//kinematic bodies move with the velocity set by the user, neither forces nor collisions change it
+//dynamic bodies are simulated
+//static and kinematic bodies have an infinite mass in collisions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BodyType{
+    Static,
+    Kinematic,
+    #[default]
+    Dynamic,
+}
+
 pub trait PhysicsObject : NodeObject + TransposeObject + ForceObject + CollisionObject{
-    fn is_static(&self)-> bool {false}
+    fn body_type(&self)-> BodyType {BodyType::Dynamic}
+    fn is_static(&self)-> bool {self.body_type() == BodyType::Static}
+    fn is_kinematic(&self)-> bool {self.body_type() == BodyType::Kinematic}
+    fn is_dynamic(&self)-> bool {self.body_type() == BodyType::Dynamic}
+    //zero for bodies that are not dynamic, instead of a huge mass
+    fn get_inv_mass(&self)-> f32 {
+        if self.is_dynamic() {1. / self.get_mass()} else {0.}
+    }
+    fn get_inv_inertia(&self)-> f32 {
+        if self.is_dynamic() {1. / self.get_inertia()} else {0.}
+    }
+    //multiplies the gravity fields of the world for this body
+    fn gravity_scale(&self)-> f32 {1.}
+    //bullets skip the velocity clamp and are swept along their velocity against the other bodies,
+    //so fast bodies do not pass through them, see ccd
+    fn is_bullet(&self)-> bool {false}
+    //recomputes data derived from the pose, called by the world after moving the bodies
+    fn update_cache(&mut self) {}
+    //bodies breaking into pieces on hard impacts
+    fn breakable(&self)-> Option<Breakable> {None}
+    //one way platforms are only hit by bodies coming from the side this world space direction points to
+    fn one_way(&self)-> Option<Vec2> {None}
 }
 
 pub trait TransposeObject{
@@ -40,9 +74,15 @@ pub enum CollisionType<'a>{
     NoCollision,
     Circle(&'a Circle),
     //{x: f32, y: f32, r: f32},
-    Line{x1: f32, y1: f32, x2: f32, y2: f32},
-    Rectangle,
+    Line(&'a EdgeChain),
+    Rectangle(&'a Rect),
+    Capsule(&'a Capsule),
     Convex(&'a Convex2D),
+    //collides with each of its convex parts
+    Compound(&'a Compound),
+    //user defined shape, contacts come from functions registered
+    //in the CollisionDispatcher for ShapeKind::Custom(kind)
+    Custom{kind: u32, shape: &'a dyn std::any::Any},
 }
 
 pub trait CollisionObject{
@@ -51,9 +91,33 @@ pub trait CollisionObject{
     }
 }
 
+//touching point of two colliding objects
+//normal points from object to other with unit length
+//depth is the overlap of both objects along the normal
+pub struct Contact{
+    pub normal: Vec2,
+    pub depth: f32,
+    pub point: Vec2,
+}
+
+impl Contact{
+    //same contact seen from the other object
+    pub fn flipped(self) -> Self{
+        Self { normal: -self.normal, depth: self.depth, point: self.point }
+    }
+}
+
+//relations either provide a contact or the positions to reset both objects to
 pub trait CollisionRelation<T, O>{
-    fn check_col(object: &T, other: &O) -> bool;
-    fn pos_reset_to(object: &T, other: &O) -> Option<(TempPhyObjData, TempPhyObjData)>;
+    fn check_col(object: &T, other: &O) -> bool{
+        Self::get_contact(object, other).is_some()
+    }
+    fn pos_reset_to(_object: &T, _other: &O) -> Option<(TempPhyObjData, TempPhyObjData)>{
+        None
+    }
+    fn get_contact(_object: &T, _other: &O) -> Option<Contact>{
+        None
+    }
 }
 
 pub trait NodeObject{
@@ -2,17 +2,24 @@
 //
 //
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+
 use cgmath::{InnerSpace, num_traits::clamp};
 
-use crate::{gen_vec::GenVec, primitives_2d::utils::Radians};
+use crate::{gen_vec::{GenVec, Key}, primitives_2d::utils::{Radians, Mat2}};
 
-use super::{circle_body::Circle, convex_body::Convex2D};
+use super::{circle_body::Circle, chain_body::Chain, soft_body::SoftBody, collision_dispatch::CollisionDispatcher,
+    ccd::{time_of_impact, cast_shape, CastHit, CCD_TARGET}, gjk::ConvexProxy,
+    sleeping::{SleepState, Islands, TIME_TO_SLEEP}, force_field::ForceField, fluid::Fluid,
+    particles::{ParticleSystem, Obstacle}, fracture::{self, body_outline}, geometry::Aabb,
+    contact_filter::{PreSolveContact, PreSolveFn, one_way_pass, passes_one_way}, debug_draw::{self, DebugDraw},
+    stats::{StepStats, PhaseTimer, phase_span}, gjk};
 use crate::physics_engine::physic_obj_traits::*;
 
 //TODO: separating shape information from Physicsbody
 
 type Vec2 = cgmath::Vector2<f32>;
-type PhyObjPointer = Box<dyn PhysicsObject>;
+pub type PhyObjPointer = Box<dyn PhysicsObject>;
 
 
 pub struct TempPhyObjData{
@@ -21,41 +28,320 @@ pub struct TempPhyObjData{
     pub torque: f32,
 }
 
-static GRAVITY_FACTOR : f32 = 0.0009;
+//acceleration per step of a gravity direction with unit length
+pub const GRAVITY_FACTOR : f32 = 0.0009;
+//share of the gravity it has to change by to wake sleeping bodies
+pub const GRAVITY_WAKE_TOLERANCE : f32 = 0.01;
+//share of the velocity along the contact normal kept after a collision
+static RESTITUTION : f32 = 0.4;
+//impacts a bullet may resolve within one step
+static CCD_MAX_SUBSTEPS : usize = 4;
 
 pub struct World{
-    pub global_gravity_dir: cgmath::Vector2<f32>,
     obj_count : usize,
     width_bound: (f32, f32),
     height_bound: (f32, f32),
     pub physics_objects: GenVec<Box<dyn PhysicsObject>>,//Vec<Box<dyn PhysicsObject>>,
     pub static_objects: GenVec<Box<dyn PhysicsObject>>,
+    pub chains: Vec<Chain>,
+    pub soft_bodies: Vec<SoftBody>,
+    pub particle_systems: Vec<ParticleSystem>,
+    //act besides the gravity of the world
+    //sleeping bodies only notice changed fields after wake_all
+    pub force_fields: Vec<ForceField>,
+    //acceleration per step everywhere, see set_gravity_dir
+    gravity: Vec2,
+    //gravity when the bodies were woken for the last change of it
+    woken_gravity: Vec2,
+    pub fluids: Vec<Fluid>,
+    //contact functions for every pair of shapes that can collide
+    pub collision_dispatcher: CollisionDispatcher,
+    //resting islands are not simulated until something touches them
+    pub allow_sleeping: bool,
+    //by body id, bodies without a state are awake
+    sleep_states: HashMap<usize, SleepState>,
+    //keys of the bodies in physics_objects by body id
+    keys: HashMap<usize, Key>,
+    //strongest contact of every body during the current step, by body id
+    //ordered, so bodies breaking in the same step get the same ids in every run
+    impacts: BTreeMap<usize, Impact>,
+    //asked for every contact before it is resolved, see contact_filter
+    pre_solve: Option<PreSolveFn>,
+    //(platform id, body id) of bodies passing through one way platforms
+    passing: HashSet<(usize, usize)>,
+    //contacts resolved in the last step, the normal points from the first body to the second one
+    contacts: Vec<Contact>,
+    //measure the time of the phases of a step, see stats
+    pub profiling: bool,
+    stats: StepStats,
+}
+
+#[derive(Clone, Copy)]
+struct Impact{
+    impulse: f32,
+    point: Vec2,
+    normal: Vec2,
+}
+
+fn awake_in(sleep_states: &HashMap<usize, SleepState>, id: usize) -> bool{
+    sleep_states.get(&id).is_none_or(|s| s.awake)
+}
+
+//gravity of the world and of the gravity fields at pos
+fn gravity_at(gravity: Vec2, force_fields: &[ForceField], pos: Vec2) -> Vec2{
+    gravity + force_fields.iter().map(|f| f.gravity_at(pos)).sum::<Vec2>()
 }
 
 impl World{
     pub fn new() -> Self{
-        let global_gravity_dir = Vec2::new(0., -0.01);
         //let Circle { id, nodes, pos, r, m, force, vel }
         let physics_objects = GenVec::new();
         let static_objects = GenVec::new();
         Self{
-            global_gravity_dir,
             obj_count: 0,
             width_bound: (-1., 1.),
             height_bound: (-1., 1.),
             physics_objects,
             static_objects,
+            chains: vec![],
+            soft_bodies: vec![],
+            particle_systems: vec![],
+            force_fields: vec![],
+            gravity: Vec2::new(0., -0.01) * GRAVITY_FACTOR,
+            woken_gravity: Vec2::new(0., -0.01) * GRAVITY_FACTOR,
+            fluids: vec![],
+            collision_dispatcher: CollisionDispatcher::new(),
+            allow_sleeping: true,
+            sleep_states: HashMap::new(),
+            keys: HashMap::new(),
+            impacts: BTreeMap::new(),
+            pre_solve: None,
+            passing: HashSet::new(),
+            contacts: vec![],
+            profiling: cfg!(not(target_arch = "wasm32")),
+            stats: StepStats::default(),
         }
     }
 
     pub fn add_phy_obj<T: PhysicsObject + 'static>(&mut self, mut add: T) -> usize{
         add.set_id(self.obj_count);
-        self.physics_objects.insert(Box::new(add));
+        let key = self.physics_objects.insert(Box::new(add));
+        self.keys.insert(self.obj_count, key);
         self.obj_count += 1;
         //self.obj_count = self.physics_objects.len();
         self.obj_count-1
     }
 
+    //bodies resting on it wake up, chains attached to it let go
+    pub fn remove_phy_obj(&mut self, id: usize) -> Option<PhyObjPointer>{
+        let key = self.keys.remove(&id)?;
+        self.wake(id);
+        self.sleep_states.remove(&id);
+        self.impacts.remove(&id);
+        for chain in self.chains.iter_mut(){
+            chain.detach_body(id);
+        }
+        self.physics_objects.remove(&key)
+    }
+
+    pub fn get_phy_obj(&self, id: usize) -> Option<&PhyObjPointer>{
+        self.physics_objects.get(self.keys.get(&id)?)
+    }
+
+    //the body may be changed from outside, so it is woken up
+    pub fn get_phy_obj_mut(&mut self, id: usize) -> Option<&mut PhyObjPointer>{
+        self.wake(id);
+        self.physics_objects.get_mut(self.keys.get(&id)?)
+    }
+
+    //false for static and unknown bodies, kinematic bodies never sleep
+    pub fn is_awake(&self, id: usize) -> bool{
+        self.get_phy_obj(id).is_some_and(|p| !p.is_static()) && awake_in(&self.sleep_states, id)
+    }
+
+    //scripted motion of kinematic bodies, e.g. moving platforms
+    //the velocities are per step and kept until they are set again
+    pub fn set_kinematic_velocity(&mut self, id: usize, vel: Vec2, ang_vel: f32){
+        if let Some(p) = self.get_phy_obj_mut(id){
+            if !p.is_kinematic(){return;}
+            *p.get_vel_mut() = vel;
+            p.set_angular_accel(cgmath::Rad(ang_vel));
+        }
+    }
+
+    //wakes the body and all bodies that fell asleep with it
+    pub fn wake(&mut self, id: usize){
+        let Some(state) = self.sleep_states.get(&id).copied() else {return;};
+        if state.awake{return;}
+        for s in self.sleep_states.values_mut(){
+            if !s.awake && s.island == state.island{
+                s.wake();
+            }
+        }
+    }
+
+    //acts during the next step besides the gravity
+    pub fn apply_force(&mut self, id: usize, force: Vec2){
+        if let Some(p) = self.get_phy_obj_mut(id){
+            *p.get_force_mut() += force;
+        }
+    }
+
+    //changes the velocity at once by impulse / mass
+    pub fn apply_impulse(&mut self, id: usize, impulse: Vec2){
+        if let Some(p) = self.get_phy_obj_mut(id){
+            let inv_mass = p.get_inv_mass();
+            *p.get_vel_mut() += impulse * inv_mass;
+        }
+    }
+
+    //the acceleration of the gravity is dir * GRAVITY_FACTOR
+    //sleeping bodies are only woken once the gravity moved away from the one of the last wake
+    //by more than GRAVITY_WAKE_TOLERANCE of its strength, small changes every frame add up until then
+    pub fn set_gravity_dir(&mut self, dir: Vec2){
+        self.gravity = dir * GRAVITY_FACTOR;
+        let change = (self.gravity - self.woken_gravity).magnitude();
+        if change > GRAVITY_WAKE_TOLERANCE * self.gravity.magnitude().max(self.woken_gravity.magnitude()){
+            self.woken_gravity = self.gravity;
+            self.wake_all();
+        }
+    }
+
+    pub fn gravity_dir(&self) -> Vec2{
+        self.gravity / GRAVITY_FACTOR
+    }
+
+    //returns index of field in force_fields
+    pub fn add_force_field(&mut self, field: ForceField) -> usize{
+        self.force_fields.push(field);
+        self.wake_all();
+        self.force_fields.len()-1
+    }
+
+    //returns index of fluid in fluids
+    pub fn add_fluid(&mut self, fluid: Fluid) -> usize{
+        self.fluids.push(fluid);
+        self.wake_all();
+        self.fluids.len()-1
+    }
+
+    pub fn wake_all(&mut self){
+        self.sleep_states.values_mut().for_each(SleepState::wake);
+    }
+
+    //returns index of chain in chains
+    pub fn add_chain(&mut self, chain: Chain) -> usize{
+        self.chains.push(chain);
+        self.chains.len()-1
+    }
+
+    //returns index of soft body in soft_bodies
+    pub fn add_soft_body(&mut self, soft_body: SoftBody) -> usize{
+        self.soft_bodies.push(soft_body);
+        self.soft_bodies.len()-1
+    }
+
+    //returns index of particle system in particle_systems
+    pub fn add_particle_system(&mut self, particle_system: ParticleSystem) -> usize{
+        self.particle_systems.push(particle_system);
+        self.particle_systems.len()-1
+    }
+
+    //contacts the hook returns false for are not resolved in this step
+    //bullets only follow the one way platforms, not the hook
+    pub fn set_pre_solve(&mut self, hook: impl FnMut(&PreSolveContact) -> bool + 'static){
+        self.pre_solve = Some(Box::new(hook));
+    }
+
+    pub fn clear_pre_solve(&mut self){
+        self.pre_solve = None;
+    }
+
+    //counters and timings of the last step
+    pub fn stats(&self) -> &StepStats{
+        &self.stats
+    }
+
+    pub fn contacts(&self) -> &[Contact]{
+        &self.contacts
+    }
+
+    //emits the bodies, chains and soft bodies and the contacts of the last step
+    pub fn debug_draw(&self, draw: &mut dyn DebugDraw){
+        for p in self.physics_objects.iter().chain(self.static_objects.iter()){
+            debug_draw::draw_body(draw, p.as_ref(), self.is_awake(p.get_id()));
+        }
+        for chain in self.chains.iter(){
+            debug_draw::draw_chain(draw, chain, |id, local_anchor| self.get_phy_obj(id).map(
+                |b| *b.get_pos() + Mat2::from_angle(b.get_angle()) * local_anchor
+            ));
+        }
+        for soft_body in self.soft_bodies.iter(){
+            debug_draw::draw_soft_body(draw, soft_body);
+        }
+        if draw.flags().contacts{
+            for contact in self.contacts.iter(){
+                debug_draw::draw_contact(draw, contact);
+            }
+        }
+    }
+
+    //first body hit by the shape moved by translation, with its id
+    //the shape stops CCD_TARGET in front of it, bodies in ignore are passed through
+    pub fn cast_shape(&self, shape: &ConvexProxy, translation: Vec2, ignore: &[usize]) -> Option<(usize, CastHit)>{
+        let start = Aabb::from_points(&shape.points);
+        let swept = start.union(&Aabb { min: start.min + translation, max: start.max + translation });
+        let margin = Vec2::new(1., 1.) * (shape.radius + CCD_TARGET);
+        let swept = Aabb { min: swept.min - margin, max: swept.max + margin };
+        self.physics_objects.iter().chain(self.static_objects.iter())
+            .filter(|p| !ignore.contains(&p.get_id()))
+            .filter(|p| !passes_one_way(p.as_ref(), std::slice::from_ref(shape), translation))
+            .filter(|p| p.get_col_type().aabb().is_none_or(|b| b.overlaps(&swept)))
+            .filter_map(|p| cast_shape(shape, translation, &p.get_col_type()).map(|hit| (p.get_id(), hit)))
+            .min_by(|(_, h1), (_, h2)| h1.fraction.total_cmp(&h2.fraction))
+    }
+
+    //cuts the convex bodies and rectangles the segment passes through completely
+    //the halves replace the bodies, returns their ids
+    pub fn slice(&mut self, from: Vec2, to: Vec2) -> Vec<usize>{
+        let cuts : Vec<(usize, Vec<Vec<Vec2>>)> = self.physics_objects.iter().filter_map(|p| {
+            let outline = body_outline(&p.get_col_type())?;
+            let (left, right) = fracture::slice_polygon(&outline, from, to)?;
+            Some((p.get_id(), vec![left, right]))
+        }).collect();
+        cuts.into_iter().flat_map(|(id, polygons)| self.replace_by_pieces(id, &polygons)).collect()
+    }
+
+    //the pieces are added in place of the body, returns their ids
+    fn replace_by_pieces(&mut self, id: usize, polygons: &[Vec<Vec2>]) -> Vec<usize>{
+        let Some(body) = self.get_phy_obj(id) else {return vec![];};
+        let pieces = fracture::pieces_of(body.as_ref(), polygons);
+        if pieces.len() < 2{
+            return vec![];
+        }
+        self.remove_phy_obj(id);
+        pieces.into_iter().map(|piece| self.add_phy_obj(piece)).collect()
+    }
+
+    //breakable bodies hit hard enough during the step break along cracks through the impact
+    fn break_bodies(&mut self){
+        let impacts = std::mem::take(&mut self.impacts);
+        let mut breaking : Vec<(usize, Vec<Vec<Vec2>>)> = vec![];
+        for (id, impact) in impacts{
+            let Some(body) = self.get_phy_obj(id) else {continue;};
+            let Some(breakable) = body.breakable() else {continue;};
+            if impact.impulse < breakable.threshold{continue;}
+            let Some(outline) = body_outline(&body.get_col_type()) else {continue;};
+            //cracks start inside the body, a crack along the surface would not cut anything
+            let origin = impact.point + (body.get_pos() - impact.point) * 0.5;
+            breaking.push((id, fracture::crack_polygon(&outline, origin, impact.normal, breakable.cracks)));
+        }
+        for (id, polygons) in breaking{
+            let pieces = self.replace_by_pieces(id, &polygons);
+            log::debug!(target: "physics::fracture", "body {id} broke into {:?}", pieces);
+        }
+    }
+
     pub fn add_circles(&mut self){
         for i in 1..2{
             let add = Circle::new(0, 0.2*i as f32 -1.0, 0.8, 0.07, 6.);
@@ -77,11 +363,35 @@ impl World{
         }
     }
     pub fn simulation_step(&mut self){
+        phase_span!("step");
+        let mut timer = PhaseTimer::new(self.profiling);
+        self.stats = StepStats::default();
+        gjk::take_counters();
+
+        {
+            phase_span!("integration");
+            self.simulate_force();
+            self.simulate_velocity();
+            self.simulate_movement();
+            self.update_caches();
+        }
+        self.stats.integration = timer.lap();
+        {
+            phase_span!("deformables");
+            self.simulate_chains();
+            self.simulate_particles();
+        }
+        self.stats.deformables = timer.lap();
+        self.collision_detection(&mut timer);
+        //forces applied between two steps act in the next one
         self.set_forces_to_zero();
-        self.simulate_force();
-        self.simulate_velocity();
-        self.simulate_movement();
-        self.collision_detection();
+
+        self.stats.bodies_awake = self.physics_objects.iter().filter(
+            |p| p.is_dynamic() && awake_in(&self.sleep_states, p.get_id())
+        ).count();
+        (self.stats.gjk_calls, self.stats.gjk_iterations) = gjk::take_counters();
+        self.stats.step = timer.total();
+        log::debug!(target: "physics::stats", "{:?}", self.stats);
     }
 
     fn set_forces_to_zero(&mut self){
@@ -93,18 +403,32 @@ impl World{
 
     fn simulate_force(&mut self){
         for p in self.physics_objects.iter_mut(){
-            if p.is_static(){continue;}
-
-            let g_force = p.get_mass()*self.global_gravity_dir*GRAVITY_FACTOR;
-            *p.get_force_mut() += g_force;
+            if !p.is_dynamic() || !awake_in(&self.sleep_states, p.get_id()){continue;}
+
+            let pos = *p.get_pos();
+            let gravity = gravity_at(self.gravity, &self.force_fields, pos) * p.gravity_scale();
+            let field_force = self.force_fields.iter().map(
+                |f| f.force_on(pos, *p.get_vel(), p.get_mass(), gravity)
+            ).sum::<Vec2>();
+            let force = p.get_mass()*gravity + field_force;
+            *p.get_force_mut() += force;
+
+            //buoyancy does not depend on the gravity scale of the body
+            for fluid in self.fluids.iter(){
+                fluid.apply(p.as_mut(), gravity_at(self.gravity, &self.force_fields, pos));
+            }
         }
     }
 
     fn simulate_velocity(&mut self){
         for p in self.physics_objects.iter_mut(){
-            p.get_vel_mut().x = clamp(p.get_vel().x, -0.01, 0.01);
-            p.get_vel_mut().y = clamp(p.get_vel().y, -0.01, 0.01);
-            if p.is_static(){continue;}
+            //kinematic bodies keep the velocity set by the user
+            if !p.is_dynamic() || !awake_in(&self.sleep_states, p.get_id()){continue;}
+            //bullets are protected against tunneling by the ccd instead
+            if !p.is_bullet(){
+                p.get_vel_mut().x = clamp(p.get_vel().x, -0.01, 0.01);
+                p.get_vel_mut().y = clamp(p.get_vel().y, -0.01, 0.01);
+            }
 
             p.set_angular_accel(
                 cgmath::Rad(p.get_torque()/p.get_inertia())
@@ -119,17 +443,117 @@ impl World{
 
     fn simulate_movement(&mut self){
         for p in self.physics_objects.iter_mut(){
-            if p.is_static(){continue;}
+            if p.is_static() || !awake_in(&self.sleep_states, p.get_id()){continue;}
 
             p.set_angle(p.get_angle() + p.get_angular_accel());
-            println!("angle in rad: {}", p.get_angle().0);
-            println!("inertia: {}", p.get_inertia());
 
+            if p.is_bullet(){continue;}
             *p.get_pos_mut() = *p.get_pos() + *p.get_vel();
         }
+        self.advance_bullets();
+    }
+
+    //bullets move in substeps, each one ends in front of the next body they would hit
+    //there the impact is resolved and the rest of the step continues with the new velocity
+    fn advance_bullets(&mut self){
+        //bodies hit by a bullet wake up
+        let mut woken : Vec<usize> = vec![];
+        let mut pobjs : Vec<&mut PhyObjPointer> = self.physics_objects.iter_mut().collect();
+        for i in 0..pobjs.len(){
+            if !pobjs[i].is_dynamic() || !pobjs[i].is_bullet(){continue;}
+            if !awake_in(&self.sleep_states, pobjs[i].get_id()){continue;}
+
+            let mut remaining = 1.;
+            for _ in 0..CCD_MAX_SUBSTEPS{
+                let translation = *pobjs[i].get_vel() * remaining;
+                let shapes = ConvexProxy::from_col_type(&pobjs[i].get_col_type());
+
+                let mut hit : Option<(usize, f32)> = None;
+                for (j, obj) in pobjs.iter().enumerate(){
+                    if j == i || passes_one_way(obj.as_ref(), &shapes, translation){continue;}
+                    for other in ConvexProxy::from_col_type(&obj.get_col_type()).iter(){
+                        for shape in shapes.iter(){
+                            if let Some(toi) = time_of_impact(shape, other, translation){
+                                if hit.is_none_or(|(_, t)| toi < t){
+                                    hit = Some((j, toi));
+                                }
+                            }
+                        }
+                    }
+                }
+
+                let Some((j, toi)) = hit else {
+                    *pobjs[i].get_pos_mut() += translation;
+                    pobjs[i].update_cache();
+                    break;
+                };
+                *pobjs[i].get_pos_mut() += translation * toi;
+                pobjs[i].update_cache();
+                remaining *= 1. - toi;
+                let Some(impact) = resolve_impact(&self.collision_dispatcher, &mut pobjs, i, j) else {
+                    //without a contact the bullet waits at the impact for the discrete collision
+                    break;
+                };
+                log::trace!(target: "physics::ccd", "bullet {} hit {} after {} of its step, impulse {}",
+                    pobjs[i].get_id(), pobjs[j].get_id(), 1. - remaining, impact.impulse);
+                record_impact(&mut self.impacts, pobjs[i].get_id(), impact);
+                record_impact(&mut self.impacts, pobjs[j].get_id(), impact);
+                woken.push(pobjs[j].get_id());
+            }
+        }
+        for id in woken{
+            self.wake(id);
+        }
     }
 
-    fn collision_detection(&mut self){
+    //bodies only compute their cached shapes again, if they moved
+    fn update_caches(&mut self){
+        for p in self.physics_objects.iter_mut(){
+            p.update_cache();
+        }
+    }
+
+    //chains and soft bodies get the same gravity as the bodies
+    //sleeping bodies pushed by them wake up
+    fn simulate_chains(&mut self){
+        let sleeping : Vec<(usize, Vec2)> = self.physics_objects.iter().filter(
+            |p| !awake_in(&self.sleep_states, p.get_id())
+        ).map(|p| (p.get_id(), *p.get_pos())).collect();
+
+        for chain in self.chains.iter_mut(){
+            //the gravity fields where the chain starts pull on all of it
+            let start = chain.points.first().map_or(Vec2::new(0., 0.), |p| p.pos);
+            chain.simulate_movement(gravity_at(self.gravity, &self.force_fields, start));
+            chain.solve_constraints(&mut self.physics_objects, self.width_bound, self.height_bound);
+        }
+        for soft_body in self.soft_bodies.iter_mut(){
+            let center = soft_body.center();
+            soft_body.simulate_movement(gravity_at(self.gravity, &self.force_fields, center));
+            soft_body.solve_constraints(&mut self.physics_objects, self.width_bound, self.height_bound);
+        }
+
+        for (id, pos) in sleeping{
+            if self.get_phy_obj(id).is_some_and(|p| *p.get_pos() != pos){
+                self.wake(id);
+            }
+        }
+    }
+
+    //particles collide with the static bodies only, they never push anything
+    fn simulate_particles(&mut self){
+        if self.particle_systems.is_empty(){return;}
+        let obstacles : Vec<Obstacle> = self.physics_objects.iter().chain(self.static_objects.iter())
+            .filter(|p| p.is_static())
+            .map(|p| Obstacle::new(p.get_col_type()))
+            .collect();
+        let (world_gravity, force_fields) = (self.gravity, &self.force_fields);
+        let gravity = |pos| gravity_at(world_gravity, force_fields, pos);
+        for particle_system in self.particle_systems.iter_mut(){
+            particle_system.simulate(gravity, &obstacles, self.width_bound, self.height_bound);
+        }
+    }
+
+    fn collision_detection(&mut self, timer: &mut PhaseTimer){
         for p in self.physics_objects.iter_mut(){
             if p.get_pos().y + p.get_vel().y < -0.99 || 0.99 < p.get_pos().y + p.get_vel().y {
                 p.get_force_mut().y *= -0.4;
@@ -144,6 +568,9 @@ impl World{
             }
         }
 
+        //the wall clamp above moved bodies, the caches are refreshed before the contacts read them
+        self.update_caches();
+
         //TODO: create struct to cache all changes to be made during Collisionrestricition
 
         let mut temp_reset_obj_data: Vec<TempPhyObjData> = Vec::with_capacity(self.physics_objects.len());
@@ -157,67 +584,77 @@ impl World{
 
 
         let mut pobjs : Vec<&mut PhyObjPointer> = self.physics_objects.iter_mut().collect();
-        for i in 0..pobjs.len(){//self.physics_objects.len(){
-            for j in i+1..pobjs.len(){//self.physics_objects.len(){
-                if let CollisionType::Circle(p_c) = pobjs[i].get_col_type(){//self.physics_objects[i].get_col_type(){
-                    if let CollisionType::Circle(p_c_other) = pobjs[j].get_col_type(){//self.physics_objects[j].get_col_type(){
-                        if Circle::check_col(p_c, p_c_other){
-                            if let Some((t_circle1, t_circle2)) = Circle::pos_reset_to(p_c, p_c_other){
-                                reset_pos(&mut pobjs, i, j,
-                                          &mut temp_reset_obj_data,
-                                          t_circle1, t_circle2
-                                          );
-                           }
-
 
-                        }
-                    }
-                    else
-                    if let CollisionType::Convex(p_c_other) = pobjs[j].get_col_type(){
-                        if Circle::check_col(p_c, p_c_other){
-                            if let Some((t_circle, t_convex)) = Circle::pos_reset_to(p_c, p_c_other){
-                                reset_pos(&mut pobjs, i, j,
-                                          &mut temp_reset_obj_data,
-                                          t_circle, t_convex
-                                          );
-                           }
-
-                        }
+        //pairs without a moving body are not tested, neither are pairs without a dynamic body
+        let active : Vec<bool> = pobjs.iter().map(
+            |p| (p.is_dynamic() && awake_in(&self.sleep_states, p.get_id())) || (p.is_kinematic() && is_moving(p))
+        ).collect();
+        let dynamic : Vec<bool> = pobjs.iter().map(|p| p.is_dynamic()).collect();
+        let pairs = {
+            phase_span!("broad_phase");
+            let aabbs : Vec<Option<Aabb>> = pobjs.iter().map(|p| p.get_col_type().aabb()).collect();
+            let mut pairs : Vec<(usize, usize)> = vec![];
+            for i in 0..pobjs.len(){
+                for j in i+1..pobjs.len(){
+                    if !active[i] && !active[j]{continue;}
+                    if !dynamic[i] && !dynamic[j]{continue;}
+                    self.stats.pairs_checked += 1;
+                    if let (Some(a), Some(b)) = (&aabbs[i], &aabbs[j]){
+                        if !a.overlaps(b){continue;}
                     }
+                    pairs.push((i, j));
                 }
-
-                else
-                if let CollisionType::Convex(p_c) = pobjs[i].get_col_type(){//self.physics_objects[i].get_col_type(){
-                    if let CollisionType::Circle(p_c_other) = pobjs[j].get_col_type(){//self.physics_objects[j].get_col_type(){
-                        if Circle::check_col(p_c_other, p_c){
-                            if let Some((t_circle, t_convex)) = Circle::pos_reset_to(p_c_other, p_c){
-                                reset_pos(&mut pobjs, i, j,
-                                          &mut temp_reset_obj_data,
-                                          t_convex, t_circle
-                                          );
-                            }
-                        }
-                    }
-                    else
-                    if let CollisionType::Convex(p_c_other) = pobjs[j].get_col_type(){
-                        if Convex2D::check_col(p_c, p_c_other){
-                            if let Some((t_convex1, t_convex2)) = Convex2D::pos_reset_to(p_c, p_c_other){
-                                reset_pos(&mut pobjs, i, j,
-                                          &mut temp_reset_obj_data,
-                                          t_convex1, t_convex2
-                                          );
-                            }
-
-                        }
-                    }
+            }
+            pairs
+        };
+        self.stats.pairs_tested = pairs.len();
+        self.stats.broad_phase = timer.lap();
+
+        //bodies are not changed before the solver, the contacts do not depend on each other
+        let contacts = {
+            phase_span!("narrow_phase");
+            let mut contacts : Vec<(usize, usize, Contact)> = vec![];
+            let mut passing : HashSet<(usize, usize)> = HashSet::new();
+            for (i, j) in pairs{
+                let Some(contact) = self.collision_dispatcher.get_contact(
+                    &pobjs[i].get_col_type(), &pobjs[j].get_col_type()
+                ) else {continue;};
+                self.stats.contacts += 1;
+                let info = PreSolveContact::new(pobjs[i].as_ref(), pobjs[j].as_ref(), contact.normal, contact.depth, contact.point);
+                if let Some(pair) = one_way_pass(&info, &self.passing){
+                    log::trace!(target: "physics::narrowphase", "body {} passes through one way platform {}", pair.1, pair.0);
+                    passing.insert(pair);
+                    self.stats.contacts_disabled += 1;
+                    continue;
                 }
-
-
-
+                if self.pre_solve.as_mut().is_some_and(|hook| !hook(&info)){
+                    log::trace!(target: "physics::narrowphase", "contact of {} and {} disabled by the pre solve hook",
+                        pobjs[i].get_id(), pobjs[j].get_id());
+                    self.stats.contacts_disabled += 1;
+                    continue;
+                }
+                contacts.push((i, j, contact));
             }
+            self.passing = passing;
+            contacts
+        };
+        self.stats.narrow_phase = timer.lap();
+
+        phase_span!("solver");
+        let mut touching : Vec<(usize, usize)> = vec![];
+        self.contacts.clear();
+        for (i, j, contact) in contacts{
+            let impulse = resolve_contact(&mut pobjs, i, j, &mut temp_reset_obj_data, &contact);
+            log::trace!(target: "physics::solver", "contact of {} and {} at {:?}: depth {}, impulse {}",
+                pobjs[i].get_id(), pobjs[j].get_id(), contact.point, contact.depth, impulse);
+            let impact = Impact { impulse, point: contact.point, normal: contact.normal };
+            record_impact(&mut self.impacts, pobjs[i].get_id(), impact);
+            record_impact(&mut self.impacts, pobjs[j].get_id(), impact);
+            touching.push((i, j));
+            self.contacts.push(contact);
         }
         for i in 0..pobjs.len(){
-            if pobjs[i].is_static(){continue;}
+            if !pobjs[i].is_dynamic(){continue;}
             *pobjs[i].get_pos_mut() = temp_reset_obj_data[i].pos;
             if let Some(t_vel) = temp_reset_obj_data[i].vel{
                 *pobjs[i].get_vel_mut() = t_vel;
@@ -225,140 +662,164 @@ impl World{
             pobjs[i].set_torque(temp_reset_obj_data[i].torque)
         }
 
+        self.update_sleep(&touching);
+        self.break_bodies();
+        self.stats.solver = timer.lap();
     }
 
-}
+    //wakes touched islands and puts islands to sleep that rested long enough
+    //touching holds indices of colliding pairs in the order of physics_objects
+    fn update_sleep(&mut self, touching: &[(usize, usize)]){
+        if !self.allow_sleeping{
+            self.wake_all();
+            return;
+        }
+        let ids : Vec<usize> = self.physics_objects.iter().map(|p| p.get_id()).collect();
+        let dynamic : Vec<bool> = self.physics_objects.iter().map(|p| p.is_dynamic()).collect();
+
+        //bodies attached to chains stay awake like the chains
+        let attached : Vec<usize> = self.chains.iter().flat_map(
+            |c| c.get_attachments().iter().map(|a| a.body_id)
+        ).collect();
+        let touched = touching.iter().flat_map(|&(i, j)| [i, j]).filter(|&k| dynamic[k]).map(|k| ids[k]);
+        for id in touched.chain(attached.iter().copied()).collect::<Vec<usize>>(){
+            self.wake(id);
+        }
 
-fn reset_pos(
-    pobjs: &mut Vec<&mut PhyObjPointer>,
-    i: usize,
-    j: usize,
-    temp_reset_pos: &mut Vec<TempPhyObjData>,
-    t_obj1 : TempPhyObjData,
-    t_obj2: TempPhyObjData,
-    //x1: f32, y1: f32,
-    //x2: f32, y2: f32
-){
-
-    let (x1, y1) = t_obj1.pos.into();
-    let (x2, y2) = t_obj2.pos.into();
-
-                                /*let pos1 = pobjs[i].get_pos();
-                                if !pobjs[i].is_static()
-                                && (temp_reset_pos[i].pos - pos1).magnitude2()
-                                < (Vec2::new(x1, y1) - pos1).magnitude2() {*/
-                                    temp_reset_pos[i].pos.x = x1;
-                                    temp_reset_pos[i].pos.y = y1;
-                                    println!("reset pos1: {x1}, {y1}");
-                                //}
-                                /*let pos2 = pobjs[j].get_pos();
-                                if !pobjs[j].is_static()
-                                && (temp_reset_pos[j].pos - pos2).magnitude2()
-                                < (Vec2::new(x2, y2) - pos2).magnitude2() {*/
-                                    temp_reset_pos[j].pos.x = x2;
-                                    temp_reset_pos[j].pos.y = y2;
-                                    println!("reset pos2: {x2}, {y2}");
-                                //}
-
-                                /*pobjs[i].get_pos_mut().x = temp_reset_pos[i].x;
-                                pobjs[i].get_pos_mut().y = temp_reset_pos[i].y;
-
-                                pobjs[j].get_pos_mut().x = temp_reset_pos[j].x;
-                                pobjs[j].get_pos_mut().y = temp_reset_pos[j].x;*/
-
-                                //simple impuls calc after col
-
-                                let (pv1, pv2) = //(*pobjs[i].get_vel(), *pobjs[j].get_vel());
-                                calc_vel_after_col(
-                                    pobjs[i].get_pos(), pobjs[i],
-                                    pobjs[j].get_pos(), pobjs[j]
-                                );
-
-                                if !pobjs[i].is_static() {
-                                    if let Some(t_vel) = temp_reset_pos[i].vel{
-                                        temp_reset_pos[i].vel = Some(t_vel + pv1);
-                                    }
-                                    else{
-
-                                        temp_reset_pos[i].vel = Some(pv1);
-                                    }
-                                        temp_reset_pos[i].torque = t_obj1.torque;
-                                        let reset_angle = pobjs[i].get_angle()-pobjs[i].get_angular_accel();
-                                        pobjs[i].set_angle(reset_angle);
-                                }
-                                if !pobjs[j].is_static() {
-                                    if let Some(t_vel) = temp_reset_pos[j].vel{
-                                        temp_reset_pos[j].vel = Some(t_vel + pv2);
-                                    }
-                                    else{
-                                        temp_reset_pos[j].vel = Some(pv2);
-                                    }
-                                        temp_reset_pos[j].torque = t_obj2.torque;
-                                        let reset_angle = pobjs[j].get_angle()-pobjs[j].get_angular_accel();
-                                        pobjs[j].set_angle(reset_angle);
+        for p in self.physics_objects.iter(){
+            if !p.is_dynamic(){continue;}
+            let state = self.sleep_states.entry(p.get_id()).or_default();
+            if !state.awake{continue;}
+            state.update_rest(*p.get_pos(), p.get_angle().0, p.get_vel().magnitude(), p.get_angular_accel().0);
+        }
 
-                                }
+        let mut islands = Islands::new(ids.len());
+        for &(i, j) in touching{
+            if dynamic[i] && dynamic[j]{
+                islands.union(i, j);
+            }
+        }
+        //a single restless body keeps its whole island awake
+        let mut ready = vec![true; ids.len()];
+        for k in 0..ids.len(){
+            let state = self.sleep_states.get(&ids[k]);
+            if !dynamic[k] || state.is_none_or(|s| !s.awake){continue;}
+            if state.is_some_and(|s| s.rest_steps < TIME_TO_SLEEP) || attached.contains(&ids[k]){
+                ready[islands.find(k)] = false;
+            }
+        }
 
-}
+        for (k, p) in self.physics_objects.iter_mut().enumerate(){
+            if !dynamic[k]{continue;}
+            let root = islands.find(k);
+            let Some(state) = self.sleep_states.get_mut(&ids[k]) else {continue;};
+            if !state.awake || !ready[root]{continue;}
+            state.awake = false;
+            state.island = ids[root];
+            log::trace!(target: "physics::sleep", "body {} falls asleep in island {}", ids[k], ids[root]);
+            *p.get_vel_mut() = Vec2::new(0., 0.);
+            p.set_torque(0.);
+            p.set_angular_accel(cgmath::Rad(0.));
+        }
+    }
 
-fn calc_vel_after_col(pos1: &Vec2, obj1: &PhyObjPointer, pos2: &Vec2, obj2: &PhyObjPointer) -> (Vec2, Vec2){
-    //formula for centric push
-    // pv2 = (m2 * v2 + m1 * (2 * v1 - v2)) / (m1 + m2)
-    //
-    //non_centric
-    // v1 = par_v1 + orth_v1
-    // v2 = par_v2 + orth_v2
-    // u1 = par_v2 + orth_v1
-    // u2 = par_v1 + orth_v2
-    //
-    // doesnt work for now; rework of displacement of objects probably needed
-    // doesnt work without exact positions during collision
-
-    /*
-    let par_v1_dir = pos2 - pos1;
-    let orth_v1 : Vec2 = obj1.get_vel().project_on(par_v1_dir);
-    //println!("par_v1_dir: {}, {}", par_v1_dir.x, par_v1_dir.y);
-    //println!("orth_v1: {}, {}", orth_v1.x, orth_v1.y);
-    let par_v1 = obj1.get_vel() - orth_v1;
-
-    let par_v2_dir = pos1 - pos2;
-    let orth_v2 : Vec2 = obj2.get_vel().project_on(par_v2_dir);
-    //println!("par_v2_dir: {}, {}", par_v2_dir.x, par_v2_dir.y);
-    //println!("orth_v2: {}, {}", orth_v2.x, orth_v2.y);
-    let par_v2 = obj2.get_vel() - orth_v2;
-
-    let u1 = par_v2 + orth_v1;
-    let u2 = par_v1 + orth_v2;
-
-    println!("u1, u2: {:?}, {:?}", u1.normalize(), u2.normalize());
-
-    (u1, u2)
-*/
-
-    let v1 = obj1.get_vel();
-    let m1 = obj1.get_mass();
-    let v2 = obj2.get_vel();
-    let m2 = obj2.get_mass();
-
-    let pv1 : Vec2 = (m1 * v1 + m2 * (2. * v2 - v1)) / (m2 + m1);
-    let pv2 : Vec2 = (m2 * v2 + m1 * (2. * v1 - v2)) / (m1 + m2);
-
-    (pv1, pv2)
 }
 
+fn record_impact(impacts: &mut BTreeMap<usize, Impact>, id: usize, impact: Impact){
+    let strongest = impacts.entry(id).or_insert(impact);
+    if impact.impulse > strongest.impulse{
+        *strongest = impact;
+    }
+}
 
+//bullet i stopped just in front of body j
+//probing slightly further along its velocity gives the contact to apply the impulse with
+fn resolve_impact(dispatcher: &CollisionDispatcher, pobjs: &mut [&mut PhyObjPointer], i: usize, j: usize) -> Option<Impact>{
+    let vel = *pobjs[i].get_vel();
+    if vel.magnitude2() <= 0.{
+        return None;
+    }
+    let probe = vel.normalize() * (2. * CCD_TARGET);
+    *pobjs[i].get_pos_mut() += probe;
+    let contact = dispatcher.get_contact(&pobjs[i].get_col_type(), &pobjs[j].get_col_type());
+    *pobjs[i].get_pos_mut() -= probe;
+    let contact = contact?;
+
+    let mut temp_obj_data : Vec<TempPhyObjData> = pobjs.iter().map(
+        |obj| TempPhyObjData { pos: *obj.get_pos(), vel: None, torque: obj.get_torque() }
+    ).collect();
+    let impulse = resolve_contact(pobjs, i, j, &mut temp_obj_data, &contact);
+    for k in [i, j]{
+        if !pobjs[k].is_dynamic(){continue;}
+        if let Some(t_vel) = temp_obj_data[k].vel{
+            *pobjs[k].get_vel_mut() = t_vel;
+        }
+        pobjs[k].set_torque(temp_obj_data[k].torque);
+    }
+    Some(Impact { impulse, point: contact.point, normal: contact.normal })
+}
 
+//pushes both objects apart along the contact normal, weighted by their inverse masses
+//and applies an impulse to the velocities and torques at the contact point
+//returns the impulse, 0.0 for objects already separating
+fn resolve_contact(
+    pobjs: &mut [&mut PhyObjPointer],
+    i: usize,
+    j: usize,
+    temp_reset_obj: &mut [TempPhyObjData],
+    contact: &Contact,
+) -> f32{
+    let (w1, inv_i1) = inverse_mass_inertia(pobjs[i]);
+    let (w2, inv_i2) = inverse_mass_inertia(pobjs[j]);
+    let w_sum = w1 + w2;
+    if w_sum <= 0.{return 0.;}
+
+    let n = contact.normal;
+    let correction = n * (contact.depth / w_sum);
+    temp_reset_obj[i].pos -= correction * w1;
+    temp_reset_obj[j].pos += correction * w2;
+
+    let r1 = contact.point - pobjs[i].get_pos();
+    let r2 = contact.point - pobjs[j].get_pos();
+    let v1 = temp_reset_obj[i].vel.unwrap_or(*pobjs[i].get_vel());
+    let v2 = temp_reset_obj[j].vel.unwrap_or(*pobjs[j].get_vel());
+    let ang_vel1 = angular_vel(pobjs[i], &temp_reset_obj[i], inv_i1);
+    let ang_vel2 = angular_vel(pobjs[j], &temp_reset_obj[j], inv_i2);
+    let vp1 = v1 + Vec2::new(-ang_vel1 * r1.y, ang_vel1 * r1.x);
+    let vp2 = v2 + Vec2::new(-ang_vel2 * r2.y, ang_vel2 * r2.x);
+
+    let vn = (vp2 - vp1).dot(n);
+    //already separating
+    if vn > 0.{return 0.;}
+
+    let rn1 = r1.x*n.y - r1.y*n.x;
+    let rn2 = r2.x*n.y - r2.y*n.x;
+    let impulse = -(1. + RESTITUTION) * vn
+        / (w_sum + rn1*rn1*inv_i1 + rn2*rn2*inv_i2);
+
+    if w1 > 0.{
+        temp_reset_obj[i].vel = Some(v1 - n * (impulse * w1));
+        temp_reset_obj[i].torque -= rn1 * impulse;
+    }
+    if w2 > 0.{
+        temp_reset_obj[j].vel = Some(v2 + n * (impulse * w2));
+        temp_reset_obj[j].torque += rn2 * impulse;
+    }
+    impulse
+}
 
+//static and kinematic objects are neither moved nor rotated by collisions
+fn inverse_mass_inertia(obj: &PhyObjPointer) -> (f32, f32){
+    (obj.get_inv_mass(), obj.get_inv_inertia())
+}
 
-
-pub struct Rect{
-    pub pos: Vec2,
-    force: Vec2,
-    vel: Vec2,
-    pub w: f32,
-    pub h: f32,
-    pub rot: f32,
-    rot_mat: cgmath::Matrix2<f32>,
+//the torque of dynamic objects acts like an angular momentum
+//kinematic objects rotate with the angular velocity set by the user
+fn angular_vel(obj: &PhyObjPointer, temp: &TempPhyObjData, inv_inertia: f32) -> f32{
+    if obj.is_dynamic() {temp.torque * inv_inertia} else {obj.get_angular_accel().0}
 }
 
+fn is_moving(obj: &PhyObjPointer) -> bool{
+    obj.get_vel().magnitude2() > 0. || obj.get_angular_accel().0 != 0.
+}
 
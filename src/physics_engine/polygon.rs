@@ -0,0 +1,366 @@
+//polygon utilities
+//
+//validation and repair of vertex lists for Convex2D
+//valid polygons are convex, wound counter clockwise,
+//have no duplicate or collinear vertices and at most MAX_POLYGON_VERTICES vertices
+//concave outlines are triangulated by ear clipping and
+//split into convex pieces by merging the triangles again (hertel-mehlhorn)
+
+use std::fmt;
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::Vec2;
+
+use super::geometry::{cross, segment_normal, segments_intersection, Intersects, EPSILON};
+
+pub const MAX_POLYGON_VERTICES : usize = 16;
+//vertices closer than this are merged into one
+pub const WELD_DISTANCE : f32 = 0.0001;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolygonError{
+    //number of distinct vertices left
+    TooFewVertices(usize),
+    //number of vertices of the repaired polygon
+    TooManyVertices(usize),
+    //all vertices on one line
+    ZeroArea,
+    //index of the first vertex bending the wrong way
+    NotConvex(usize),
+    //edges cross or touch each other
+    NotSimple,
+    NotFinite,
+}
+
+impl fmt::Display for PolygonError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            PolygonError::TooFewVertices(n) => write!(f, "polygon needs at least 3 distinct vertices, got {n}"),
+            PolygonError::TooManyVertices(n) => write!(f, "polygon has {n} vertices, at most {MAX_POLYGON_VERTICES} are allowed"),
+            PolygonError::ZeroArea => write!(f, "polygon vertices lie on one line"),
+            PolygonError::NotConvex(i) => write!(f, "polygon is not convex at vertex {i}"),
+            PolygonError::NotSimple => write!(f, "polygon edges intersect"),
+            PolygonError::NotFinite => write!(f, "polygon vertex is not finite"),
+        }
+    }
+}
+
+impl std::error::Error for PolygonError{}
+
+//signed area, positive for counter clockwise winding
+pub fn signed_area(verts: &[Vec2]) -> f32{
+    (0..verts.len()).map(|i| cross(verts[i], verts[(i+1)%verts.len()])).sum::<f32>() * 0.5
+}
+
+//center of mass of the polygon area
+pub fn centroid(verts: &[Vec2]) -> Vec2{
+    let area = signed_area(verts);
+    let sum = (0..verts.len()).fold(Vec2::new(0., 0.), |acc, i| {
+        let (a, b) = (verts[i], verts[(i+1)%verts.len()]);
+        acc + (a + b) * cross(a, b)
+    });
+    sum / (6. * area)
+}
+
+//even odd rule, works for concave polygons and both windings
+pub fn contains_point(verts: &[Vec2], p: Vec2) -> bool{
+    let mut inside = false;
+    for i in 0..verts.len(){
+        let (a, b) = (verts[i], verts[(i+1)%verts.len()]);
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x){
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+//unit normal of every edge i -> i+1 pointing away from the polygon
+//works for both windings
+pub fn outward_normals(verts: &[Vec2]) -> Vec<Vec2>{
+    let orientation = if signed_area(verts) >= 0. {1.} else {-1.};
+    (0..verts.len()).map(|i| segment_normal(verts[i], verts[(i+1)%verts.len()]) * -orientation).collect()
+}
+
+//merges neighbouring vertices closer than WELD_DISTANCE, including last and first
+pub fn remove_duplicates(verts: &[Vec2]) -> Vec<Vec2>{
+    let mut result : Vec<Vec2> = Vec::with_capacity(verts.len());
+    for v in verts.iter(){
+        if result.last().is_none_or(|last| (v - last).magnitude() > WELD_DISTANCE){
+            result.push(*v);
+        }
+    }
+    while result.len() > 1 && (result[0] - result[result.len()-1]).magnitude() <= WELD_DISTANCE{
+        result.pop();
+    }
+    result
+}
+
+//removes vertices lying on the line through their neighbours
+pub fn remove_collinear(verts: &[Vec2]) -> Vec<Vec2>{
+    let mut result = verts.to_vec();
+    let mut i = 0;
+    while result.len() > 2 && i < result.len(){
+        let len = result.len();
+        let prev = result[(i+len-1)%len];
+        let next = result[(i+1)%len];
+        let v = result[i];
+        if cross(v - prev, next - v).abs() <= EPSILON * (v - prev).magnitude().max(EPSILON){
+            result.remove(i);
+            //the previous vertex may be collinear now
+            i = i.saturating_sub(1);
+        }
+        else{
+            i += 1;
+        }
+    }
+    result
+}
+
+//convex hull of arbitrary points, counter clockwise without collinear points
+//monotone chain by sorting the points along x
+pub fn convex_hull(points: &[Vec2]) -> Vec<Vec2>{
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup_by(|a, b| (*a - *b).magnitude() <= WELD_DISTANCE);
+    if sorted.len() < 3{
+        return sorted;
+    }
+
+    let mut hull : Vec<Vec2> = Vec::with_capacity(sorted.len() + 1);
+    //lower hull from left to right, upper hull back from right to left
+    for pass in [sorted.clone(), sorted.iter().rev().copied().collect()]{
+        let start = hull.len();
+        for p in pass{
+            while hull.len() >= start + 2
+                && cross(hull[hull.len()-1] - hull[hull.len()-2], p - hull[hull.len()-1]) <= EPSILON{
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        //last point is the first one of the other half
+        hull.pop();
+    }
+    hull
+}
+
+fn check_finite(points: &[Vec2]) -> Result<(), PolygonError>{
+    if points.iter().all(|p| p.x.is_finite() && p.y.is_finite()) {Ok(())} else {Err(PolygonError::NotFinite)}
+}
+
+fn check_count(verts: Vec<Vec2>) -> Result<Vec<Vec2>, PolygonError>{
+    if verts.len() > MAX_POLYGON_VERTICES{
+        return Err(PolygonError::TooManyVertices(verts.len()));
+    }
+    Ok(verts)
+}
+
+//removes duplicate and collinear vertices and winds the polygon counter clockwise
+//the polygon may still be concave
+pub fn simplify(verts: &[Vec2]) -> Result<Vec<Vec2>, PolygonError>{
+    check_finite(verts)?;
+    let mut result = remove_duplicates(verts);
+    if result.len() < 3{
+        return Err(PolygonError::TooFewVertices(result.len()));
+    }
+    if signed_area(&result) < 0.{
+        result.reverse();
+    }
+    let result = remove_collinear(&result);
+    if result.len() < 3 || signed_area(&result).abs() <= EPSILON * EPSILON{
+        return Err(PolygonError::ZeroArea);
+    }
+    Ok(result)
+}
+
+//repairs winding, duplicate and collinear vertices of a convex polygon
+//concave polygons are rejected instead of silently changed
+pub fn validate_convex(verts: &[Vec2]) -> Result<Vec<Vec2>, PolygonError>{
+    let result = simplify(verts)?;
+    let len = result.len();
+    for i in 0..len{
+        let prev = result[(i+len-1)%len];
+        let next = result[(i+1)%len];
+        if cross(result[i] - prev, next - result[i]) <= 0.{
+            return Err(PolygonError::NotConvex(i));
+        }
+    }
+    check_count(result)
+}
+
+//convex polygon around any set of points
+pub fn hull_polygon(points: &[Vec2]) -> Result<Vec<Vec2>, PolygonError>{
+    check_finite(points)?;
+    let hull = convex_hull(points);
+    if hull.len() < 3{
+        //three distinct points on a line give a hull of two
+        let distinct = remove_duplicates(points).len();
+        return Err(if distinct >= 3 {PolygonError::ZeroArea} else {PolygonError::TooFewVertices(distinct)});
+    }
+    check_count(hull)
+}
+
+//true, if only neighbouring edges touch in their shared vertex
+pub fn is_simple(verts: &[Vec2]) -> bool{
+    let n = verts.len();
+    for i in 0..n{
+        for j in i+2..n{
+            if i == 0 && j == n-1{
+                continue;
+            }
+            let hit = segments_intersection(verts[i], verts[(i+1)%n], verts[j], verts[(j+1)%n]);
+            if !matches!(hit, Intersects::None){
+                return false;
+            }
+        }
+    }
+    true
+}
+
+//b is an ear, if abc is convex and no other vertex lies in the triangle
+fn is_ear(verts: &[Vec2], remaining: &[usize], a: usize, b: usize, c: usize) -> bool{
+    let (pa, pb, pc) = (verts[a], verts[b], verts[c]);
+    if cross(pb - pa, pc - pb) <= 0.{
+        return false;
+    }
+    !remaining.iter().filter(|&&i| i != a && i != b && i != c).any(|&i| {
+        let p = verts[i];
+        cross(pb - pa, p - pa) >= 0. && cross(pc - pb, p - pb) >= 0. && cross(pa - pc, p - pc) >= 0.
+    })
+}
+
+//ear clipping of a simple counter clockwise polygon, e.g. the result of simplify
+//triangles are counter clockwise indices into verts
+pub fn triangulate(verts: &[Vec2]) -> Result<Vec<[usize; 3]>, PolygonError>{
+    if verts.len() < 3{
+        return Err(PolygonError::TooFewVertices(verts.len()));
+    }
+    if !is_simple(verts){
+        return Err(PolygonError::NotSimple);
+    }
+    let mut remaining : Vec<usize> = (0..verts.len()).collect();
+    let mut triangles = Vec::with_capacity(verts.len() - 2);
+    while remaining.len() > 3{
+        let n = remaining.len();
+        let ear = (0..n).find(
+            |&i| is_ear(verts, &remaining, remaining[(i+n-1)%n], remaining[i], remaining[(i+1)%n])
+        ).ok_or(PolygonError::NotSimple)?;
+        triangles.push([remaining[(ear+n-1)%n], remaining[ear], remaining[(ear+1)%n]]);
+        remaining.remove(ear);
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    Ok(triangles)
+}
+
+//cleaned vertices of any simple polygon and the triangles covering it
+pub fn tessellate(verts: &[Vec2]) -> Result<(Vec<Vec2>, Vec<[usize; 3]>), PolygonError>{
+    let verts = simplify(verts)?;
+    let triangles = triangulate(&verts)?;
+    Ok((verts, triangles))
+}
+
+//union of two pieces sharing an edge, None if it is not convex or gets too many vertices
+fn merge_pieces(verts: &[Vec2], p: &[usize], q: &[usize]) -> Option<Vec<usize>>{
+    //edge a b of p runs from b to a in q
+    let (pi, qi) = (0..p.len()).find_map(|i| {
+        let (a, b) = (p[i], p[(i+1)%p.len()]);
+        (0..q.len()).find(|&j| q[j] == b && q[(j+1)%q.len()] == a).map(|j| (i, j))
+    })?;
+    //p from b around to a, then the rest of q between a and b
+    let mut merged : Vec<usize> = (1..=p.len()).map(|k| p[(pi+k)%p.len()]).collect();
+    merged.extend((2..q.len()).map(|k| q[(qi+k)%q.len()]));
+
+    let n = merged.len();
+    let turns : Vec<f32> = (0..n).map(|i| {
+        let (a, b, c) = (verts[merged[(i+n-1)%n]], verts[merged[i]], verts[merged[(i+1)%n]]);
+        cross(b - a, c - b)
+    }).collect();
+    //collinear vertices are removed later and do not count
+    let corners = turns.iter().filter(|&&t| t > EPSILON).count();
+    if turns.iter().any(|&t| t < -EPSILON) || corners > MAX_POLYGON_VERTICES{
+        return None;
+    }
+    Some(merged)
+}
+
+//convex pieces of a simple polygon, each counter clockwise and valid for Convex2D
+pub fn decompose(verts: &[Vec2]) -> Result<Vec<Vec<Vec2>>, PolygonError>{
+    let (verts, triangles) = tessellate(verts)?;
+    let mut pieces : Vec<Vec<usize>> = triangles.iter().map(|t| t.to_vec()).collect();
+
+    //removes diagonals between pieces as long as the union stays convex
+    let mut merged = true;
+    while merged{
+        merged = false;
+        'search: for i in 0..pieces.len(){
+            for j in i+1..pieces.len(){
+                if let Some(piece) = merge_pieces(&verts, &pieces[i], &pieces[j]){
+                    pieces[i] = piece;
+                    pieces.swap_remove(j);
+                    merged = true;
+                    break 'search;
+                }
+            }
+        }
+    }
+
+    Ok(pieces.iter().map(|p| {
+        let piece : Vec<Vec2> = p.iter().map(|&i| verts[i]).collect();
+        remove_collinear(&piece)
+    }).collect())
+}
+
+//part of subject inside the convex polygon clip (sutherland-hodgman)
+//subject may be concave, then the result can contain degenerate edges, its area stays right
+//empty, if they do not overlap
+pub fn clip_convex(subject: &[Vec2], clip: &[Vec2]) -> Vec<Vec2>{
+    let orientation = if signed_area(clip) >= 0. {1.} else {-1.};
+    let mut result = subject.to_vec();
+    for i in 0..clip.len(){
+        if result.is_empty(){
+            break;
+        }
+        let (a, b) = (clip[i], clip[(i+1)%clip.len()]);
+        let inside = |p: Vec2| cross(b - a, p - a) * orientation >= 0.;
+        let input = std::mem::take(&mut result);
+        for j in 0..input.len(){
+            let (p, q) = (input[j], input[(j+1)%input.len()]);
+            match (inside(p), inside(q)){
+                (true, true) => result.push(q),
+                (true, false) => result.push(line_intersection(p, q, a, b)),
+                (false, true) => {
+                    result.push(line_intersection(p, q, a, b));
+                    result.push(q);
+                },
+                (false, false) => {},
+            }
+        }
+    }
+    result
+}
+
+//parts of a convex polygon left and right of the line from a to b,
+//sides without a part of the polygon are empty
+pub fn split_convex(verts: &[Vec2], a: Vec2, b: Vec2) -> (Vec<Vec2>, Vec<Vec2>){
+    let side = |p: Vec2| cross(b - a, p - a);
+    let (mut left, mut right) = (vec![], vec![]);
+    for j in 0..verts.len(){
+        let (p, q) = (verts[j], verts[(j+1)%verts.len()]);
+        let (sp, sq) = (side(p), side(q));
+        if sp >= 0. {left.push(p);}
+        if sp <= 0. {right.push(p);}
+        if (sp > 0. && sq < 0.) || (sp < 0. && sq > 0.){
+            let x = line_intersection(p, q, a, b);
+            left.push(x);
+            right.push(x);
+        }
+    }
+    let part = |p: Vec<Vec2>| if p.len() < 3 {vec![]} else {p};
+    (part(left), part(right))
+}
+
+//point of segment p q on the line through a and b, p and q lie on different sides
+fn line_intersection(p: Vec2, q: Vec2, a: Vec2, b: Vec2) -> Vec2{
+    let (dp, dq) = (cross(b - a, p - a), cross(b - a, q - a));
+    p + (q - p) * (dp / (dp - dq))
+}
@@ -0,0 +1,167 @@
+//rect body
+//
+//oriented box, w and h are the full side lengths
+
+
+use cgmath::InnerSpace;
+
+use crate::primitives_2d::utils::{Vec2, Radians, Mat2};
+
+use super::physic_obj_traits::*;
+
+pub struct Rect{
+    pub body_type : BodyType,
+    pub gravity_scale : f32,
+    pub bullet : bool,
+    pub pos: Vec2,
+    pub w: f32,
+    pub h: f32,
+    m : f32,
+    force: Vec2,
+    torque: f32,
+    vel: Vec2,
+    angle: cgmath::Rad<f32>,
+    ang_vel: cgmath::Rad<f32>,
+    inertia: f32,
+    id : usize,
+    nodes : Vec<usize>,
+}
+
+impl Rect{
+    pub fn new(x: f32, y: f32, w: f32, h: f32, m: f32) -> Self{
+        Self {
+            body_type: BodyType::Dynamic,
+            gravity_scale: 1.,
+            bullet: false,
+            pos: Vec2::new(x, y),
+            w, h, m,
+            force: Vec2::new(0., 0.),
+            torque: 0.,
+            vel: Vec2::new(0., 0.),
+            angle: cgmath::Rad(0.),
+            ang_vel: cgmath::Rad(0.),
+            inertia: m * (w.powi(2) + h.powi(2)) / 12.,
+            id: 0,
+            nodes: vec![],
+        }
+    }
+
+    pub fn half_extents(&self) -> Vec2{
+        Vec2::new(self.w * 0.5, self.h * 0.5)
+    }
+
+    //unit vectors of the local x and y axis in world space
+    pub fn axes(&self) -> (Vec2, Vec2){
+        let rot = Mat2::from_angle(self.angle);
+        (rot.x, rot.y)
+    }
+
+    //counter clockwise, starting bottom left
+    pub fn local_vertices(&self) -> [Vec2; 4]{
+        let h = self.half_extents();
+        [
+            Vec2::new(-h.x, -h.y),
+            Vec2::new(h.x, -h.y),
+            Vec2::new(h.x, h.y),
+            Vec2::new(-h.x, h.y),
+        ]
+    }
+
+    pub fn world_vertices(&self) -> Vec<Vec2>{
+        let rot = Mat2::from_angle(self.angle);
+        self.local_vertices().iter().map(|v| rot * v + self.pos).collect()
+    }
+
+    pub fn to_local(&self, p: Vec2) -> Vec2{
+        let (ax, ay) = self.axes();
+        let d = p - self.pos;
+        Vec2::new(d.dot(ax), d.dot(ay))
+    }
+
+    pub fn to_world(&self, p: Vec2) -> Vec2{
+        let (ax, ay) = self.axes();
+        self.pos + ax * p.x + ay * p.y
+    }
+}
+
+impl PhysicsObject for Rect{
+    fn body_type(&self)-> BodyType {
+        self.body_type
+    }
+    fn gravity_scale(&self)-> f32 {
+        self.gravity_scale
+    }
+    fn is_bullet(&self)-> bool {
+        self.bullet
+    }
+}
+
+impl ForceObject for Rect{
+    fn get_mass(&self) -> f32 {
+        self.m
+    }
+    fn get_force(&self) -> &Vec2 {
+        &self.force
+    }
+    fn get_force_mut(&mut self) -> &mut Vec2 {
+        &mut self.force
+    }
+    fn get_vel(&self) -> &Vec2 {
+        &self.vel
+    }
+    fn get_vel_mut(&mut self) -> &mut Vec2 {
+        &mut self.vel
+    }
+    fn get_torque(&self) -> f32 {
+        self.torque
+    }
+    fn set_torque(&mut self, torque: f32) {
+        self.torque = torque;
+    }
+    fn get_inertia(&self) -> f32 {
+        self.inertia
+    }
+    fn set_inertia(&mut self, inertia: f32) {
+        self.inertia = inertia;
+    }
+    fn get_angular_accel(&self) -> Radians {
+        self.ang_vel
+    }
+    fn set_angular_accel(&mut self, angle : Radians) {
+        self.ang_vel = angle;
+    }
+
+}
+
+impl TransposeObject for Rect{
+    fn get_pos(&self) -> &cgmath::Vector2<f32> {
+        &self.pos
+    }
+    fn get_pos_mut(&mut self) -> &mut cgmath::Vector2<f32> {
+        &mut self.pos
+    }
+    fn get_angle(&self) -> Radians {
+        self.angle
+    }
+    fn set_angle(&mut self, angle: Radians) {
+        self.angle = angle;
+    }
+}
+
+impl NodeObject for Rect{
+    fn get_id(&self) -> usize {
+        self.id
+    }
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    fn get_connections(&self) -> &[usize] {
+        &self.nodes[..]
+    }
+}
+
+impl CollisionObject for Rect{
+    fn get_col_type(&self) -> CollisionType {
+        CollisionType::Rectangle(self)
+    }
+}
@@ -0,0 +1,86 @@
+//sleeping
+//
+//bodies resting for a while are no longer simulated until something wakes them
+//bodies touching each other form an island, an island only falls asleep
+//when all of its bodies rest, so stacks sleep and wake up together
+//bodies attached to a chain never sleep, the chain keeps moving them
+
+//velocities are per step like everywhere in the world
+pub const SLEEP_LINEAR_TOLERANCE : f32 = 0.0008;
+pub const SLEEP_ANGULAR_TOLERANCE : f32 = 0.006;
+//the gravity only adds little velocity per step, so slowly falling bodies
+//are told apart from resting ones by how far they got since they started resting
+pub const SLEEP_DISTANCE_TOLERANCE : f32 = 0.004;
+pub const SLEEP_ANGLE_TOLERANCE : f32 = 0.015;
+//steps an island has to rest before it falls asleep
+pub const TIME_TO_SLEEP : usize = 60;
+
+use crate::primitives_2d::utils::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SleepState{
+    pub awake: bool,
+    //steps the body rested in a row
+    pub rest_steps: usize,
+    //pose the body had when it started resting
+    pub rest_pos: Vec2,
+    pub rest_angle: f32,
+    //bodies that fell asleep together share the island and wake up together
+    pub island: usize,
+}
+
+impl Default for SleepState{
+    fn default() -> Self{
+        Self { awake: true, rest_steps: 0, rest_pos: Vec2::new(0., 0.), rest_angle: 0., island: 0 }
+    }
+}
+
+impl SleepState{
+    pub fn wake(&mut self){
+        self.awake = true;
+        self.rest_steps = 0;
+    }
+
+    //counts the steps the body rests, the pose is kept from the first one
+    pub fn update_rest(&mut self, pos: Vec2, angle: f32, vel: f32, ang_vel: f32){
+        let resting = self.rest_steps > 0
+            && vel < SLEEP_LINEAR_TOLERANCE
+            && ang_vel.abs() < SLEEP_ANGULAR_TOLERANCE
+            && (pos.x - self.rest_pos.x).hypot(pos.y - self.rest_pos.y) < SLEEP_DISTANCE_TOLERANCE
+            && (angle - self.rest_angle).abs() < SLEEP_ANGLE_TOLERANCE;
+        if resting{
+            self.rest_steps += 1;
+        }else{
+            self.rest_steps = 1;
+            self.rest_pos = pos;
+            self.rest_angle = angle;
+        }
+    }
+}
+
+//union find over the bodies of one step
+pub struct Islands{
+    parent: Vec<usize>,
+}
+
+impl Islands{
+    pub fn new(len: usize) -> Self{
+        Self { parent: (0..len).collect() }
+    }
+
+    pub fn find(&mut self, mut i: usize) -> usize{
+        while self.parent[i] != i{
+            //path halving
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    pub fn union(&mut self, a: usize, b: usize){
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb{
+            self.parent[rb] = ra;
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//soft body
+//
+//deformable body of particles simulated like the chain (verlet integration)
+//springs keep the distances of neighbouring particles,
+//shape matching pulls the particles towards the rotated rest shape
+//and the pressure keeps the area inside the outline, which makes blobs
+//every particle collides with the bodies of the World as a small circle,
+//particles closer than two radii keep the bodies from slipping in between
+
+use cgmath::InnerSpace;
+
+use crate::{gen_vec::GenVec, primitives_2d::utils::{Vec2, Mat2}};
+
+use super::{physics_world::PhyObjPointer, chain_body::{Point, MIN_DIST, capsule_body_contact, solve_body_collisions, apply_body_corrections, keep_in_bounds}, polygon};
+
+pub struct SoftBody{
+    pub points : Vec<Point>,
+    pub point_mass : f32,
+    //collision radius of every particle
+    pub radius : f32,
+    //number of constraint solver passes per simulation step
+    pub iterations : usize,
+    //share of the velocity that is kept each step
+    pub damping : f32,
+    //share of the spring error corrected per pass, 1.0: stiff springs
+    pub stiffness : f32,
+    //share of the way to the rest shape moved per pass, 0.0: no shape matching
+    pub shape_matching : f32,
+    //area kept inside the outline relative to the rest area, 0.0: no pressure
+    pub pressure : f32,
+    pub body_collision : bool,
+    //share of the sliding movement removed on contact with a body
+    pub friction : f32,
+    springs : Vec<Spring>,
+    //indices of the particles on the outline, counter clockwise
+    outline : Vec<usize>,
+    //particle positions relative to the center of the rest shape
+    rest_shape : Vec<Vec2>,
+    rest_area : f32,
+}
+
+pub struct Spring{
+    pub a: usize,
+    pub b: usize,
+    pub rest: f32,
+}
+
+impl SoftBody{
+
+    //closed ring of particles around center, kept round by its pressure
+    pub fn ring(
+        center: [f32; 2],
+        ring_radius: f32,
+        segments: usize,
+        point_mass: f32,
+        radius: f32
+    ) -> Self{
+        let segments = segments.max(3);
+        let center = Vec2::new(center[0], center[1]);
+        let points : Vec<Point> = (0..segments).map(
+            |i| {
+                let a = std::f32::consts::TAU * i as f32 / segments as f32;
+                let p = center + Vec2::new(a.cos(), a.sin()) * ring_radius;
+                Point::new(p.x, p.y)
+            }
+        ).collect();
+
+        //neighbours and second neighbours, the latter resist bending
+        let pairs = (0..segments).flat_map(|i| [(i, (i+1)%segments), (i, (i+2)%segments)]).collect();
+        let mut ring = Self::with_points(points, pairs, (0..segments).collect(), point_mass, radius);
+        ring.pressure = 1.;
+        ring
+    }
+
+    //cols x rows particles with the given spacing, starting at the lower left corner
+    //kept in shape by shape matching
+    pub fn grid(
+        lower_left: [f32; 2],
+        cols: usize,
+        rows: usize,
+        spacing: f32,
+        point_mass: f32,
+        radius: f32
+    ) -> Self{
+        let (cols, rows) = (cols.max(2), rows.max(2));
+        let index = |c: usize, r: usize| r * cols + c;
+        let points : Vec<Point> = (0..rows).flat_map(
+            |r| (0..cols).map(move |c| Point::new(lower_left[0] + c as f32 * spacing, lower_left[1] + r as f32 * spacing))
+        ).collect();
+
+        let mut pairs = vec![];
+        for r in 0..rows{
+            for c in 0..cols{
+                if c+1 < cols {pairs.push((index(c, r), index(c+1, r)));}
+                if r+1 < rows {pairs.push((index(c, r), index(c, r+1)));}
+                if c+1 < cols && r+1 < rows{
+                    pairs.push((index(c, r), index(c+1, r+1)));
+                    pairs.push((index(c+1, r), index(c, r+1)));
+                }
+            }
+        }
+
+        let outline = (0..cols).map(|c| index(c, 0))
+            .chain((1..rows).map(|r| index(cols-1, r)))
+            .chain((0..cols-1).rev().map(|c| index(c, rows-1)))
+            .chain((1..rows-1).rev().map(|r| index(0, r)))
+            .collect();
+        let mut grid = Self::with_points(points, pairs, outline, point_mass, radius);
+        grid.shape_matching = 0.2;
+        grid
+    }
+
+    //springs rest at the distances of the particles given
+    fn with_points(
+        points: Vec<Point>,
+        pairs: Vec<(usize, usize)>,
+        outline: Vec<usize>,
+        point_mass: f32,
+        radius: f32
+    ) -> Self{
+        let springs = pairs.into_iter().map(
+            |(a, b)| Spring { a, b, rest: (points[b].pos - points[a].pos).magnitude() }
+        ).collect();
+        let mut soft_body = Self{
+            points,
+            point_mass,
+            radius,
+            iterations: 10,
+            damping: 0.99,
+            stiffness: 1.,
+            shape_matching: 0.,
+            pressure: 0.,
+            body_collision: true,
+            friction: 0.3,
+            springs,
+            outline,
+            rest_shape: vec![],
+            rest_area: 0.,
+        };
+        let center = soft_body.center();
+        soft_body.rest_shape = soft_body.points.iter().map(|p| p.pos - center).collect();
+        soft_body.rest_area = soft_body.area();
+        soft_body
+    }
+
+    pub fn pin(&mut self, point_index: usize){
+        self.points[point_index].pinned = true;
+    }
+
+    pub fn unpin(&mut self, point_index: usize){
+        self.points[point_index].pinned = false;
+    }
+
+    pub fn get_springs(&self) -> &[Spring]{
+        &self.springs[..]
+    }
+
+    //average position of the particles
+    pub fn center(&self) -> Vec2{
+        self.points.iter().fold(Vec2::new(0., 0.), |sum, p| sum + p.pos) / self.points.len() as f32
+    }
+
+    pub fn area(&self) -> f32{
+        polygon::signed_area(&self.outline_points())
+    }
+
+    pub fn outline_points(&self) -> Vec<Vec2>{
+        self.outline.iter().map(|&i| self.points[i].pos).collect()
+    }
+
+    //closed line for LineMesh, the first point is repeated at the end
+    pub fn outline_coords(&self) -> Vec<[f32; 2]>{
+        let mut coords : Vec<[f32; 2]> = self.outline.iter().map(|&i| self.points[i].as_f32_arr()).collect();
+        coords.extend(coords.first().copied());
+        coords
+    }
+
+    //vertices for draw_convex::Convex::from_polygon, relative to the origin
+    pub fn outline_vertices(&self) -> Vec<(f32, f32)>{
+        self.outline_points().into_iter().map(Into::into).collect()
+    }
+
+    //gravity is the velocity change per step, as used for the bodies of World
+    pub fn simulate_movement(&mut self, gravity: Vec2){
+        for p in self.points.iter_mut(){
+            if p.pinned{
+                p.prev_pos = p.pos;
+                continue;
+            }
+            let vel = p.get_vel() * self.damping;
+            p.prev_pos = p.pos;
+            p.pos += vel + gravity;
+        }
+    }
+
+    //bodies hit by particles are pushed away depending on their mass
+    pub fn solve_constraints(
+        &mut self,
+        bodies: &mut GenVec<PhyObjPointer>,
+        width_bound: (f32, f32),
+        height_bound: (f32, f32)
+    ){
+        //corrections of bodies the particles collided with, by body id
+        let mut body_corrections : Vec<(usize, Vec2)> = vec![];
+
+        for _ in 0..self.iterations{
+            for s in 0..self.springs.len(){
+                self.solve_spring(s);
+            }
+            if self.shape_matching > 0.{
+                self.solve_shape_matching();
+            }
+            if self.pressure > 0.{
+                self.solve_pressure();
+            }
+            if self.body_collision{
+                self.solve_body_collisions(bodies, &mut body_corrections);
+            }
+            keep_in_bounds(&mut self.points, self.radius, width_bound, height_bound);
+        }
+
+        apply_body_corrections(bodies, body_corrections);
+    }
+
+    fn inv_mass(&self, point_index: usize) -> f32{
+        self.points[point_index].inv_mass(self.point_mass)
+    }
+
+    fn solve_spring(&mut self, s: usize){
+        let Spring { a, b, rest } = self.springs[s];
+        let w1 = self.inv_mass(a);
+        let w2 = self.inv_mass(b);
+        let w_sum = w1 + w2;
+        if w_sum <= 0.{return;}
+
+        let delta = self.points[b].pos - self.points[a].pos;
+        let dist = delta.magnitude();
+        if dist < MIN_DIST{return;}
+
+        let diff = (dist - rest) / (dist * w_sum) * self.stiffness;
+        self.points[a].pos += delta * (w1 * diff);
+        self.points[b].pos -= delta * (w2 * diff);
+    }
+
+    //rotation of the rest shape fitting the particles best, around their center
+    fn solve_shape_matching(&mut self){
+        let center = self.center();
+        let (mut dot, mut cross) = (0., 0.);
+        for (p, q) in self.points.iter().zip(self.rest_shape.iter()){
+            let d = p.pos - center;
+            dot += q.dot(d);
+            cross += q.x * d.y - q.y * d.x;
+        }
+        let rot = Mat2::from_angle(cgmath::Rad(cross.atan2(dot)));
+        for (p, q) in self.points.iter_mut().zip(self.rest_shape.iter()){
+            if p.pinned{continue;}
+            let goal = center + rot * q;
+            p.pos += (goal - p.pos) * self.shape_matching;
+        }
+    }
+
+    //moves the outline along its normals until it holds pressure * rest area
+    fn solve_pressure(&mut self){
+        let n = self.outline.len();
+        let error = self.area() - self.pressure * self.rest_area;
+        //gradient of the area for every outline particle
+        let gradients : Vec<Vec2> = (0..n).map(|k| {
+            let prev = self.points[self.outline[(k+n-1)%n]].pos;
+            let next = self.points[self.outline[(k+1)%n]].pos;
+            Vec2::new(next.y - prev.y, prev.x - next.x) * 0.5
+        }).collect();
+        let denom : f32 = (0..n).map(|k| self.inv_mass(self.outline[k]) * gradients[k].magnitude2()).sum();
+        if denom <= MIN_DIST{return;}
+
+        let lambda = -error / denom;
+        for (k, gradient) in gradients.into_iter().enumerate(){
+            let i = self.outline[k];
+            let w = self.inv_mass(i);
+            self.points[i].pos += gradient * (lambda * w);
+        }
+    }
+
+    fn solve_body_collisions(
+        &mut self,
+        bodies: &GenVec<PhyObjPointer>,
+        body_corrections: &mut Vec<(usize, Vec2)>
+    ){
+        solve_body_collisions(bodies.iter(), body_corrections, |body, mut body_offset| {
+            let body_inv_mass = body.get_inv_mass();
+            for i in 0..self.points.len(){
+                let p = self.points[i].pos;
+                //n points from the body to the particle
+                if let Some(contact) = capsule_body_contact(p, p, self.radius, &body.get_col_type(), body_offset){
+                    let n = -contact.normal;
+                    let w = self.inv_mass(i);
+                    let w_sum = w + body_inv_mass;
+                    if w_sum <= 0.{continue;}
+                    self.points[i].pos += n * (contact.depth * w / w_sum);
+                    body_offset -= n * (contact.depth * body_inv_mass / w_sum);
+
+                    let tangent = Vec2::new(-n.y, n.x);
+                    let slide = self.points[i].get_vel().dot(tangent);
+                    self.points[i].prev_pos += tangent * (slide * self.friction);
+                }
+            }
+            body_offset
+        });
+    }
+}
@@ -0,0 +1,70 @@
+//step statistics
+//
+//counters and timings of the last simulation step, see World::stats
+//the counters are always kept, the timings only while World::profiling is set
+//(std::time::Instant is not available on wasm, it is off there by default)
+//with the log crate at debug level the world logs the stats of every step to "physics::stats"
+//with the tracing feature every phase is also a tracing span with the name of its stats field
+
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StepStats{
+    //whole simulation_step
+    pub step: Duration,
+    //forces, velocities and movement of the bodies including the bullets
+    pub integration: Duration,
+    //chains, soft bodies and particles
+    pub deformables: Duration,
+    //finding the pairs of bodies whose bounding boxes overlap
+    pub broad_phase: Duration,
+    //contacts of the pairs and the contact filter
+    pub narrow_phase: Duration,
+    //resolving the contacts, sleeping and breaking bodies
+    pub solver: Duration,
+    //pairs of bodies with a moving and a dynamic body
+    pub pairs_checked: usize,
+    //pairs passed to the narrow phase
+    pub pairs_tested: usize,
+    pub contacts: usize,
+    //contacts disabled by one way platforms or the pre solve hook
+    pub contacts_disabled: usize,
+    //awake dynamic bodies after the step
+    pub bodies_awake: usize,
+    //gjk distance queries of the step and their iterations, including ccd and shape casts
+    pub gjk_calls: usize,
+    pub gjk_iterations: usize,
+}
+
+//span of a phase for tracing, entered until the end of the block, nothing without the feature
+macro_rules! phase_span {
+    ($name: literal) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(target: "physics", $name).entered();
+    };
+}
+pub(crate) use phase_span;
+
+//time since the last lap, zero while disabled
+pub(crate) struct PhaseTimer{
+    start: Option<Instant>,
+    last: Option<Instant>,
+}
+
+impl PhaseTimer{
+    pub(crate) fn new(enabled: bool) -> Self{
+        let now = enabled.then(Instant::now);
+        Self { start: now, last: now }
+    }
+
+    pub(crate) fn lap(&mut self) -> Duration{
+        let Some(last) = self.last else {return Duration::ZERO;};
+        let now = Instant::now();
+        self.last = Some(now);
+        now - last
+    }
+
+    pub(crate) fn total(&self) -> Duration{
+        self.start.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+}
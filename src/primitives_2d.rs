@@ -4,4 +4,7 @@ pub mod draw_line;
 pub mod draw_circle;
 pub mod draw_rect;
 pub mod draw_convex;
+pub mod draw_capsule;
+pub mod draw_particles;
+pub mod draw_debug;
 pub mod utils;
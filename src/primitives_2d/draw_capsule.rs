@@ -0,0 +1,59 @@
+//draw_capsule.rs
+//
+//stadium shape drawn as a convex fan
+
+use super::draw_convex::Convex;
+use crate::physics_engine::{capsule_body, physic_obj_traits::TransposeObject};
+
+//vertices per half circle
+const CAP_SEGMENTS : usize = 8;
+
+pub struct Capsule{
+    pub half_length: f32,
+    pub r: f32,
+    convex: Convex,
+}
+
+impl Capsule{
+    pub fn new(device: &wgpu::Device, x: f32, y: f32, half_length: f32, r: f32, color: [f32; 4]) -> Self{
+        Self {
+            half_length, r,
+            convex: Convex::new(device, x, y, Self::get_capsule_verts(half_length, r), color),
+        }
+    }
+
+    pub fn from_body(device: &wgpu::Device, body: &capsule_body::Capsule, color: [f32; 4]) -> Self{
+        let mut capsule = Self::new(device, body.pos.x, body.pos.y, body.half_length, body.r, color);
+        capsule.update_from_body(device, body);
+        capsule
+    }
+
+    //the segment of the capsule lies along its local x axis
+    //vertices go clockwise
+    pub fn get_capsule_verts(half_length: f32, r: f32) -> Vec<(f32, f32)>{
+        let mut vertices = Vec::with_capacity(2*(CAP_SEGMENTS+1));
+        for (cx, start) in [(half_length, std::f32::consts::FRAC_PI_2), (-half_length, -std::f32::consts::FRAC_PI_2)]{
+            for i in 0..=CAP_SEGMENTS{
+                let a = start - std::f32::consts::PI * i as f32 / CAP_SEGMENTS as f32;
+                vertices.push((cx + r*a.cos(), r*a.sin()));
+            }
+        }
+        vertices
+    }
+
+    pub fn update_from_body(&mut self, device: &wgpu::Device, body: &capsule_body::Capsule){
+        if self.half_length != body.half_length || self.r != body.r{
+            *self = Self::new(device, body.pos.x, body.pos.y, body.half_length, body.r, self.convex.color);
+        }
+        self.convex.rotate(device, body.get_angle().0);
+        self.convex.translate(device, body.pos.x, body.pos.y);
+    }
+
+    pub fn draw<'a, 'b>(
+        &'a self,
+        render_pipeline: &'a wgpu::RenderPipeline,
+        render_pass: &'b mut wgpu::RenderPass<'a>
+    ){
+        self.convex.draw(render_pipeline, render_pass);
+    }
+}
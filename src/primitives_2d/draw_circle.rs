@@ -211,6 +211,22 @@ impl Circle{
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration
     ) -> wgpu::RenderPipeline{
+        Self::create_instanced_pipeline(
+            device, config,
+            wgpu::include_wgsl!("circle.wgsl"),
+            Instance2DRaw::desc(),
+            "Circle Render Pipeline"
+        )
+    }
+
+    //circle vertices drawn with the given instance layout, e.g. for the particles
+    pub fn create_instanced_pipeline(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        shader: wgpu::ShaderModuleDescriptor,
+        instance_layout: wgpu::VertexBufferLayout,
+        label: &str
+    ) -> wgpu::RenderPipeline{
 
 
         let render_pipeline_layout = device.create_pipeline_layout(
@@ -222,19 +238,17 @@ impl Circle{
             }
         );
 
-        let shader = device.create_shader_module(
-            wgpu::include_wgsl!("circle.wgsl")
-        );
+        let shader = device.create_shader_module(shader);
         device.create_render_pipeline(
             &wgpu::RenderPipelineDescriptor{
-                label: Some("Circle Render Pipeline"),
+                label: Some(label),
                 layout: Some(&render_pipeline_layout),
                 vertex: wgpu::VertexState{
                     module: &shader,
                     entry_point: "vs_main",
                     buffers: &[
                         CircleVertex::desc(),
-                        Instance2DRaw::desc(),
+                        instance_layout,
                     ],
                 },
                 fragment: Some(wgpu::FragmentState {
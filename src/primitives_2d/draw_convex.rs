@@ -5,6 +5,7 @@ use cgmath::Deg;
 use wgpu::util::DeviceExt;
 
 use super::{utils::{Instance2D, Vec2, Mat2}, draw_rect::RectVertex};
+use crate::physics_engine::polygon::{self, PolygonError};
 
 pub struct Convex{
     pub vertices : Vec<(f32, f32)>,
@@ -12,6 +13,8 @@ pub struct Convex{
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub num_indices: u32,
+    //vertices and indices the buffers have room for
+    capacity: (usize, usize),
     instance: Instance2D,
     instance_buffer: wgpu::Buffer,
 }
@@ -20,7 +23,22 @@ pub struct Convex{
 impl Convex{
 
     pub fn new(device: &wgpu::Device, x: f32, y: f32, vertices : Vec<(f32, f32)>, color: [f32; 4]) -> Self{
-        let (vs, is) = Self::get_buffers(device, &vertices, color);
+        let (vs, is) = Self::get_convex_verts_inds(&vertices, color);
+        Self::from_mesh(device, x, y, vertices, color, &vs, &is)
+    }
+
+    //any simple polygon, concave ones get triangulated instead of drawn as a fan
+    pub fn from_polygon(device: &wgpu::Device, x: f32, y: f32, vertices : Vec<(f32, f32)>, color: [f32; 4]) -> Result<Self, PolygonError>{
+        let (vs, is) = Self::get_polygon_verts_inds(&vertices, color)?;
+        Ok(Self::from_mesh(device, x, y, vertices, color, &vs, &is))
+    }
+
+    fn from_mesh(
+        device: &wgpu::Device, x: f32, y: f32,
+        vertices : Vec<(f32, f32)>, color: [f32; 4],
+        vs: &[RectVertex], is: &[u16]
+    ) -> Self{
+        let (vertex_buffer, index_buffer) = Self::create_buffers(device, vs, is);
         let instance = Instance2D{
             pos: Vec2::new(x, y),
             rot: Mat2::from_angle(Deg(0.)),
@@ -34,18 +52,58 @@ impl Convex{
          }
         );
 
-        let num_indices = 3*vertices.len() as u32;
+        let num_indices = is.len() as u32;
 
         Self {
             vertices,
             color,
-            vertex_buffer: vs, index_buffer: is, num_indices,
+            vertex_buffer, index_buffer, num_indices,
+            capacity: (vs.len(), is.len()),
             instance,
             instance_buffer
         }
 
     }
 
+    //new outline of a polygon from from_polygon, the buffers are only recreated when they are too small
+    pub fn update_polygon(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, vertices : Vec<(f32, f32)>) -> Result<(), PolygonError>{
+        let (vs, mut is) = match Self::get_polygon_verts_inds(&vertices, self.color){
+            Ok(mesh) => mesh,
+            Err(e) => {
+                //nothing is drawn until the outline is simple again
+                self.num_indices = 0;
+                return Err(e);
+            },
+        };
+        self.vertices = vertices;
+        self.num_indices = is.len() as u32;
+        //buffer writes have to be a multiple of 4 bytes
+        if is.len() % 2 == 1 {is.push(0);}
+        if vs.len() > self.capacity.0 || is.len() > self.capacity.1{
+            let (vertex_buffer, index_buffer) = Self::create_buffers(device, &vs, &is);
+            self.vertex_buffer = vertex_buffer;
+            self.index_buffer = index_buffer;
+            self.capacity = (vs.len(), is.len());
+        }
+        else{
+            queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vs));
+            queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&is));
+        }
+        Ok(())
+    }
+
+    //triangles of the ear clipping, counter clockwise like the fan
+    pub fn get_polygon_verts_inds(
+        init_vertices : &[(f32, f32)],
+        color: [f32; 4]
+    ) -> Result<(Vec<RectVertex>, Vec<u16>), PolygonError>{
+        let points : Vec<Vec2> = init_vertices.iter().map(|&v| v.into()).collect();
+        let (points, triangles) = polygon::tessellate(&points)?;
+        let vertices = points.iter().map(|p| RectVertex{position: [p.x, p.y, 0.0], color}).collect();
+        let indices = triangles.iter().flatten().map(|&i| i as u16).collect();
+        Ok((vertices, indices))
+    }
+
     pub fn get_convex_verts_inds(
         init_vertices : &Vec<(f32, f32)>,
         color: [f32; 4]
@@ -55,7 +113,20 @@ impl Convex{
         let mut vertices = Vec::with_capacity(init_vertices.len()+1);
         let mut indices : Vec<u16> = Vec::with_capacity(3*init_vertices.len());
 
-        vertices.push(RectVertex{position: [0.0, 0.0, 0.0], color});
+        //fan around the vertex average, so the center is inside for any convex polygon
+        let n = init_vertices.len() as f32;
+        let (cx, cy) = init_vertices.iter().fold((0., 0.), |(sx, sy), (x, y)| (sx + x/n, sy + y/n));
+        vertices.push(RectVertex{position: [cx, cy, 0.0], color});
+
+        //triangles have to be counter clockwise for both windings of the polygon
+        let area : f32 = (0..init_vertices.len()).map(|i| {
+            let (x1, y1) = init_vertices[i];
+            let (x2, y2) = init_vertices[(i+1)%init_vertices.len()];
+            x1*y2 - x2*y1
+        }).sum();
+        let mut push_tri = |a: u16, b: u16|{
+            if area > 0. {indices.extend([0, a, b]);} else {indices.extend([0, b, a]);}
+        };
 
         let (x, y) = init_vertices[0];
         vertices.push(RectVertex{position: [x, y, 0.0], color});
@@ -64,11 +135,11 @@ impl Convex{
             let (x, y) = init_vertices[i];
             vertices.push(RectVertex{position: [x, y, 0.0], color});
             let ind = i as u16 + 1;
-            indices.push(0); indices.push(ind); indices.push(ind-1);
+            push_tri(ind-1, ind);
         }
 
         let ind = (init_vertices.len()) as u16;
-        indices.push(0); indices.push(1); indices.push(ind);
+        push_tri(ind, 1);
 
 
         (vertices, indices)
@@ -138,19 +209,23 @@ impl Convex{
         vertices : &Vec<(f32, f32)>, color: [f32; 4]
     ) -> (wgpu::Buffer, wgpu::Buffer) {
         let (vs, is) = Self::get_convex_verts_inds(vertices, color);
+        Self::create_buffers(device, &vs, &is)
+    }
+
+    fn create_buffers(device: &wgpu::Device, vs: &[RectVertex], is: &[u16]) -> (wgpu::Buffer, wgpu::Buffer) {
         let vertex_buffer = device.create_buffer_init(
             &wgpu::util::BufferInitDescriptor {
                 label: Some("Vertex Buffer"),
-                contents: bytemuck::cast_slice(&vs),
-                usage: wgpu::BufferUsages::VERTEX,
+                contents: bytemuck::cast_slice(vs),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             }
         );
 
         let index_buffer = device.create_buffer_init(
             &wgpu::util::BufferInitDescriptor{
                 label: Some("Index Buffer"),
-                contents: bytemuck::cast_slice(&is),
-                usage: wgpu::BufferUsages::INDEX,
+                contents: bytemuck::cast_slice(is),
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
             }
         );
         (vertex_buffer, index_buffer)
@@ -0,0 +1,117 @@
+//draw debug
+//
+//DebugDraw collecting the segments and dots of a frame into one buffer each,
+//segments are drawn as quads with the line pipeline and dots as instances of the particle pipeline,
+//the buffers are written again every frame and only grow when there is more to draw
+
+use crate::physics_engine::debug_draw::{DebugDraw, DebugDrawFlags};
+
+use super::{draw_line::{Line, LineVertex}, draw_particles::{ParticleMesh, ParticleInstanceRaw}, utils::{Vec2, Instance2DRaw}};
+
+pub struct DebugRenderer{
+    pub flags: DebugDrawFlags,
+    pub line_width: f32,
+    pub velocity_scale: f32,
+    vertices: Vec<LineVertex>,
+    indices: Vec<u32>,
+    dots: Vec<ParticleInstanceRaw>,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    //vertices and indices the buffers have room for
+    capacity: (usize, usize),
+    num_indices: u32,
+    dot_mesh: ParticleMesh,
+}
+
+impl DebugRenderer{
+    pub fn new(device: &wgpu::Device, flags: DebugDrawFlags) -> Self{
+        let capacity = (1024, 1536);
+        Self {
+            flags,
+            line_width: 0.004,
+            velocity_scale: 1.,
+            vertices: vec![],
+            indices: vec![],
+            dots: vec![],
+            vertex_buffer: Self::create_buffer::<LineVertex>(device, capacity.0, wgpu::BufferUsages::VERTEX),
+            index_buffer: Self::create_buffer::<u32>(device, capacity.1, wgpu::BufferUsages::INDEX),
+            capacity,
+            num_indices: 0,
+            dot_mesh: ParticleMesh::new(device, 256),
+        }
+    }
+
+    fn create_buffer<T>(device: &wgpu::Device, len: usize, usage: wgpu::BufferUsages) -> wgpu::Buffer{
+        device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some("Debug Draw Buffer"),
+                size: (len * std::mem::size_of::<T>()) as wgpu::BufferAddress,
+                usage: usage | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }
+        )
+    }
+
+    //drops everything drawn for the last frame
+    pub fn clear(&mut self){
+        self.vertices.clear();
+        self.indices.clear();
+        self.dots.clear();
+    }
+
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue){
+        if self.vertices.len() > self.capacity.0 || self.indices.len() > self.capacity.1{
+            self.capacity = (self.vertices.len().next_power_of_two(), self.indices.len().next_power_of_two());
+            self.vertex_buffer = Self::create_buffer::<LineVertex>(device, self.capacity.0, wgpu::BufferUsages::VERTEX);
+            self.index_buffer = Self::create_buffer::<u32>(device, self.capacity.1, wgpu::BufferUsages::INDEX);
+        }
+        if !self.vertices.is_empty(){
+            queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&self.vertices));
+            queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&self.indices));
+        }
+        self.num_indices = self.indices.len() as u32;
+        self.dot_mesh.update_instances(device, queue, &self.dots);
+    }
+
+    //line_pipeline from Line::create_render_pipeline, dot_pipeline from ParticleMesh::create_render_pipeline
+    pub fn draw<'a>(
+        &'a self,
+        line_pipeline: &'a wgpu::RenderPipeline,
+        dot_pipeline: &'a wgpu::RenderPipeline,
+        render_pass: &mut wgpu::RenderPass<'a>
+    ){
+        if self.num_indices > 0{
+            render_pass.set_pipeline(line_pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+        }
+        self.dot_mesh.draw(dot_pipeline, render_pass);
+    }
+}
+
+impl DebugDraw for DebugRenderer{
+    fn flags(&self) -> DebugDrawFlags{
+        self.flags
+    }
+
+    fn draw_segment(&mut self, a: Vec2, b: Vec2, color: [f32; 4]){
+        if a == b {return;}
+        let line = Line { p1: a.into(), p2: b.into(), width: self.line_width, color };
+        let (vertices, indices) = line.get_line_vertices();
+        let first = self.vertices.len() as u32;
+        self.vertices.extend(vertices);
+        self.indices.extend(indices.iter().map(|&i| first + i as u32));
+    }
+
+    fn draw_dot(&mut self, center: Vec2, radius: f32, color: [f32; 4]){
+        self.dots.push(ParticleInstanceRaw {
+            instance: Instance2DRaw { pos: [center.x, center.y, 0.], rot: [[radius, 0.], [0., radius]] },
+            color,
+        });
+    }
+
+    fn velocity_scale(&self) -> f32{
+        self.velocity_scale
+    }
+}
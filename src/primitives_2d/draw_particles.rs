@@ -0,0 +1,138 @@
+//draw particles
+//
+//all particles of a ParticleSystem in one instanced draw call
+//every instance is a unit circle scaled by the size of the particle,
+//so the rotation matrix of Instance2DRaw carries the size
+
+use crate::physics_engine::particles::Particle;
+
+use super::{draw_circle::Circle, utils::Instance2DRaw};
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ParticleInstanceRaw{
+    pub instance: Instance2DRaw,
+    pub color: [f32; 4],
+}
+
+impl ParticleInstanceRaw{
+    pub fn from_particle(particle: &Particle) -> Self{
+        let size = particle.current_size();
+        Self {
+            instance: Instance2DRaw {
+                pos: [particle.pos.x, particle.pos.y, 0.],
+                rot: [[size, 0.], [0., size]],
+            },
+            color: particle.current_color(),
+        }
+    }
+
+    //the attributes of Instance2DRaw followed by the color
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<ParticleInstanceRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 6,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 7,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<Instance2DRaw>() as wgpu::BufferAddress,
+                    shader_location: 8,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+           ],
+        }
+    }
+}
+
+pub struct ParticleMesh{
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub num_indices: u32,
+    pub instance_buffer: wgpu::Buffer,
+    //instances the buffer has room for, it grows when more particles are alive
+    capacity: usize,
+    num_instances: u32,
+}
+
+impl ParticleMesh{
+    pub fn new(device: &wgpu::Device, capacity: usize) -> Self{
+        let (vertex_buffer, index_buffer) = Circle::get_buffers(device, 0., 0., 1., [1., 1., 1., 1.]);
+        let (_, indices) = Circle::get_circle_vertices(0., 0., 1., [1., 1., 1., 1.]);
+        let capacity = capacity.max(1);
+        Self {
+            vertex_buffer, index_buffer,
+            num_indices: indices.len() as u32,
+            instance_buffer: Self::create_instance_buffer(device, capacity),
+            capacity,
+            num_instances: 0,
+        }
+    }
+
+    fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer{
+        device.create_buffer(
+            &wgpu::BufferDescriptor {
+                label: Some("Particle Instance Buffer"),
+                size: (capacity * std::mem::size_of::<ParticleInstanceRaw>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }
+        )
+    }
+
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, particles: &[Particle]){
+        let instance_data : Vec<ParticleInstanceRaw> = particles.iter().map(ParticleInstanceRaw::from_particle).collect();
+        self.update_instances(device, queue, &instance_data);
+    }
+
+    //colored circles that are not particles, e.g. the dots of draw_debug
+    pub fn update_instances(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, instance_data: &[ParticleInstanceRaw]){
+        if instance_data.len() > self.capacity{
+            self.capacity = instance_data.len().next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, self.capacity);
+        }
+        if !instance_data.is_empty(){
+            queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(instance_data));
+        }
+        self.num_instances = instance_data.len() as u32;
+    }
+
+    pub fn draw<'a>(
+        &'a self,
+        render_pipeline: &'a wgpu::RenderPipeline,
+        render_pass: &mut wgpu::RenderPass<'a>
+    ){
+        if self.num_instances == 0{return;}
+        render_pass.set_pipeline(render_pipeline);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..self.num_indices, 0, 0..self.num_instances);
+    }
+
+    pub fn create_render_pipeline(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration
+    ) -> wgpu::RenderPipeline{
+        Circle::create_instanced_pipeline(
+            device, config,
+            wgpu::include_wgsl!("particle.wgsl"),
+            ParticleInstanceRaw::desc(),
+            "Particle Render Pipeline"
+        )
+    }
+}
@@ -2,7 +2,7 @@
 //
 //
 
-use cgmath::Deg;
+use cgmath::{Deg, Rad};
 use wgpu::{VertexAttribute, util::DeviceExt};
 
 use crate::model::Vertex;
@@ -60,8 +60,8 @@ impl Rect{
     pub fn new(device: &wgpu::Device, x: f32, y: f32, w: f32, h: f32, color: [f32; 4]) -> Self{
         let (vs, is) = Self::get_buffers(device, x, y, w, h, color);
         let instance = Instance2D{
-            pos: Vec2::new(-0.5, -0.5),
-            rot: Mat2::from_angle(Deg(75.)),
+            pos: Vec2::new(x, y),
+            rot: Mat2::from_angle(Deg(0.)),
         };
         let instance_data = vec![instance.to_raw()];
         let instance_buffer = device.create_buffer_init(
@@ -82,6 +82,27 @@ impl Rect{
 
     }
 
+    //same size and placement as the physics rectangle
+    pub fn from_body(device: &wgpu::Device, body: &crate::physics_engine::rect_body::Rect, color: [f32; 4]) -> Self{
+        let mut rect = Self::new(device, body.pos.x, body.pos.y, body.w, body.h, color);
+        rect.update_from_body(device, body);
+        rect
+    }
+
+    //follows position and rotation of the body, vertices are rebuilt if its size changed
+    pub fn update_from_body(&mut self, device: &wgpu::Device, body: &crate::physics_engine::rect_body::Rect){
+        use crate::physics_engine::physic_obj_traits::TransposeObject;
+        if self.w != body.w || self.h != body.h{
+            self.w = body.w;
+            self.h = body.h;
+            let (vs, is) = Self::get_buffers(device, body.pos.x, body.pos.y, body.w, body.h, self.color);
+            self.vertex_buffer = vs;
+            self.index_buffer = is;
+        }
+        self.instance.rot = Mat2::from_angle(Rad(body.get_angle().0));
+        self.translate(device, body.pos.x, body.pos.y);
+    }
+
     pub fn get_rect_verts_inds(
         x: f32, y: f32, w: f32, h: f32, color: [f32; 4]
     ) -> (Vec<RectVertex>, Vec<u16>){
@@ -7,8 +7,10 @@ use wgpu::{util::{DeviceExt, RenderEncoder}, core::device};
 use winit::{window::Window, event::{WindowEvent, MouseButton, ElementState}};
 
 use crate::{texture, asset_manager::AssetManager, sprite::{self, Sprite},
-    primitives_2d::{draw_line::{Line, self, LineMesh}, draw_circle::{self, Circle}, draw_rect::Rect, draw_convex::Convex},
-    physics_engine::{physic_obj_traits::{CollisionType, CollisionRelation, NodeObject}, chain_body, self, physics_world::{self, World}, convex_body::{Convex2D, self}, col_relations::*, circle_body}};
+    primitives_2d::{draw_line::{Line, self, LineMesh}, draw_circle::{self, Circle}, draw_rect::Rect, draw_convex::Convex, draw_capsule, draw_particles::ParticleMesh, draw_debug::DebugRenderer},
+    physics_engine::{physic_obj_traits::{CollisionType, CollisionRelation, NodeObject, TransposeObject, BodyType}, chain_body, self, physics_world::{self, World}, convex_body::{Convex2D, self}, col_relations::*, circle_body, line_body, rect_body, capsule_body, compound_body,
+        force_field::{ForceField, Region, FieldKind, Falloff}, fluid::Fluid, geometry::Aabb, soft_body::SoftBody,
+        particles::{ParticleSystem, Emitter}, debug_draw::DebugDrawFlags}};
 
 pub struct Renderer2D{
     pub state : State,
@@ -38,6 +40,10 @@ const INDICES: &[u16] = &[
     2, 3, 4,
 ];
 
+//per step, the elevator turns around at the heights below
+const ELEVATOR_SPEED : f32 = 0.002;
+const ELEVATOR_RANGE : (f32, f32) = (-0.4, 0.3);
+
 
 
 impl Vertex {
@@ -77,11 +83,29 @@ pub struct State{
     line: draw_line::Line,
     line_past: draw_line::Line,
     line_mesh : draw_line::LineMesh,
-    chain: chain_body::Chain,
+    terrain_mesh : draw_line::LineMesh,
+    chain: usize,
     physics_engine: physics_world::World,
     circles: Vec<draw_circle::Circle>,
     circle_rp : wgpu::RenderPipeline,
     rect: Convex,
+    box_body: usize,
+    box_rect: Rect,
+    capsule_body: usize,
+    capsule: draw_capsule::Capsule,
+    ledge_body: usize,
+    ledge: Convex,
+    elevator_body: usize,
+    elevator: Rect,
+    pool: Convex,
+    blob: usize,
+    blob_outline: LineMesh,
+    //None while the outline crosses itself
+    blob_fill: Option<Convex>,
+    sparks: usize,
+    particle_mesh: ParticleMesh,
+    particle_rp: wgpu::RenderPipeline,
+    debug_draw: DebugRenderer,
     pub window : Window,
 }
 
@@ -290,7 +314,7 @@ impl State{
             [0., 1., 1., 1.]
             );
 
-        let chain = chain_body::Chain::from_coords(
+        let mut chain = chain_body::Chain::from_coords(
             mesh_coords,
             1., 0.3, 0.002
         );
@@ -313,39 +337,121 @@ impl State{
         let mut convex2d = convex_body::Convex2D::new(0.3, 0.3,
                         vec![(-0.2, 0.3), (0.2, 0.3), (0.35, 0.0), (0.2, -0.3), (-0.2, -0.3), (-0.35, 0.0)],
                        1.0);
-        //convex2d.static_body = true;
+        //convex2d.body_type = BodyType::Static;
         let mut convex2d2 = convex_body::Convex2D::new(-0.5, -0.5,
                         vec![(-0.2, 0.2), (0.2, 0.2), (0.35, 0.0), (0.2, -0.2), (-0.2, -0.2), (-0.35, 0.0)],
                        1.0);
 
 
+        //the mass of static bodies is not used
         let mut wall1 = convex_body::Convex2D::new(-1.0, 0.0,
                         vec![(-0.2, 3.0), (0.1, 3.0), (0.1, -3.0), (-0.2, -3.0)],
-                       f32::MAX);
-        wall1.static_body = true;
+                       0.);
+        wall1.body_type = BodyType::Static;
 
         let mut wall2 = convex_body::Convex2D::new(0.0, 1.0,
                         vec![(-3.0, 0.2), (3.0, 0.2), (3.0, -0.1), (-3.0, -0.1)],
-                       f32::MAX);
-        wall2.static_body = true;
+                       0.);
+        wall2.body_type = BodyType::Static;
 
         let mut wall3 = convex_body::Convex2D::new(1.0, 0.0,
                         vec![(-0.1, 3.0), (0.2, 3.0), (0.2, -3.0), (-0.1, -3.0)],
-                       f32::MAX);
-        wall3.static_body = true;
+                       0.);
+        wall3.body_type = BodyType::Static;
 
         let mut wall4 = convex_body::Convex2D::new(0.0, -1.0,
                         vec![(-3.0, 0.1), (3.0, 0.1), (3.0, -0.2), (-3.0, -0.2)],
-                       f32::MAX);
-        wall4.static_body = true;
+                       0.);
+        wall4.body_type = BodyType::Static;
 
-        physics_engine.physics_objects.insert(Box::new(wall1));
-        physics_engine.physics_objects.insert(Box::new(wall2));
-        physics_engine.physics_objects.insert(Box::new(wall3));
-        physics_engine.physics_objects.insert(Box::new(wall4));
+        physics_engine.add_phy_obj(wall1);
+        physics_engine.add_phy_obj(wall2);
+        physics_engine.add_phy_obj(wall3);
+        physics_engine.add_phy_obj(wall4);
 
-        physics_engine.physics_objects.insert(Box::new(convex2d2));
-        physics_engine.physics_objects.insert(Box::new(convex2d));
+        //terrain is built from the same points it is drawn with
+        let terrain = line_body::EdgeChain::from_coords(
+            vec![[-0.9, -0.5], [-0.5, -0.7], [-0.1, -0.65], [0.3, -0.85], [0.9, -0.6]]
+        );
+        let terrain_mesh = draw_line::LineMesh::new(
+            &device,
+            terrain.as_f32_arr_points(),
+            0.02,
+            [0.4, 0.8, 0.2, 1.]
+            );
+        physics_engine.add_phy_obj(terrain);
+
+        physics_engine.add_phy_obj(convex2d2);
+
+        let box_rect_body = rect_body::Rect::new(-0.6, 0.5, 0.2, 0.12, 0.6);
+        let box_rect = Rect::from_body(&device, &box_rect_body, [0.8, 0.6, 0.2, 1.0]);
+        let box_body = physics_engine.add_phy_obj(box_rect_body);
+
+        let mut pill = capsule_body::Capsule::new(0.6, 0.5, 0.1, 0.05, 0.5);
+        pill.set_angle(cgmath::Rad(0.4));
+        let capsule = draw_capsule::Capsule::from_body(&device, &pill, [0.3, 0.5, 0.9, 1.0]);
+        let capsule_body = physics_engine.add_phy_obj(pill);
+
+        //concave L shape, split into convex parts for the collisions
+        let ledge_outline = vec![(-0.15, -0.1), (0.15, -0.1), (0.15, -0.02), (-0.07, -0.02), (-0.07, 0.12), (-0.15, 0.12)];
+        let ledge_compound = compound_body::Compound::from_polygon(0.0, 0.6, ledge_outline, 0.8)
+            .expect("ledge outline is a simple polygon");
+        let ledge = Convex::from_polygon(&device, ledge_compound.pos.x, ledge_compound.pos.y,
+                        ledge_compound.outline.clone(), [0.7, 0.3, 0.6, 1.0])
+            .expect("ledge outline is a simple polygon");
+        let ledge_body = physics_engine.add_phy_obj(ledge_compound);
+
+        //platform moving up and down below the capsule
+        let mut elevator_rect_body = rect_body::Rect::new(0.6, 0.0, 0.3, 0.04, 0.);
+        elevator_rect_body.body_type = BodyType::Kinematic;
+        let elevator = Rect::from_body(&device, &elevator_rect_body, [0.5, 0.5, 0.5, 1.0]);
+        let elevator_body = physics_engine.add_phy_obj(elevator_rect_body);
+        physics_engine.set_kinematic_velocity(elevator_body, cgmath::Vector2::new(0., ELEVATOR_SPEED), 0.);
+        let crate_id = physics_engine.add_phy_obj(convex2d);
+
+        //pool above the terrain on the right, the box floats in it
+        let pool_aabb = Aabb { min: cgmath::Vector2::new(0.3, -0.85), max: cgmath::Vector2::new(0.95, -0.55) };
+        let pool = Convex::new(&device, 0., 0.,
+                        vec![(0.3, -0.85), (0.95, -0.85), (0.95, -0.55), (0.3, -0.55)],
+                        [0.2, 0.35, 0.8, 1.0]);
+        physics_engine.add_fluid(Fluid::from_aabb(pool_aabb, 40.));
+
+        //swirl in the upper left, strongest at its center
+        let vortex_center = cgmath::Vector2::new(-0.5, 0.3);
+        physics_engine.add_force_field(ForceField::new(
+            Region::Circle { center: vortex_center, r: 0.25 },
+            FieldKind::Vortex { center: vortex_center, strength: 0.0006, falloff: Falloff::Linear { radius: 0.25 } },
+        ));
+
+        //rope hangs from its first point and carries the crate
+        chain.pin(0);
+        let last_point = chain.points.len()-1;
+        chain.attach_to_body(last_point, crate_id, cgmath::Vector2::new(0., 0.3));
+        let chain = physics_engine.add_chain(chain);
+
+        //jelly blob falling onto the terrain in the middle
+        let blob_body = SoftBody::ring([-0.1, 0.1], 0.1, 16, 0.05, 0.015);
+        let blob_outline = draw_line::LineMesh::new(&device, blob_body.outline_coords(), 0.01, [0.9, 0.4, 0.5, 1.0]);
+        let blob_fill = Convex::from_polygon(&device, 0., 0., blob_body.outline_vertices(), [0.6, 0.2, 0.3, 1.0]).ok();
+        let blob = physics_engine.add_soft_body(blob_body);
+
+        //sparks raining down from the top, cooling from yellow to red
+        let mut sparks = ParticleSystem::new(3000);
+        let mut spark_emitter = Emitter::new(cgmath::Vector2::new(-0.2, 0.9), -std::f32::consts::FRAC_PI_2, 1.2, 4.);
+        spark_emitter.speed = (0.005, 0.02);
+        spark_emitter.lifetime = (120., 240.);
+        spark_emitter.color = [1.0, 0.9, 0.3, 1.0];
+        spark_emitter.end_color = [0.7, 0.1, 0.0, 1.0];
+        spark_emitter.size = 0.006;
+        spark_emitter.end_size = 0.002;
+        sparks.add_emitter(spark_emitter);
+        let sparks = physics_engine.add_particle_system(sparks);
+        let particle_mesh = ParticleMesh::new(&device, 3000);
+        let particle_rp = ParticleMesh::create_render_pipeline(&device, &config);
+        //the bodies have their own meshes, only velocities, contacts and joints are drawn on top
+        let debug_draw = DebugRenderer::new(&device, DebugDrawFlags {
+            velocities: true, contacts: true, joints: true, ..DebugDrawFlags::none()
+        });
 
         let rect = Convex::new(&device, 0.2, 0.2,
                         vec![(-0.2, 0.3), (0.2, 0.3), (0.35, 0.0), (0.2, -0.3), (-0.2, -0.3), (-0.35, 0.0)],
@@ -356,8 +462,14 @@ impl State{
             surface, size, config, device, queue, window,
             render_pipeline, vertex_buffer, index_buffer,num_indices,
             diffuse_bind_group, diffuse_texture, assets,
-            line, line_past, line_mesh, chain,
+            line, line_past, line_mesh, terrain_mesh, chain,
             physics_engine, circles, rect, circle_rp,
+            box_body, box_rect, capsule_body, capsule,
+            ledge_body, ledge,
+            elevator_body, elevator, pool,
+            blob, blob_outline, blob_fill,
+            sparks, particle_mesh, particle_rp,
+            debug_draw,
         }
     }
 
@@ -409,6 +521,12 @@ impl State{
         false
     }
 
+    fn move_elevator(&mut self){
+        let Some(y) = self.physics_engine.get_phy_obj(self.elevator_body).map(|p| p.get_pos().y) else {return;};
+        let vel = if y < ELEVATOR_RANGE.0 {ELEVATOR_SPEED} else if y > ELEVATOR_RANGE.1 {-ELEVATOR_SPEED} else {return;};
+        self.physics_engine.set_kinematic_velocity(self.elevator_body, cgmath::Vector2::new(0., vel), 0.);
+    }
+
     pub fn update(&mut self) {
         //self.camera_controller.update_camera(&mut self.camera);
         //self.camera_uniform.update_view_proj(&self.camera);
@@ -445,12 +563,18 @@ impl State{
             p1: [-0.5, 0.5], p2: [0.5, -0.5], width: 0.8, color: [1.0, 1.0, 0.0, 1.0]
         };
 */
-        self.chain.gravity_dir =
-            cgmath::Vector2::new(self.line.p2[0], self.line.p2[1]).normalize();
-        self.chain.simulation_step();
-        for i in 0..self.chain.points.len(){
-            self.line_mesh.points[i][0] = self.chain.points[i].x;
-            self.line_mesh.points[i][1] = self.chain.points[i].y;
+        let (vs, is) = self.line.get_buffers(&self.device);
+        let line_rp = Line::create_render_pipeline(&self.device, &self.config);
+        //let (vs2, is2) = LineMesh::
+        self.physics_engine.set_gravity_dir(
+            cgmath::Vector2::new(self.line.p2[0], self.line.p2[1]).normalize()
+        );
+        self.move_elevator();
+        self.physics_engine.simulation_step();
+
+        let chain = &self.physics_engine.chains[self.chain];
+        for i in 0..chain.points.len(){
+            self.line_mesh.points[i] = chain.points[i].as_f32_arr();
         }
         let (vs2, is2) = LineMesh::get_buffers(
             &self.device, &self.line_mesh.points,
@@ -459,13 +583,6 @@ impl State{
         self.line_mesh.vertex_buffer = vs2;
         self.line_mesh.index_buffer = is2;
 
-        let (vs, is) = self.line.get_buffers(&self.device);
-        let line_rp = Line::create_render_pipeline(&self.device, &self.config);
-        //let (vs2, is2) = LineMesh::
-        self.physics_engine.global_gravity_dir =
-            cgmath::Vector2::new(self.line.p2[0], self.line.p2[1]).normalize();
-        self.physics_engine.simulation_step();
-
         let mut cl_p_circle = draw_circle::Circle::new(&self.device, 0., 0., 0.05, [0., 1., 0., 1.]);
         let mut mouse_circle = draw_circle::Circle::new(&self.device, self.line_past.p2[0], self.line_past.p2[1], 0.05, [0.9, 0.6, 0., 1.]);
         let mut mouse_circle_col = circle_body::Circle::new(0, self.line_past.p2[0], self.line_past.p2[1], 0.05, 0.5);
@@ -497,16 +614,32 @@ impl State{
             }
         );
 
-        let rect_rp = Rect::create_render_pipeline(&self.device, &self.config);
-
-        let mut velocity_lines : Vec<draw_line::LineMesh> = Vec::with_capacity(self.physics_engine.physics_objects.len());
-
-        for pobj in self.physics_engine.physics_objects.iter(){
-            let (x1, y1) = (pobj.get_pos().x, pobj.get_pos().y);
-            let (x2, y2) = (pobj.get_pos()+pobj.get_vel()).into();
-            velocity_lines.push(draw_line::LineMesh::new(&self.device, vec![[x1, y1], [x2, y2]], 0.006, [0.6, 1.0, 0.734, 1.0]));
+        if let Some(CollisionType::Rectangle(r)) = self.physics_engine.get_phy_obj(self.box_body).map(|p| p.get_col_type()){
+            self.box_rect.update_from_body(&self.device, r);
+        }
+        if let Some(CollisionType::Capsule(c)) = self.physics_engine.get_phy_obj(self.capsule_body).map(|p| p.get_col_type()){
+            self.capsule.update_from_body(&self.device, c);
+        }
+        if let Some(CollisionType::Rectangle(r)) = self.physics_engine.get_phy_obj(self.elevator_body).map(|p| p.get_col_type()){
+            self.elevator.update_from_body(&self.device, r);
         }
+        let blob = &self.physics_engine.soft_bodies[self.blob];
+        self.blob_outline.update_mesh(&self.device, blob.outline_coords());
+        if let Some(blob_fill) = &mut self.blob_fill{
+            //a self intersecting outline hides the fill until it untangles
+            blob_fill.update_polygon(&self.device, &self.queue, blob.outline_vertices()).ok();
+        }
+        self.particle_mesh.update(&self.device, &self.queue, &self.physics_engine.particle_systems[self.sparks].particles);
+        if let Some(ledge) = self.physics_engine.get_phy_obj(self.ledge_body){
+            self.ledge.rotate(&self.device, ledge.get_angle().0);
+            self.ledge.translate(&self.device, ledge.get_pos().x, ledge.get_pos().y);
+        }
+
+        let rect_rp = Rect::create_render_pipeline(&self.device, &self.config);
 
+        self.debug_draw.clear();
+        self.physics_engine.debug_draw(&mut self.debug_draw);
+        self.debug_draw.upload(&self.device, &self.queue);
 
         {
         let mut render_pass = encoder.begin_render_pass(
@@ -539,18 +672,28 @@ impl State{
             &mut render_pass
         );
 
+        self.pool.draw(&rect_rp, &mut render_pass);
+        self.terrain_mesh.draw(&line_rp, &mut render_pass);
+
         for c in self.circles.iter(){
             c.draw(&self.circle_rp, &mut render_pass);
         }
 
         self.rect.draw(&rect_rp, &mut render_pass);
+        self.box_rect.draw(&rect_rp, &mut render_pass);
+        self.capsule.draw(&rect_rp, &mut render_pass);
+        self.ledge.draw(&rect_rp, &mut render_pass);
+        self.elevator.draw(&rect_rp, &mut render_pass);
+        if let Some(blob_fill) = &self.blob_fill{
+            blob_fill.draw(&rect_rp, &mut render_pass);
+        }
+        self.blob_outline.draw(&line_rp, &mut render_pass);
+        self.particle_mesh.draw(&self.particle_rp, &mut render_pass);
 
         cl_p_circle.draw(&self.circle_rp, &mut render_pass);
         mouse_circle.draw(&self.circle_rp, &mut render_pass);
 
-        for vel_line in velocity_lines.iter(){
-            vel_line.draw(&line_rp, &mut render_pass);
-        }
+        self.debug_draw.draw(&line_rp, &self.particle_rp, &mut render_pass);
 /*
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
@@ -581,12 +724,12 @@ impl State{
                 std::thread::sleep(Self::delta_time - elapsed_time);
             }
             else{
-                println!("delta < elapsed time");
+                log::debug!(target: "renderer::frame", "frame took {elapsed_time:?}, longer than {:?}", Self::delta_time);
                 std::thread::sleep(Self::delta_time);
             }
         }
         else{
-            println!("Duration error");
+            log::warn!(target: "renderer::frame", "system time went backwards, frame time unknown");
             std::thread::sleep(Self::delta_time);
         }
 
@@ -0,0 +1,95 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    convex_body::Convex2D,
+    rect_body::Rect,
+    line_body::EdgeChain,
+    physics_world::World,
+    physic_obj_traits::{BodyType, PhysicsObject},
+};
+
+mod common;
+use common::*;
+
+fn platform(x: f32, y: f32) -> Rect{
+    let mut platform = Rect::new(x, y, 0.4, 0.04, 0.);
+    platform.body_type = BodyType::Kinematic;
+    platform
+}
+
+#[test]
+fn only_dynamic_bodies_have_a_finite_mass(){
+    let mut rect = Rect::new(0., 0., 0.2, 0.2, 2.);
+    assert!(rect.is_dynamic());
+    assert_eq!(rect.get_inv_mass(), 0.5);
+
+    rect.body_type = BodyType::Kinematic;
+    assert!(rect.is_kinematic() && !rect.is_static());
+    assert_eq!(rect.get_inv_mass(), 0.);
+    assert_eq!(rect.get_inv_inertia(), 0.);
+
+    let terrain = EdgeChain::from_coords(vec![[-0.5, 0.], [0.5, 0.]]);
+    assert!(terrain.is_static());
+    assert_eq!(terrain.get_inv_mass(), 0.);
+}
+
+#[test]
+fn kinematic_body_follows_its_velocity(){
+    let mut world = World::new();
+    let id = world.add_phy_obj(platform(0., 0.));
+    world.set_kinematic_velocity(id, v(0.002, 0.001), 0.01);
+    for _ in 0..100{
+        world.simulation_step();
+    }
+    //no gravity and no air resistance
+    let p = world.get_phy_obj(id).unwrap();
+    assert!((p.get_pos() - v(0.2, 0.1)).x.abs() < 0.0001 && (p.get_pos() - v(0.2, 0.1)).y.abs() < 0.0001);
+    assert!((p.get_angle().0 - 1.).abs() < 0.0001);
+    assert_eq!(*p.get_vel(), v(0.002, 0.001));
+    assert!(world.is_awake(id));
+}
+
+#[test]
+fn kinematic_velocity_is_only_set_on_kinematic_bodies(){
+    let mut world = World::new();
+    let id = world.add_phy_obj(Circle::new(0, 0., 0., 0.05, 1.));
+    world.set_kinematic_velocity(id, v(0.005, 0.), 0.);
+    assert_eq!(world.get_phy_obj(id).unwrap().get_vel().x, 0.);
+}
+
+#[test]
+fn elevator_lifts_body_without_slowing_down(){
+    let mut world = World::new();
+    let lift = world.add_phy_obj(platform(0., -0.5));
+    let box_id = world.add_phy_obj(Rect::new(0., -0.42, 0.1, 0.1, 1.));
+    world.set_kinematic_velocity(lift, v(0., 0.002), 0.);
+    for _ in 0..200{
+        world.simulation_step();
+    }
+    let lift_pos = *world.get_phy_obj(lift).unwrap().get_pos();
+    let box_pos = *world.get_phy_obj(box_id).unwrap().get_pos();
+    assert!((lift_pos.y - -0.1).abs() < 0.0001, "{lift_pos:?}");
+    //the box rides on top of the platform
+    assert!(box_pos.y > lift_pos.y + 0.06, "{box_pos:?} {lift_pos:?}");
+    assert!(world.is_awake(box_id));
+}
+
+#[test]
+fn kinematic_body_ignores_hits_and_static_bodies(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., 0.));
+    let mut wall = Convex2D::new(0.1, 0., vec![(-0.02, 0.3), (0.02, 0.3), (0.02, -0.3), (-0.02, -0.3)], 0.);
+    wall.body_type = BodyType::Static;
+    world.add_phy_obj(wall);
+    let mover = world.add_phy_obj(platform(0., 0.));
+    let ball = world.add_phy_obj(Circle::new(0, -0.05, 0.2, 0.05, 10.));
+    world.set_kinematic_velocity(mover, v(0.001, 0.), 0.);
+    world.apply_impulse(ball, v(0., -0.05));
+    for _ in 0..100{
+        world.simulation_step();
+    }
+    //passed through the wall and was not pushed by the heavy ball
+    let p = world.get_phy_obj(mover).unwrap();
+    assert!((p.get_pos().x - 0.1).abs() < 0.0001 && p.get_pos().y == 0.);
+    assert_eq!(*p.get_vel(), v(0.001, 0.));
+    assert!(world.get_phy_obj(ball).unwrap().get_pos().y > 0.);
+}
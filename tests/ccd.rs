@@ -0,0 +1,65 @@
+use wgpu_tutorial::physics_engine::{
+    ccd::{time_of_impact, CCD_TARGET},
+    gjk::ConvexProxy,
+    circle_body::Circle,
+    convex_body::Convex2D,
+    physics_world::World,
+    physic_obj_traits::{BodyType, ForceObject},
+};
+
+mod common;
+use common::*;
+
+//thin static wall standing at x
+fn wall(x: f32) -> Convex2D{
+    let mut wall = Convex2D::new(x, 0., vec![(-0.01, 0.5), (0.01, 0.5), (0.01, -0.5), (-0.01, -0.5)], 0.);
+    wall.body_type = BodyType::Static;
+    wall
+}
+
+#[test]
+fn impact_with_wall_ahead(){
+    let bullet = ConvexProxy::point(v(0., 0.), 0.1);
+    let target = ConvexProxy::new(square(1., 0., 0.1), 0.);
+    let toi = time_of_impact(&bullet, &target, v(2., 0.)).expect("bullet reaches the square");
+    //stops CCD_TARGET in front of the face at x = 0.9
+    let expected = (0.9 - 0.1 - CCD_TARGET) / 2.;
+    assert!((toi - expected).abs() < 0.001, "{toi} != {expected}");
+}
+
+#[test]
+fn no_impact_when_missing_or_too_short(){
+    let bullet = ConvexProxy::point(v(0., 0.), 0.1);
+    let target = ConvexProxy::new(square(1., 0., 0.1), 0.);
+    assert!(time_of_impact(&bullet, &target, v(0., 2.)).is_none());
+    assert!(time_of_impact(&bullet, &target, v(0.5, 0.)).is_none());
+}
+
+#[test]
+fn bullet_does_not_tunnel_through_wall(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., 0.));
+    world.add_phy_obj(wall(0.5));
+    let mut circle = Circle::new(0, 0., 0., 0.02, 1.);
+    circle.bullet = true;
+    *circle.get_vel_mut() = v(0.3, 0.);
+    let id = world.add_phy_obj(circle);
+
+    for _ in 0..5{
+        world.simulation_step();
+        let bullet = world.get_phy_obj(id).unwrap();
+        assert!(bullet.get_pos().x < 0.49 - 0.02 + TOL, "bullet passed the wall at {:?}", bullet.get_pos());
+    }
+    assert!(world.get_phy_obj(id).unwrap().get_vel().x < 0., "bullet bounces back");
+}
+
+#[test]
+fn fast_body_without_bullet_flag_is_clamped(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., 0.));
+    let mut circle = Circle::new(0, 0., 0., 0.02, 1.);
+    *circle.get_vel_mut() = v(0.3, 0.);
+    let id = world.add_phy_obj(circle);
+    world.simulation_step();
+    assert!(world.get_phy_obj(id).unwrap().get_pos().x <= 0.0101);
+}
@@ -0,0 +1,153 @@
+use wgpu_tutorial::physics_engine::{
+    character::CharacterController,
+    convex_body::Convex2D,
+    gjk::ConvexProxy,
+    line_body::EdgeChain,
+    rect_body::Rect,
+    physics_world::World,
+    physic_obj_traits::BodyType,
+};
+
+mod common;
+use common::*;
+
+const GRAVITY : f32 = 0.0009;
+const RADIUS : f32 = 0.03;
+const HALF_HEIGHT : f32 = 0.03;
+//from the center to the bottom of the capsule
+const FEET : f32 = RADIUS + HALF_HEIGHT;
+
+//terrain drawn from left to right, solid from above
+fn world_with_ground(points: Vec<[f32; 2]>) -> World{
+    let mut world = World::new();
+    world.add_phy_obj(EdgeChain::from_coords(points));
+    world
+}
+
+//walks with speed dx per step under gravity, a jump sets the upward velocity
+fn walk(character: &mut CharacterController, world: &World, dx: f32, steps: usize, jump: f32){
+    let mut vy = jump;
+    for _ in 0..steps{
+        vy -= GRAVITY;
+        character.move_by(world, v(dx, vy));
+        if (character.is_grounded() && vy < 0.) || (character.state().ceiling && vy > 0.){
+            vy = 0.;
+        }
+    }
+}
+
+#[test]
+fn shape_casts_stop_in_front_of_bodies(){
+    let mut world = World::new();
+    let mut floor = Convex2D::new(0., -0.6, vec![(-1., -0.1), (1., -0.1), (1., 0.1), (-1., 0.1)], 0.);
+    floor.body_type = BodyType::Static;
+    let floor = world.add_phy_obj(floor);
+    let ball = ConvexProxy::point(v(0., 0.), 0.1);
+
+    let (id, hit) = world.cast_shape(&ball, v(0., -1.), &[]).unwrap();
+    assert_eq!(id, floor);
+    assert!((hit.fraction - 0.399).abs() < 0.001);
+    assert!((hit.normal - v(0., 1.)).y.abs() < 0.0001);
+    assert!((hit.point.y - -0.5).abs() < 0.001);
+    assert!(world.cast_shape(&ball, v(0., -0.2), &[]).is_none());
+    assert!(world.cast_shape(&ball, v(0., -1.), &[floor]).is_none());
+}
+
+#[test]
+fn lands_on_the_ground(){
+    let world = world_with_ground(vec![[-1., -0.5], [1., -0.5]]);
+    let mut character = CharacterController::new(0., 0., RADIUS, HALF_HEIGHT);
+    walk(&mut character, &world, 0., 100, 0.);
+    assert!(character.is_grounded());
+    assert_eq!(character.state().ground_body, Some(0));
+    assert!((character.pos.y - FEET - -0.5).abs() < 0.002);
+    assert!(!character.touching_wall());
+}
+
+#[test]
+fn climbs_gentle_slopes_and_is_stopped_by_steep_ones(){
+    //30 degrees up, flat, then 60 degrees up
+    let world = world_with_ground(vec![[-1., -0.5], [-0.5, -0.5], [-0.3, -0.3845], [0.1, -0.3845], [0.2, -0.2113], [1., -0.2113]]);
+    let mut character = CharacterController::new(-0.8, -0.5 + FEET + 0.001, RADIUS, HALF_HEIGHT);
+    walk(&mut character, &world, 0.005, 200, 0.);
+    assert!(character.is_grounded());
+    assert!(character.touching_wall());
+    //on the flat part between both slopes
+    assert!((character.pos.y - FEET - -0.3845).abs() < 0.005, "{:?}", character.pos);
+    assert!(character.pos.x < 0.2);
+}
+
+#[test]
+fn steps_up_low_ledges_only(){
+    let low = world_with_ground(vec![[-1., -0.5], [0., -0.5], [0., -0.49], [1., -0.49]]);
+    let mut character = CharacterController::new(-0.3, -0.5 + FEET + 0.001, RADIUS, HALF_HEIGHT);
+    walk(&mut character, &low, 0.005, 100, 0.);
+    assert!(character.pos.x > 0.1);
+    assert!(character.is_grounded() && !character.touching_wall());
+
+    let high = world_with_ground(vec![[-1., -0.5], [0., -0.5], [0., -0.45], [1., -0.45]]);
+    let mut character = CharacterController::new(-0.3, -0.5 + FEET + 0.001, RADIUS, HALF_HEIGHT);
+    walk(&mut character, &high, 0.005, 100, 0.);
+    assert!(character.pos.x < 0.);
+    assert_eq!(character.state().wall_normal.map(|n| n.x < -0.99), Some(true));
+}
+
+#[test]
+fn keeps_the_ground_walking_down_slopes(){
+    let world = world_with_ground(vec![[-1., -0.2], [-0.5, -0.2], [0.5, -0.6], [1., -0.6]]);
+    let mut character = CharacterController::new(-0.6, -0.2 + FEET + 0.001, RADIUS, HALF_HEIGHT);
+    walk(&mut character, &world, 0., 5, 0.);
+    for _ in 0..150{
+        character.move_by(&world, v(0.005, -GRAVITY));
+        assert!(character.is_grounded(), "{:?}", character.pos);
+    }
+    assert!(character.pos.x > 0.1);
+}
+
+#[test]
+fn jumps_through_one_way_platforms(){
+    let mut world = world_with_ground(vec![[-1., -0.5], [1., -0.5]]);
+    let mut platform = EdgeChain::from_coords(vec![[-0.3, -0.3], [0.3, -0.3]]);
+    platform.one_sided = true;
+    let platform = world.add_phy_obj(platform);
+    let mut character = CharacterController::new(0., -0.5 + FEET + 0.001, RADIUS, HALF_HEIGHT);
+    walk(&mut character, &world, 0., 5, 0.);
+    walk(&mut character, &world, 0., 100, 0.03);
+    assert!(character.is_grounded());
+    assert_eq!(character.state().ground_body, Some(platform));
+    assert!((character.pos.y - FEET - -0.3).abs() < 0.002);
+}
+
+#[test]
+fn bumps_into_ceilings(){
+    let mut world = world_with_ground(vec![[-1., -0.5], [1., -0.5]]);
+    let mut ceiling = Rect::new(0., -0.3, 0.6, 0.1, 0.);
+    ceiling.body_type = BodyType::Static;
+    world.add_phy_obj(ceiling);
+    let mut character = CharacterController::new(0., -0.5 + FEET + 0.001, RADIUS, HALF_HEIGHT);
+    let mut hit_ceiling = false;
+    let mut vy = 0.03;
+    for _ in 0..20{
+        character.move_by(&world, v(0., vy));
+        hit_ceiling |= character.state().ceiling;
+        vy -= GRAVITY;
+    }
+    assert!(hit_ceiling);
+    assert!(character.pos.y + FEET < -0.35 + 0.0001);
+}
+
+#[test]
+fn ignored_bodies_are_passed(){
+    let mut world = world_with_ground(vec![[-1., -0.5], [1., -0.5]]);
+    let mut crate_body = Rect::new(0.2, -0.4, 0.1, 0.2, 1.);
+    crate_body.body_type = BodyType::Kinematic;
+    let crate_id = world.add_phy_obj(crate_body);
+    let mut character = CharacterController::new(0., -0.5 + FEET + 0.001, RADIUS, HALF_HEIGHT);
+    walk(&mut character, &world, 0.005, 60, 0.);
+    assert!(character.touching_wall());
+    assert!(character.pos.x < 0.15 - RADIUS + 0.0001);
+
+    character.ignore.push(crate_id);
+    walk(&mut character, &world, 0.005, 60, 0.);
+    assert!(character.pos.x > 0.3);
+}
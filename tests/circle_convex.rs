@@ -0,0 +1,69 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    convex_body::Convex2D,
+    col_relations::circle_polygon_contact,
+    physic_obj_traits::{CollisionRelation, TransposeObject},
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn face_contact(){
+    let c = circle_polygon_contact(v(0.2, 0.7), 0.3, &square(0., 0., 0.5)).expect("circle touches top face");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.1).abs() < TOL);
+    assert_close(c.point, v(0.2, 0.5));
+}
+
+#[test]
+fn vertex_contact(){
+    let center = v(0.6, 0.6);
+    let c = circle_polygon_contact(center, 0.2, &square(0., 0., 0.5)).expect("circle touches corner");
+    let expected_normal = (v(0.5, 0.5) - center) / (0.02f32).sqrt();
+    assert_close(c.normal, expected_normal);
+    assert!((c.depth - (0.2 - (0.02f32).sqrt())).abs() < TOL);
+    assert_close(c.point, v(0.5, 0.5));
+}
+
+#[test]
+fn vertex_region_without_contact(){
+    //within the radius of both faces lines, but not of the corner
+    assert!(circle_polygon_contact(v(0.7, 0.7), 0.25, &square(0., 0., 0.5)).is_none());
+}
+
+#[test]
+fn separated_circle(){
+    assert!(circle_polygon_contact(v(0., 1.2), 0.3, &square(0., 0., 0.5)).is_none());
+}
+
+#[test]
+fn center_inside_polygon(){
+    let c = circle_polygon_contact(v(0.3, 0.), 0.1, &square(0., 0., 0.5)).expect("circle is inside");
+    assert_close(c.normal, v(-1., 0.));
+    assert!((c.depth - 0.3).abs() < TOL);
+    assert_close(c.point, v(0.5, 0.));
+}
+
+#[test]
+fn clockwise_winding(){
+    let mut verts = square(0., 0., 0.5);
+    verts.reverse();
+    let c = circle_polygon_contact(v(0.2, 0.7), 0.3, &verts).expect("circle touches top face");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.1).abs() < TOL);
+    let c = circle_polygon_contact(v(0.3, 0.), 0.1, &verts).expect("circle is inside");
+    assert_close(c.normal, v(-1., 0.));
+}
+
+#[test]
+fn rotated_convex_body(){
+    let mut convex = Convex2D::new(0., 0., vec![(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)], 1.);
+    convex.set_angle(cgmath::Rad(std::f32::consts::FRAC_PI_4));
+    //corner of the rotated square points up to (0, 0.707)
+    let circle = Circle::new(0, 0., 0.8, 0.1, 1.);
+    let c = Circle::get_contact(&circle, &convex).expect("circle touches rotated corner");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - (0.1 - (0.8 - 0.5f32.sqrt()))).abs() < TOL);
+    assert!(Circle::check_col(&circle, &convex));
+}
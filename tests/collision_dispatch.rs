@@ -0,0 +1,107 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    rect_body::Rect,
+    collision_dispatch::{CollisionDispatcher, ShapeKind},
+    physic_obj_traits::{CollisionType, CollisionObject, Contact},
+};
+
+mod common;
+use common::*;
+
+//axis aligned half plane below y
+struct Floor{
+    y: f32,
+}
+
+const FLOOR : u32 = 1;
+
+fn floor_circle(a: &CollisionType, b: &CollisionType) -> Option<Contact>{
+    match (a, b){
+        (CollisionType::Custom { shape, .. }, CollisionType::Circle(c)) => {
+            let floor = shape.downcast_ref::<Floor>()?;
+            let depth = floor.y - (c.pos.y - c.r);
+            if depth <= 0.{
+                return None;
+            }
+            Some(Contact { normal: Vec2::new(0., 1.), depth, point: Vec2::new(c.pos.x, floor.y) })
+        },
+        _ => None,
+    }
+}
+
+#[test]
+fn circles_in_both_orders(){
+    let dispatcher = CollisionDispatcher::new();
+    let c1 = Circle::new(0, 0., 0., 0.1, 1.);
+    let c2 = Circle::new(1, 0.15, 0., 0.1, 1.);
+    let contact = dispatcher.get_contact(&c1.get_col_type(), &c2.get_col_type()).expect("circles overlap");
+    assert_close(contact.normal, Vec2::new(1., 0.));
+    assert!((contact.depth - 0.05).abs() < TOL);
+    let contact = dispatcher.get_contact(&c2.get_col_type(), &c1.get_col_type()).expect("circles overlap");
+    assert_close(contact.normal, Vec2::new(-1., 0.));
+}
+
+#[test]
+fn swapped_pair_gets_flipped_normal(){
+    let dispatcher = CollisionDispatcher::new();
+    let rect = Rect::new(0., 0., 0.4, 0.2, 1.);
+    let circle = Circle::new(0, 0., 0.15, 0.1, 1.);
+    let rect_first = dispatcher.get_contact(&rect.get_col_type(), &circle.get_col_type()).expect("shapes overlap");
+    let circle_first = dispatcher.get_contact(&circle.get_col_type(), &rect.get_col_type()).expect("shapes overlap");
+    assert_close(rect_first.normal, Vec2::new(0., 1.));
+    assert_close(circle_first.normal, -rect_first.normal);
+    assert!((rect_first.depth - circle_first.depth).abs() < TOL);
+}
+
+#[test]
+fn separated_shapes_have_no_contact(){
+    let dispatcher = CollisionDispatcher::new();
+    let rect = Rect::new(0., 0., 0.4, 0.2, 1.);
+    let circle = Circle::new(0, 0., 0.5, 0.1, 1.);
+    assert!(dispatcher.get_contact(&rect.get_col_type(), &circle.get_col_type()).is_none());
+}
+
+#[test]
+fn empty_dispatcher_knows_no_pairs(){
+    let dispatcher = CollisionDispatcher::empty();
+    let c1 = Circle::new(0, 0., 0., 0.1, 1.);
+    let c2 = Circle::new(1, 0.05, 0., 0.1, 1.);
+    assert!(!dispatcher.handles(ShapeKind::Circle, ShapeKind::Circle));
+    assert!(dispatcher.get_contact(&c1.get_col_type(), &c2.get_col_type()).is_none());
+}
+
+#[test]
+fn no_collision_is_never_dispatched(){
+    let dispatcher = CollisionDispatcher::new();
+    let circle = Circle::new(0, 0., 0., 0.1, 1.);
+    assert!(dispatcher.get_contact(&CollisionType::NoCollision, &circle.get_col_type()).is_none());
+}
+
+#[test]
+fn custom_shape_in_both_orders(){
+    let mut dispatcher = CollisionDispatcher::new();
+    dispatcher.register(ShapeKind::Custom(FLOOR), ShapeKind::Circle, floor_circle);
+    assert!(dispatcher.handles(ShapeKind::Circle, ShapeKind::Custom(FLOOR)));
+
+    let floor = Floor { y: 0. };
+    let floor_type = CollisionType::Custom { kind: FLOOR, shape: &floor };
+    let circle = Circle::new(0, 0.3, 0.05, 0.1, 1.);
+
+    let contact = dispatcher.get_contact(&floor_type, &circle.get_col_type()).expect("circle touches floor");
+    assert_close(contact.normal, Vec2::new(0., 1.));
+    assert!((contact.depth - 0.05).abs() < TOL);
+    assert_close(contact.point, Vec2::new(0.3, 0.));
+
+    let contact = dispatcher.get_contact(&circle.get_col_type(), &floor_type).expect("circle touches floor");
+    assert_close(contact.normal, Vec2::new(0., -1.));
+}
+
+#[test]
+fn unregistered_pair_is_ignored(){
+    let mut dispatcher = CollisionDispatcher::new();
+    dispatcher.unregister(ShapeKind::Circle, ShapeKind::Rectangle);
+    assert!(!dispatcher.handles(ShapeKind::Rectangle, ShapeKind::Circle));
+    let rect = Rect::new(0., 0., 0.4, 0.2, 1.);
+    let circle = Circle::new(0, 0., 0.15, 0.1, 1.);
+    assert!(dispatcher.get_contact(&circle.get_col_type(), &rect.get_col_type()).is_none());
+}
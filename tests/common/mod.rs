@@ -0,0 +1,48 @@
+//common
+//
+//fixtures shared by the integration tests, every test crate only uses some of them
+#![allow(dead_code)]
+
+use wgpu_tutorial::physics_engine::{
+    convex_body::Convex2D,
+    physics_world::World,
+    physic_obj_traits::BodyType,
+};
+
+pub type Vec2 = cgmath::Vector2<f32>;
+
+pub const TOL : f32 = 0.0001;
+
+pub fn v(x: f32, y: f32) -> Vec2{
+    Vec2::new(x, y)
+}
+
+pub fn assert_close(a: Vec2, b: Vec2){
+    assert!((a.x - b.x).abs() < TOL && (a.y - b.y).abs() < TOL, "{:?} != {:?}", a, b);
+}
+
+//counter clockwise square around (x, y) with half side length h
+pub fn square(x: f32, y: f32, h: f32) -> Vec<Vec2>{
+    vec![v(x-h, y-h), v(x+h, y-h), v(x+h, y+h), v(x-h, y+h)]
+}
+
+//static floor with its top at y = -0.7, the gravity of the world is left as it is
+pub fn add_floor(world: &mut World) -> usize{
+    let mut floor = Convex2D::new(0.0, -0.8, vec![(-1.0, -0.1), (1.0, -0.1), (1.0, 0.1), (-1.0, 0.1)], 0.);
+    floor.body_type = BodyType::Static;
+    world.add_phy_obj(floor)
+}
+
+//floor and gravity pointing down with unit length
+pub fn world_with_floor() -> World{
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., -1.));
+    add_floor(&mut world);
+    world
+}
+
+pub fn steps(world: &mut World, n: usize){
+    for _ in 0..n{
+        world.simulation_step();
+    }
+}
@@ -0,0 +1,63 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    compound_body::Compound,
+    collision_dispatch::{CollisionDispatcher, ShapeKind},
+    physic_obj_traits::{CollisionObject, TransposeObject},
+};
+
+mod common;
+use common::*;
+
+//L shape with its notch in the upper right, area 3
+fn l_outline() -> Vec<(f32, f32)>{
+    vec![(0., 0.), (2., 0.), (2., 1.), (1., 1.), (1., 2.), (0., 2.)]
+}
+
+#[test]
+fn body_is_placed_at_center_of_mass(){
+    let l = Compound::from_polygon(1., 1., l_outline(), 3.).unwrap();
+    assert!((l.pos.x - (1. + 5./6.)).abs() < TOL && (l.pos.y - (1. + 5./6.)).abs() < TOL);
+    assert_eq!(l.parts().len(), 2);
+    assert_eq!(l.outline.len(), 6);
+}
+
+#[test]
+fn circle_in_the_notch_touches_the_inner_corner(){
+    let dispatcher = CollisionDispatcher::new();
+    assert!(dispatcher.handles(ShapeKind::Compound, ShapeKind::Circle));
+    let l = Compound::from_polygon(0., 0., l_outline(), 3.).unwrap();
+
+    //resting on the lower arm
+    let circle = Circle::new(0, 1.5, 1.2, 0.3, 1.);
+    let c = dispatcher.get_contact(&l.get_col_type(), &circle.get_col_type()).expect("circle rests on the arm");
+    assert!(c.normal.x.abs() < TOL && (c.normal.y - 1.).abs() < TOL);
+    assert!((c.depth - 0.1).abs() < TOL);
+
+    //same contact seen from the circle
+    let flipped = dispatcher.get_contact(&circle.get_col_type(), &l.get_col_type()).unwrap();
+    assert!((flipped.normal.y + 1.).abs() < TOL);
+
+    //the notch itself is empty
+    let circle = Circle::new(0, 1.5, 1.5, 0.2, 1.);
+    assert!(dispatcher.get_contact(&l.get_col_type(), &circle.get_col_type()).is_none());
+}
+
+#[test]
+fn rotated_compound(){
+    let dispatcher = CollisionDispatcher::new();
+    let mut l = Compound::from_polygon(0., 0., l_outline(), 3.).unwrap();
+    l.set_angle(cgmath::Rad(std::f32::consts::PI));
+    //after half a turn the notch lies lower left of the center
+    let center = l.pos;
+    let notch = Circle::new(0, 2. * center.x - 1.5, 2. * center.y - 1.5, 0.2, 1.);
+    assert!(dispatcher.get_contact(&l.get_col_type(), &notch.get_col_type()).is_none());
+    let arm = Circle::new(0, 2. * center.x - 0.5, 2. * center.y - 0.5, 0.2, 1.);
+    assert!(dispatcher.get_contact(&l.get_col_type(), &arm.get_col_type()).is_some());
+}
+
+#[test]
+fn concave_parts_are_validated(){
+    let concave = vec![(0., 0.), (2., 0.), (1., 0.5), (2., 1.), (0., 1.)];
+    assert!(Compound::new(0., 0., vec![concave.clone()], 1.).is_err());
+    assert_eq!(Compound::from_polygon(0., 0., concave, 1.).unwrap().parts().len(), 2);
+}
@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+
+use cgmath::InnerSpace;
+use wgpu_tutorial::physics_engine::{
+    convex_body::Convex2D,
+    compound_body::Compound,
+    collision_dispatch::CollisionDispatcher,
+    physic_obj_traits::{CollisionObject, CollisionRelation, TransposeObject},
+};
+
+mod common;
+use common::*;
+
+fn hexagon(x: f32, y: f32) -> Convex2D{
+    //clockwise like the bodies of the demo scene
+    Convex2D::new(x, y, vec![(-0.2, 0.3), (0.2, 0.3), (0.35, 0.0), (0.2, -0.3), (-0.2, -0.3), (-0.35, 0.0)], 1.)
+}
+
+#[test]
+fn cache_matches_transformed_vertices(){
+    let mut convex = hexagon(0.3, -0.2);
+    convex.set_angle(cgmath::Rad(0.7));
+    convex.update_cache();
+    assert!(matches!(convex.world(), Cow::Borrowed(_)));
+
+    let world = convex.world();
+    let rot = cgmath::Matrix2::from_angle(cgmath::Rad(0.7));
+    for (i, v) in world.vertices.iter().enumerate(){
+        let expected : Vec2 = convex.transformed_vertex(&rot, i).into();
+        assert!((v - expected).magnitude() < TOL);
+    }
+    //outward normals point away from the center
+    for (v, n) in world.vertices.iter().zip(world.normals.iter()){
+        assert!((v - convex.pos).dot(*n) > 0.);
+        assert!((n.magnitude() - 1.).abs() < TOL);
+    }
+    for v in world.vertices.iter(){
+        assert!(world.aabb.min.x <= v.x && v.x <= world.aabb.max.x);
+        assert!(world.aabb.min.y <= v.y && v.y <= world.aabb.max.y);
+    }
+}
+
+#[test]
+fn moved_body_is_not_read_from_a_stale_cache(){
+    let mut convex = hexagon(0., 0.);
+    convex.pos = Vec2::new(0.5, 0.);
+    let world = convex.world();
+    assert!(matches!(world, Cow::Owned(_)));
+    assert!((world.aabb.min.x - 0.15).abs() < TOL);
+
+    //the contact uses the new position as well
+    let other = hexagon(1.1, 0.);
+    assert!(Convex2D::get_contact(&convex, &other).is_some());
+}
+
+#[test]
+fn bounding_boxes_skip_distant_pairs(){
+    let dispatcher = CollisionDispatcher::new();
+    let a = hexagon(0., 0.);
+    let far = hexagon(0.8, 0.);
+    assert!(!a.aabb().overlaps(&far.aabb()));
+    assert!(dispatcher.get_contact(&a.get_col_type(), &far.get_col_type()).is_none());
+
+    //boxes overlap, but the hexagons only touch with their corners cut off
+    let diagonal = hexagon(0.6, 0.55);
+    assert!(a.aabb().overlaps(&diagonal.aabb()));
+    assert!(dispatcher.get_contact(&a.get_col_type(), &diagonal.get_col_type()).is_none());
+
+    let close = hexagon(0.6, 0.);
+    let c = dispatcher.get_contact(&a.get_col_type(), &close.get_col_type()).expect("hexagons overlap");
+    assert!(c.normal.x > 0. && c.depth > 0.);
+}
+
+#[test]
+fn changed_vertices_are_not_read_from_a_stale_cache(){
+    let mut convex = hexagon(0., 0.);
+    convex.update_cache();
+    let before = convex.world().vertices[2];
+
+    //same pose and vertex count, only the vertex moved
+    convex.vertices_mut()[2] = (0.5, 0.);
+    assert!(matches!(convex.world(), Cow::Owned(_)));
+    assert!((convex.world().vertices[2] - Vec2::new(0.5, 0.)).magnitude() < TOL);
+    assert!((convex.aabb().max.x - 0.5).abs() < TOL);
+
+    convex.update_cache();
+    assert!(matches!(convex.world(), Cow::Borrowed(_)));
+    assert!((convex.world().vertices[2] - before).magnitude() > 0.1);
+}
+
+#[test]
+fn changed_compound_parts_are_not_read_from_a_stale_cache(){
+    let square = |x: f32| vec![(x - 0.1, -0.1), (x + 0.1, -0.1), (x + 0.1, 0.1), (x - 0.1, 0.1)];
+    let mut compound = Compound::new(0., 0., vec![square(-0.1), square(0.1)], 1.).unwrap();
+    compound.update_cache();
+    assert!((compound.aabb().max.x - 0.2).abs() < TOL);
+
+    compound.parts_mut()[1] = square(0.4);
+    assert!((compound.aabb().max.x - 0.5).abs() < TOL);
+    compound.update_cache();
+    assert!((compound.aabb().max.x - 0.5).abs() < TOL);
+    assert!(matches!(compound.world_parts(), Cow::Borrowed(_)));
+}
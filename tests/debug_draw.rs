@@ -0,0 +1,154 @@
+use wgpu_tutorial::physics_engine::{
+    chain_body::Chain,
+    circle_body::Circle,
+    convex_body::Convex2D,
+    line_body::EdgeChain,
+    physics_world::World,
+    physic_obj_traits::BodyType,
+    debug_draw::*,
+};
+
+mod common;
+use common::*;
+
+//keeps everything it is asked to draw
+struct Recorder{
+    flags: DebugDrawFlags,
+    segments: Vec<(Vec2, Vec2, [f32; 4])>,
+    dots: Vec<(Vec2, [f32; 4])>,
+}
+
+impl Recorder{
+    fn new(flags: DebugDrawFlags) -> Self{
+        Self { flags, segments: vec![], dots: vec![] }
+    }
+
+    fn segments_colored(&self, color: [f32; 4]) -> usize{
+        self.segments.iter().filter(|s| s.2 == color).count()
+    }
+}
+
+impl DebugDraw for Recorder{
+    fn flags(&self) -> DebugDrawFlags{
+        self.flags
+    }
+
+    fn draw_segment(&mut self, a: Vec2, b: Vec2, color: [f32; 4]){
+        self.segments.push((a, b, color));
+    }
+
+    fn draw_dot(&mut self, center: Vec2, _radius: f32, color: [f32; 4]){
+        self.dots.push((center, color));
+    }
+}
+
+fn close(a: Vec2, b: Vec2) -> bool{
+    (a.x - b.x).abs() < 0.00001 && (a.y - b.y).abs() < 0.00001
+}
+
+fn only(set: impl Fn(&mut DebugDrawFlags)) -> DebugDrawFlags{
+    let mut flags = DebugDrawFlags::none();
+    set(&mut flags);
+    flags
+}
+
+fn floor() -> Convex2D{
+    let mut floor = Convex2D::new(0., -0.8, vec![(-1., -0.1), (1., -0.1), (1., 0.1), (-1., 0.1)], 0.);
+    floor.body_type = BodyType::Static;
+    floor
+}
+
+fn draw(world: &World, flags: DebugDrawFlags) -> Recorder{
+    let mut recorder = Recorder::new(flags);
+    world.debug_draw(&mut recorder);
+    recorder
+}
+
+#[test]
+fn nothing_is_drawn_without_flags(){
+    let mut world = World::new();
+    world.add_phy_obj(floor());
+    world.add_phy_obj(Circle::new(0, 0., 0., 0.05, 0.1));
+    world.simulation_step();
+    let recorder = draw(&world, DebugDrawFlags::none());
+    assert!(recorder.segments.is_empty() && recorder.dots.is_empty());
+}
+
+#[test]
+fn shapes_are_colored_by_body_type(){
+    let mut world = World::new();
+    world.add_phy_obj(floor());
+    world.add_phy_obj(Circle::new(0, 0., 0., 0.05, 0.1));
+    world.add_phy_obj(EdgeChain::from_coords(vec![[-0.5, 0.5], [0., 0.4], [0.5, 0.5]]));
+    let recorder = draw(&world, only(|f| f.shapes = true));
+    assert_eq!(recorder.segments_colored(STATIC_COLOR), 4 + 2);
+    //outline and the radius line
+    assert_eq!(recorder.segments_colored(DYNAMIC_COLOR), CIRCLE_SEGMENTS + 1);
+    assert!(recorder.dots.is_empty());
+}
+
+#[test]
+fn sleeping_bodies_are_grey(){
+    let mut world = World::new();
+    world.add_phy_obj(floor());
+    let ball = world.add_phy_obj(Circle::new(0, 0., -0.6, 0.05, 0.1));
+    for _ in 0..2000{
+        if !world.is_awake(ball){break;}
+        world.simulation_step();
+    }
+    assert!(!world.is_awake(ball));
+    let recorder = draw(&world, only(|f| {f.shapes = true; f.sleep_state = true;}));
+    assert_eq!(recorder.segments_colored(SLEEPING_COLOR), CIRCLE_SEGMENTS + 1);
+    let recorder = draw(&world, only(|f| f.shapes = true));
+    assert_eq!(recorder.segments_colored(DYNAMIC_COLOR), CIRCLE_SEGMENTS + 1);
+}
+
+#[test]
+fn contacts_of_the_last_step(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., -1.));
+    world.add_phy_obj(floor());
+    world.add_phy_obj(Circle::new(0, 0., -0.65, 0.05, 0.1));
+    for _ in 0..20{
+        world.simulation_step();
+    }
+    assert!(!world.contacts().is_empty());
+    let recorder = draw(&world, only(|f| f.contacts = true));
+    assert_eq!(recorder.dots.len(), world.contacts().len());
+    let (point, _) = recorder.dots[0];
+    assert!((point.y - -0.7).abs() < 0.01, "{point:?}");
+    let (a, b, color) = recorder.segments[0];
+    assert_eq!(color, CONTACT_COLOR);
+    assert!(((b - a).y.abs() - NORMAL_LENGTH).abs() < 0.0001);
+}
+
+#[test]
+fn boxes_centers_and_velocities(){
+    let mut world = World::new();
+    let ball = world.add_phy_obj(Circle::new(0, 0.2, 0.1, 0.05, 0.1));
+    *world.get_phy_obj_mut(ball).unwrap().get_vel_mut() = v(0.01, 0.);
+    let recorder = draw(&world, only(|f| {f.aabbs = true; f.center_of_mass = true; f.velocities = true;}));
+    let corners : Vec<Vec2> = recorder.segments.iter().filter(|s| s.2 == AABB_COLOR).map(|s| s.0).collect();
+    assert_eq!(corners, vec![v(0.15, 0.05), v(0.25, 0.05), v(0.25, 0.15), v(0.15, 0.15)]);
+    assert_eq!(recorder.dots, vec![(v(0.2, 0.1), CENTER_COLOR)]);
+    let velocity = recorder.segments.iter().find(|s| s.2 == VELOCITY_COLOR).unwrap();
+    assert!(close(velocity.0, v(0.2, 0.1)) && close(velocity.1, v(0.21, 0.1)));
+}
+
+#[test]
+fn chain_attachments_are_joints(){
+    let mut world = World::new();
+    let ball = world.add_phy_obj(Circle::new(0, 0.5, 0.3, 0.05, 0.1));
+    let mut rope = Chain::from_coords(vec![[0.5, 0.5], [0.5, 0.4]], 0.01, 0.1, 0.01);
+    rope.pin(0);
+    rope.attach_to_body(1, ball, v(0., 0.05));
+    world.add_chain(rope);
+    let recorder = draw(&world, only(|f| f.joints = true));
+    //the pinned point and the anchor on the ball
+    assert_eq!(recorder.dots.len(), 2);
+    assert!(close(recorder.dots[0].0, v(0.5, 0.5)) && close(recorder.dots[1].0, v(0.5, 0.35)));
+    assert!(recorder.dots.iter().all(|d| d.1 == JOINT_COLOR));
+    assert_eq!(recorder.segments.len(), 1);
+    let (a, b, _) = recorder.segments[0];
+    assert!(close(a, v(0.5, 0.4)) && close(b, v(0.5, 0.35)));
+}
@@ -0,0 +1,154 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    convex_body::Convex2D,
+    line_body::EdgeChain,
+    physics_world::World,
+    physic_obj_traits::CollisionRelation,
+};
+
+mod common;
+use common::*;
+
+//flat terrain at y = 0 drawn from left to right, solid from above, joints at x = -0.5, 0 and 0.5
+fn flat_chain() -> EdgeChain{
+    EdgeChain::new(0., 0., vec![v(-1., 0.), v(-0.5, 0.), v(0., 0.), v(0.5, 0.), v(1., 0.)])
+}
+
+fn square_body(x: f32, y: f32, h: f32) -> Convex2D{
+    Convex2D::new(x, y, square(0., 0., h).into_iter().map(Into::into).collect(), 1.)
+}
+
+#[test]
+fn segments_know_their_neighbours_as_ghost_vertices(){
+    let chain = EdgeChain::new(0., 0., vec![v(-1., 0.), v(0., 0.), v(1., -1.)]);
+    let first = chain.segment(0);
+    assert!(first.v0.is_none());
+    assert_eq!(first.v3, Some(v(1., -1.)));
+    let last = chain.segment(1);
+    assert_eq!(last.v0, Some(v(-1., 0.)));
+    assert!(last.v3.is_none());
+    assert_close(first.normal(), v(0., 1.));
+    //the terrain bends away from the solid side at the joint
+    assert!(first.convex_at_v2() && last.convex_at_v1());
+    //open ends collide like a single segment
+    assert!(first.convex_at_v1() && last.convex_at_v2());
+}
+
+#[test]
+fn joints_only_push_between_the_normals_of_their_segments(){
+    let flat = flat_chain().segment(2);
+    assert!(flat.allows_normal(v(0., 1.)));
+    //no snagging on a flat joint, not even slightly upwards
+    assert!(!flat.allows_normal(v(1., 0.2) / 1.04f32.sqrt()));
+    assert!(!flat.allows_normal(v(-1., 0.2) / 1.04f32.sqrt()));
+
+    //a convex corner pushes anywhere between the normals of both segments
+    let corner = EdgeChain::new(0., 0., vec![v(-2., 0.), v(-1., 0.), v(0., 0.), v(1., -1.)]).segment(1);
+    let diagonal = v(1., 1.) / 2f32.sqrt();
+    assert!(corner.allows_normal(v(0.3, 1.) / 1.09f32.sqrt()));
+    assert!(corner.allows_normal(diagonal));
+    assert!(!corner.allows_normal(v(1., 0.2) / 1.04f32.sqrt()));
+    assert!(!corner.allows_normal(v(-0.3, 1.) / 1.09f32.sqrt()));
+
+    //the open end of the chain pushes anything on the solid side
+    let end = flat_chain().segment(3);
+    assert!(end.allows_normal(diagonal));
+    assert!(!end.allows_normal(v(1., -0.1)));
+}
+
+#[test]
+fn circle_resting_on_a_face(){
+    let circle = Circle::new(0, 0.3, 0.08, 0.1, 1.);
+    let c = Circle::get_contact(&circle, &flat_chain()).expect("circle touches the chain");
+    //points from the circle into the terrain
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.02).abs() < TOL);
+    assert_close(c.point, v(0.3, 0.));
+}
+
+#[test]
+fn circle_over_a_joint_is_pushed_straight_up(){
+    //both segments end at the joint below the circle, the ghost vertices keep the vertex from counting
+    for x in [0., 0.001, -0.001]{
+        let circle = Circle::new(0, x, 0.08, 0.1, 1.);
+        let c = Circle::get_contact(&circle, &flat_chain()).expect("circle touches the chain");
+        assert_close(c.normal, v(0., -1.));
+        assert!((c.depth - 0.02).abs() < TOL);
+    }
+}
+
+#[test]
+fn circle_at_a_convex_corner_gets_the_vertex_normal(){
+    //the terrain bends down at the origin
+    let chain = EdgeChain::new(0., 0., vec![v(-1., 0.), v(0., 0.), v(1., -1.)]);
+    let center = v(0.05, 0.08);
+    let circle = Circle::new(0, center.x, center.y, 0.1, 1.);
+    let c = Circle::get_contact(&circle, &chain).expect("circle touches the corner");
+    let dist = center.x.hypot(center.y);
+    assert_close(c.normal, -center / dist);
+    assert!((c.depth - (0.1 - dist)).abs() < TOL);
+    assert_close(c.point, v(0., 0.));
+}
+
+#[test]
+fn one_sided_chains_ignore_shapes_behind_them(){
+    let mut chain = flat_chain();
+    let circle = Circle::new(0, 0.3, -0.05, 0.1, 1.);
+    let box_below = square_body(-0.3, -0.05, 0.1);
+    assert!(Circle::get_contact(&circle, &chain).is_none());
+    assert!(Convex2D::get_contact(&box_below, &chain).is_none());
+
+    //two sided chains push them down
+    chain.one_sided = false;
+    let c = Circle::get_contact(&circle, &chain).expect("circle touches the chain from below");
+    assert_close(c.normal, v(0., 1.));
+    assert!((c.depth - 0.05).abs() < TOL);
+    let c = Convex2D::get_contact(&box_below, &chain).expect("box touches the chain from below");
+    assert_close(c.normal, v(0., 1.));
+    assert!((c.depth - 0.05).abs() < TOL);
+}
+
+#[test]
+fn polygon_resting_on_a_face(){
+    let c = Convex2D::get_contact(&square_body(0.25, 0.09, 0.1), &flat_chain()).expect("box touches the chain");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.01).abs() < TOL);
+}
+
+#[test]
+fn polygon_sliding_over_a_joint_does_not_snag(){
+    //the box starts right at the joint at x = 0, its side faces must not push it sideways
+    for x in [0.1, -0.1, 0.05]{
+        let c = Convex2D::get_contact(&square_body(x, 0.09, 0.1), &flat_chain()).expect("box touches the chain");
+        assert_close(c.normal, v(0., -1.));
+        assert!((c.depth - 0.01).abs() < TOL);
+    }
+}
+
+#[test]
+fn polygon_at_a_convex_corner(){
+    //the terrain bends down to the right of the origin, the box hangs over the corner
+    let chain = EdgeChain::new(0., 0., vec![v(-1., 0.), v(0., 0.), v(1., -1.)]);
+    let c = Convex2D::get_contact(&square_body(0.05, 0.09, 0.1), &chain).expect("box rests on the corner");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.01).abs() < TOL);
+}
+
+#[test]
+fn box_slides_along_a_chain_of_many_segments(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., -1.));
+    let points = (0..=20).map(|i| v(-0.9 + i as f32 * 0.09, -0.5)).collect();
+    world.add_phy_obj(EdgeChain::new(0., 0., points));
+    let id = world.add_phy_obj(square_body(-0.6, -0.45, 0.05));
+    for _ in 0..150{
+        world.simulation_step();
+        let body = world.get_phy_obj_mut(id).unwrap();
+        body.get_vel_mut().x = 0.005;
+        //stays on top of the chain without bouncing on the joints
+        assert!(body.get_pos().y > -0.46, "{:?}", body.get_pos());
+    }
+    let body = world.get_phy_obj(id).unwrap();
+    assert!(body.get_pos().x > 0.);
+    assert!((body.get_pos().y - -0.45).abs() < 0.005, "{:?}", body.get_pos());
+}
@@ -0,0 +1,88 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    rect_body::Rect,
+    fluid::{Fluid, body_area},
+    geometry::Aabb,
+    polygon::{self, PolygonError},
+    physics_world::World,
+    physic_obj_traits::CollisionObject,
+};
+
+mod common;
+use common::*;
+
+//water up to y = -0.3
+fn pool(density: f32) -> Fluid{
+    Fluid::from_aabb(Aabb { min: v(-1., -1.), max: v(1., -0.3) }, density)
+}
+
+#[test]
+fn clipping_against_convex_polygons(){
+    let clip = square(0., 0., 1.);
+    //half of the square sticks out
+    let half = polygon::clip_convex(&square(1., 0., 0.5), &clip);
+    assert!((polygon::signed_area(&half) - 0.5).abs() < TOL);
+    assert!((polygon::centroid(&half) - v(0.75, 0.)).x.abs() < TOL);
+    //inside and outside, both windings of the clip polygon
+    let inside = polygon::clip_convex(&square(0., 0., 0.5), &clip.iter().rev().copied().collect::<Vec<Vec2>>());
+    assert!((polygon::signed_area(&inside) - 1.).abs() < TOL);
+    assert!(polygon::clip_convex(&square(3., 0., 0.5), &clip).is_empty());
+}
+
+#[test]
+fn submerged_part_of_bodies(){
+    let fluid = pool(1.);
+    let mut rect = Rect::new(0., -0.3, 0.2, 0.1, 1.);
+    let submerged = fluid.submerged(&rect.get_col_type()).unwrap();
+    assert!((submerged.area - 0.01).abs() < TOL);
+    assert!((submerged.fraction - 0.5).abs() < TOL);
+    assert!((submerged.centroid.y - -0.325).abs() < TOL);
+
+    rect.pos = v(0., 0.);
+    assert!(fluid.submerged(&rect.get_col_type()).is_none());
+
+    //circles are clipped as polygons of the same area
+    let circle = Circle::new(0, 0., -0.5, 0.1, 1.);
+    assert!((body_area(&circle.get_col_type()) - std::f32::consts::PI * 0.01).abs() < TOL);
+    assert!((fluid.submerged(&circle.get_col_type()).unwrap().fraction - 1.).abs() < TOL);
+}
+
+#[test]
+fn fluid_region_has_to_be_convex(){
+    let l_shape = vec![v(0., 0.), v(2., 0.), v(2., 1.), v(1., 1.), v(1., 2.), v(0., 2.)];
+    assert!(matches!(Fluid::new(l_shape, 1.).err(), Some(PolygonError::NotConvex(_))));
+    assert!(Fluid::new(square(0., 0., 1.), 1.).is_ok());
+}
+
+#[test]
+fn light_crate_floats_and_rock_sinks(){
+    let mut world = World::new();
+    world.add_fluid(pool(50.));
+    //densities 25 and about 100
+    let crate_id = world.add_phy_obj(Rect::new(-0.3, 0., 0.2, 0.12, 0.6));
+    let rock = world.add_phy_obj(Circle::new(0, 0.3, 0., 0.05, 0.8));
+    for _ in 0..1500{
+        world.simulation_step();
+    }
+    //half of the crate is under water
+    let crate_y = world.get_phy_obj(crate_id).unwrap().get_pos().y;
+    assert!((crate_y - -0.3).abs() < 0.005, "{crate_y}");
+    let rock = world.get_phy_obj(rock).unwrap();
+    assert!(rock.get_pos().y < -0.35 && rock.get_vel().y < 0.);
+}
+
+#[test]
+fn flow_carries_floating_bodies(){
+    let mut world = World::new();
+    let mut river = pool(50.);
+    river.flow = v(0.001, 0.);
+    world.add_fluid(river);
+    let id = world.add_phy_obj(Rect::new(0., -0.3, 0.2, 0.12, 0.6));
+    for _ in 0..200{
+        world.simulation_step();
+    }
+    let body = world.get_phy_obj(id).unwrap();
+    assert!(body.get_pos().x > 0.05);
+    //the drag does not overshoot the flow
+    assert!(body.get_vel().x > 0. && body.get_vel().x <= 0.001);
+}
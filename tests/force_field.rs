@@ -0,0 +1,156 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    force_field::{ForceField, Region, FieldKind, Falloff},
+    geometry::Aabb,
+    physics_world::World,
+};
+
+mod common;
+use common::*;
+
+fn weightless_world() -> World{
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., 0.));
+    world
+}
+
+fn pos_after(world: &mut World, id: usize, steps: usize) -> Vec2{
+    for _ in 0..steps{
+        world.simulation_step();
+    }
+    *world.get_phy_obj(id).unwrap().get_pos()
+}
+
+#[test]
+fn falloff_factors(){
+    assert_eq!(Falloff::Constant.factor(5.), 1.);
+    let linear = Falloff::Linear { radius: 2. };
+    assert!((linear.factor(0.5) - 0.75).abs() < TOL);
+    assert_eq!(linear.factor(3.), 0.);
+    let inverse = Falloff::InverseSquare { min_distance: 0.5 };
+    assert_eq!(inverse.factor(0.1), 1.);
+    assert!((inverse.factor(1.) - 0.25).abs() < TOL);
+}
+
+#[test]
+fn region_shapes(){
+    assert!(Region::Everywhere.contains(v(100., -100.)));
+    let circle = Region::Circle { center: v(1., 1.), r: 0.5 };
+    assert!(circle.contains(v(1.3, 1.3)) && !circle.contains(v(1.4, 1.4)));
+    let aabb = Region::Aabb(Aabb { min: v(0., 0.), max: v(1., 0.5) });
+    assert!(aabb.contains(v(1., 0.5)) && !aabb.contains(v(0.5, 0.6)));
+    //L shape, the notch is outside
+    let l_shape = Region::Polygon(vec![v(0., 0.), v(2., 0.), v(2., 1.), v(1., 1.), v(1., 2.), v(0., 2.)]);
+    assert!(l_shape.contains(v(0.5, 1.5)) && l_shape.contains(v(1.5, 0.5)));
+    assert!(!l_shape.contains(v(1.5, 1.5)));
+}
+
+#[test]
+fn gravity_scale_per_body(){
+    let mut world = World::new();
+    world.allow_sleeping = false;
+    let normal = world.add_phy_obj(Circle::new(0, -0.5, 0.5, 0.05, 1.));
+    let mut heavy = Circle::new(0, 0., 0.5, 0.05, 1.);
+    heavy.gravity_scale = 2.;
+    let heavy = world.add_phy_obj(heavy);
+    let mut floating = Circle::new(0, 0.5, 0.5, 0.05, 1.);
+    floating.gravity_scale = 0.;
+    let floating = world.add_phy_obj(floating);
+
+    let drop_normal = 0.5 - pos_after(&mut world, normal, 50).y;
+    let drop_heavy = 0.5 - world.get_phy_obj(heavy).unwrap().get_pos().y;
+    assert!(drop_normal > 0.);
+    assert!((drop_heavy - 2. * drop_normal).abs() < TOL, "{drop_heavy} {drop_normal}");
+    assert_eq!(world.get_phy_obj(floating).unwrap().get_pos().y, 0.5);
+}
+
+#[test]
+fn gravity_is_kept_apart_from_the_fields(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(1., 0.));
+    assert_eq!(world.gravity_dir(), v(1., 0.));
+    //the fields can be cleared without losing the gravity
+    world.add_force_field(ForceField::gravity(v(0., 0.001)));
+    world.force_fields.clear();
+    assert_eq!(world.gravity_dir(), v(1., 0.));
+    let id = world.add_phy_obj(Circle::new(0, 0., 0., 0.05, 1.));
+    let pos = pos_after(&mut world, id, 20);
+    assert!(pos.x > 0. && pos.y == 0.);
+}
+
+#[test]
+fn attractor_and_repulsor(){
+    let mut world = weightless_world();
+    world.add_force_field(ForceField::new(
+        Region::Circle { center: v(0., 0.), r: 0.5 },
+        FieldKind::Point { center: v(0., 0.), strength: 0.0001, falloff: Falloff::Constant },
+    ));
+    world.add_force_field(ForceField::new(
+        Region::Circle { center: v(0.6, 0.6), r: 0.3 },
+        FieldKind::Point { center: v(0.6, 0.6), strength: -0.0001, falloff: Falloff::Constant },
+    ));
+    let attracted = world.add_phy_obj(Circle::new(0, 0.3, 0., 0.02, 1.));
+    let repelled = world.add_phy_obj(Circle::new(0, 0.6, 0.5, 0.02, 5.));
+    let outside = world.add_phy_obj(Circle::new(0, -0.7, 0., 0.02, 1.));
+    pos_after(&mut world, attracted, 20);
+    assert!(world.get_phy_obj(attracted).unwrap().get_pos().x < 0.3);
+    assert!(world.get_phy_obj(repelled).unwrap().get_pos().y < 0.5);
+    assert_eq!(*world.get_phy_obj(outside).unwrap().get_pos(), v(-0.7, 0.));
+}
+
+#[test]
+fn wind_pushes_light_bodies_further(){
+    let mut world = weightless_world();
+    world.add_force_field(ForceField::new(
+        Region::Aabb(Aabb { min: v(-1., -0.2), max: v(1., 0.2) }),
+        FieldKind::Wind { force: v(0.0001, 0.) },
+    ));
+    let light = world.add_phy_obj(Circle::new(0, -0.5, 0., 0.02, 1.));
+    let heavy = world.add_phy_obj(Circle::new(0, -0.5, 0.1, 0.02, 4.));
+    let sheltered = world.add_phy_obj(Circle::new(0, -0.5, 0.5, 0.02, 1.));
+    let light_x = pos_after(&mut world, light, 30).x;
+    let heavy_x = world.get_phy_obj(heavy).unwrap().get_pos().x;
+    assert!(light_x > heavy_x && heavy_x > -0.5);
+    assert_eq!(world.get_phy_obj(sheltered).unwrap().get_pos().x, -0.5);
+}
+
+#[test]
+fn buoyancy_lifts_and_slows_bodies(){
+    let mut world = World::new();
+    world.allow_sleeping = false;
+    world.add_force_field(ForceField::new(
+        Region::Aabb(Aabb { min: v(-1., -1.), max: v(1., 0.) }),
+        FieldKind::Buoyancy { lift: 2., drag: 0.1 },
+    ));
+    let id = world.add_phy_obj(Circle::new(0, 0., -0.5, 0.05, 1.));
+    pos_after(&mut world, id, 50);
+    let p = world.get_phy_obj(id).unwrap();
+    assert!(p.get_pos().y > -0.5);
+    //the drag keeps it slower than a falling body
+    assert!(p.get_vel().y > 0. && p.get_vel().y < 0.0009);
+}
+
+#[test]
+fn vortex_turns_counter_clockwise(){
+    let mut world = weightless_world();
+    world.add_force_field(ForceField::new(
+        Region::Everywhere,
+        FieldKind::Vortex { center: v(0., 0.), strength: 0.0001, falloff: Falloff::Linear { radius: 1. } },
+    ));
+    let id = world.add_phy_obj(Circle::new(0, 0.5, 0., 0.02, 1.));
+    pos_after(&mut world, id, 10);
+    let vel = *world.get_phy_obj(id).unwrap().get_vel();
+    assert!(vel.y > 0. && vel.x.abs() < vel.y);
+}
+
+#[test]
+fn new_fields_wake_sleeping_bodies(){
+    let mut world = World::new();
+    let id = world.add_phy_obj(Circle::new(0, 0., 0., 0.05, 1.));
+    world.set_gravity_dir(v(0., 0.));
+    pos_after(&mut world, id, 100);
+    assert!(!world.is_awake(id));
+    world.add_force_field(ForceField::new(Region::Everywhere, FieldKind::Wind { force: v(0.0001, 0.) }));
+    assert!(world.is_awake(id));
+    assert!(pos_after(&mut world, id, 10).x > 0.);
+}
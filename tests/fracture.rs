@@ -0,0 +1,174 @@
+use wgpu_tutorial::physics_engine::{
+    chain_body::Chain,
+    circle_body::Circle,
+    convex_body::Convex2D,
+    rect_body::Rect,
+    fracture::{self, Breakable},
+    polygon,
+    physics_world::World,
+    physic_obj_traits::ForceObject,
+};
+
+use cgmath::InnerSpace;
+
+mod common;
+use common::*;
+
+fn pane(y: f32, threshold: f32) -> Convex2D{
+    let mut pane = Convex2D::new(0.0, y, vec![(-0.1, -0.05), (0.1, -0.05), (0.1, 0.05), (-0.1, 0.05)], 0.5);
+    pane.breakable = Some(Breakable::new(threshold));
+    pane
+}
+
+#[test]
+fn splitting_and_cracking_polygons(){
+    let (left, right) = polygon::split_convex(&square(0., 0., 1.), v(0.5, -2.), v(0.5, 2.));
+    assert!((polygon::signed_area(&left) - 3.).abs() < TOL);
+    assert!((polygon::signed_area(&right) - 1.).abs() < TOL);
+    //lines missing the polygon leave one side empty
+    let (left, right) = polygon::split_convex(&square(0., 0., 1.), v(2., -2.), v(2., 2.));
+    assert_eq!(left.len(), 4);
+    assert!(right.is_empty());
+
+    let pieces = fracture::crack_polygon(&square(0., 0., 1.), v(0., 0.), v(0., 1.), 2);
+    assert_eq!(pieces.len(), 4);
+    assert!(pieces.iter().all(|p| (polygon::signed_area(p) - 1.).abs() < TOL));
+}
+
+#[test]
+fn slices_have_to_cut_all_the_way_through(){
+    let outline = square(0., 0., 1.);
+    assert!(fracture::slice_polygon(&outline, v(-2., 0.5), v(2., 0.5)).is_some());
+    //ends inside
+    assert!(fracture::slice_polygon(&outline, v(-2., 0.5), v(0., 0.5)).is_none());
+    //misses the polygon
+    assert!(fracture::slice_polygon(&outline, v(-2., 1.5), v(2., 1.5)).is_none());
+}
+
+#[test]
+fn slicing_the_world_replaces_bodies_by_halves(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., 0.));
+    let crate_id = world.add_phy_obj(Rect::new(0., 0., 0.2, 0.1, 0.8));
+    let ball = world.add_phy_obj(Circle::new(0, 0.5, 0., 0.05, 1.));
+    *world.get_phy_obj_mut(crate_id).unwrap().get_vel_mut() = v(0.002, 0.);
+
+    let pieces = world.slice(v(0.05, -1.), v(0.05, 1.));
+    assert_eq!(pieces.len(), 2);
+    assert!(world.get_phy_obj(crate_id).is_none());
+    assert!(world.get_phy_obj(ball).is_some());
+    assert_eq!(world.physics_objects.len(), 3);
+
+    let mut masses : Vec<f32> = pieces.iter().map(|&id| world.get_phy_obj(id).unwrap().get_mass()).collect();
+    masses.sort_by(f32::total_cmp);
+    assert!((masses[0] - 0.2).abs() < TOL && (masses[1] - 0.6).abs() < TOL);
+    for &id in pieces.iter(){
+        assert_eq!(*world.get_phy_obj(id).unwrap().get_vel(), v(0.002, 0.));
+    }
+}
+
+//regular polygon with the first vertex at (r, 0)
+fn regular_polygon(n: usize, r: f32) -> Vec<(f32, f32)>{
+    (0..n).map(|i| {
+        let a = std::f32::consts::TAU * i as f32 / n as f32;
+        (r * a.cos(), r * a.sin())
+    }).collect()
+}
+
+#[test]
+fn cutting_a_corner_off_a_full_polygon_keeps_its_mass(){
+    let disc = Convex2D::new(0., 0., regular_polygon(polygon::MAX_POLYGON_VERTICES, 0.3), 1.);
+    //the rest of the polygon gets one vertex more than a Convex2D may have
+    let (rest, corner) = polygon::split_convex(&disc.world().vertices, v(0.29, -1.), v(0.29, 1.));
+    assert_eq!(rest.len(), polygon::MAX_POLYGON_VERTICES + 1);
+    let pieces = fracture::pieces_of(&disc, &[rest, corner]);
+    assert!(pieces.len() > 2);
+    let mass : f32 = pieces.iter().map(|p| p.get_mass()).sum();
+    assert!((mass - 1.).abs() < TOL);
+
+    let mut world = World::new();
+    world.add_phy_obj(Convex2D::new(0., 0., regular_polygon(polygon::MAX_POLYGON_VERTICES, 0.3), 1.));
+    assert!(world.slice(v(0.29, -1.), v(0.29, 1.)).len() > 2);
+    let mass : f32 = world.physics_objects.iter().map(|p| p.get_mass()).sum();
+    assert!((mass - 1.).abs() < TOL);
+}
+
+#[test]
+fn pieces_of_spinning_bodies_move_like_their_points(){
+    let mut world = World::new();
+    let mut wheel = Convex2D::new(0., 0., vec![(-0.1, -0.1), (0.1, -0.1), (0.1, 0.1), (-0.1, 0.1)], 1.);
+    let inertia = wheel.get_inertia();
+    wheel.set_torque(0.01 * inertia);
+    world.add_phy_obj(wheel);
+
+    let pieces = world.slice(v(0., -1.), v(0., 1.));
+    assert_eq!(pieces.len(), 2);
+    for id in pieces{
+        let piece = world.get_phy_obj(id).unwrap();
+        //angular velocity 0.01 around the origin
+        let r = *piece.get_pos();
+        assert!((piece.get_vel() - v(-0.01 * r.y, 0.01 * r.x)).magnitude2() < TOL * TOL);
+        assert!((piece.get_torque() / piece.get_inertia() - 0.01).abs() < TOL);
+    }
+}
+
+#[test]
+fn breakable_bodies_break_on_hard_impacts(){
+    let mut world = world_with_floor();
+    let dropped = world.add_phy_obj(pane(-0.4, 0.004));
+    steps(&mut world, 200);
+    assert!(world.get_phy_obj(dropped).is_none());
+    assert_eq!(world.physics_objects.len(), 5);
+    let mass : f32 = world.physics_objects.iter().map(|p| p.get_mass()).sum();
+    assert!((mass - 0.5).abs() < TOL);
+
+    //set down gently it stays whole
+    let mut world = world_with_floor();
+    let placed = world.add_phy_obj(pane(-0.649, 0.004));
+    steps(&mut world, 200);
+    assert!(world.get_phy_obj(placed).is_some());
+    assert_eq!(world.physics_objects.len(), 2);
+}
+
+#[test]
+fn removed_bodies_release_what_depends_on_them(){
+    let mut world = world_with_floor();
+    //the default gravity lets the stack settle without bouncing
+    world.set_gravity_dir(v(0., -0.01));
+    let bottom = world.add_phy_obj(Rect::new(0., -0.65, 0.2, 0.1, 0.5));
+    let top = world.add_phy_obj(Rect::new(0., -0.549, 0.2, 0.1, 0.5));
+    steps(&mut world, 600);
+    assert!(!world.is_awake(top));
+
+    let mut rope = Chain::from_coords(vec![[0.5, 0.5], [0.5, 0.4]], 0.01, 0.1, 0.01);
+    rope.attach_to_body(1, bottom, v(0., 0.));
+    let rope = world.add_chain(rope);
+    assert!(world.remove_phy_obj(bottom).is_some());
+    assert!(world.remove_phy_obj(bottom).is_none());
+    assert!(world.get_phy_obj(bottom).is_none());
+    assert!(world.chains[rope].get_attachments().is_empty());
+    //the top box falls onto the floor
+    assert!(world.is_awake(top));
+    steps(&mut world, 400);
+    assert!((world.get_phy_obj(top).unwrap().get_pos().y - -0.65).abs() < 0.01);
+}
+
+#[test]
+fn bodies_breaking_in_the_same_step_get_the_same_ids_every_run(){
+    let run = || {
+        let mut world = world_with_floor();
+        for x in [-0.5, 0., 0.5]{
+            let mut p = pane(-0.4, 0.004);
+            p.pos.x = x;
+            world.add_phy_obj(p);
+        }
+        steps(&mut world, 200);
+        world.physics_objects.iter().map(|p| (p.get_id(), *p.get_pos())).collect::<Vec<_>>()
+    };
+    let first = run();
+    //all three panes broke
+    assert_eq!(first.len(), 13);
+    for _ in 0..3{
+        assert_eq!(run(), first);
+    }
+}
@@ -0,0 +1,55 @@
+use wgpu_tutorial::gen_vec::GenVec;
+
+#[test]
+fn inserted_values_can_be_found(){
+    let mut v = GenVec::new();
+    let a = v.insert("a");
+    let b = v.insert("b");
+    assert_eq!(v.get(&a), Some(&"a"));
+    assert_eq!(v.get(&b), Some(&"b"));
+    assert_eq!(v.len(), 2);
+    *v.get_mut(&b).unwrap() = "c";
+    assert_eq!(v.iter().copied().collect::<Vec<&str>>(), vec!["a", "c"]);
+}
+
+#[test]
+fn removed_values_are_gone(){
+    let mut v = GenVec::new();
+    let a = v.insert(1);
+    let b = v.insert(2);
+    assert_eq!(v.remove(&a), Some(1));
+    assert_eq!(v.remove(&a), None);
+    assert!(v.get(&a).is_none());
+    assert_eq!(v.len(), 1);
+    assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![2]);
+    assert_eq!(v.remove(&b), Some(2));
+    assert!(v.is_empty());
+}
+
+#[test]
+fn free_entries_are_reused_with_a_new_generation(){
+    let mut v = GenVec::new();
+    let a = v.insert(1);
+    v.insert(2);
+    v.remove(&a);
+    let c = v.insert(3);
+    //the old key does not reach the new value
+    assert!(v.get(&a).is_none());
+    assert_eq!(v.get(&c), Some(&3));
+    assert_eq!(v.len(), 2);
+    let d = v.insert(4);
+    assert_eq!(v.get(&d), Some(&4));
+    assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![3, 2, 4]);
+}
+
+#[test]
+fn several_free_entries(){
+    let mut v = GenVec::new();
+    let keys : Vec<_> = (0..5).map(|i| v.insert(i)).collect();
+    v.remove(&keys[1]);
+    v.remove(&keys[3]);
+    let new : Vec<_> = (10..13).map(|i| v.insert(i)).collect();
+    assert!(new.iter().zip(10..13).all(|(k, i)| v.get(k) == Some(&i)));
+    assert_eq!(v.len(), 6);
+    assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![0, 11, 2, 10, 4, 12]);
+}
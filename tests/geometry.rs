@@ -0,0 +1,223 @@
+use wgpu_tutorial::physics_engine::geometry::*;
+
+mod common;
+use common::*;
+
+fn assert_single(i: Intersects, expected: Vec2){
+    match i{
+        Intersects::Single(p) => assert_close(p, expected),
+        Intersects::Overlap(a, b) => panic!("expected single point, got overlap {:?} {:?}", a, b),
+        Intersects::None => panic!("expected single point, got none"),
+    }
+}
+
+fn assert_overlap(i: Intersects, expected1: Vec2, expected2: Vec2){
+    match i{
+        Intersects::Overlap(a, b) => {
+            assert_close(a, expected1);
+            assert_close(b, expected2);
+        },
+        Intersects::Single(p) => panic!("expected overlap, got single point {:?}", p),
+        Intersects::None => panic!("expected overlap, got none"),
+    }
+}
+
+fn assert_none(i: Intersects){
+    match i{
+        Intersects::None => {},
+        Intersects::Single(p) => panic!("expected none, got single point {:?}", p),
+        Intersects::Overlap(a, b) => panic!("expected none, got overlap {:?} {:?}", a, b),
+    }
+}
+
+#[test]
+fn crossing_diagonals(){
+    assert_single(segments_intersection(v(-0.9, 0.9), v(0.9, -0.9), v(-0.9, -0.9), v(0.9, 0.9)), v(0., 0.));
+}
+
+#[test]
+fn vertical_and_horizontal(){
+    assert_single(segments_intersection(v(0.5, -1.), v(0.5, 1.), v(-1., 0.25), v(1., 0.25)), v(0.5, 0.25));
+}
+
+#[test]
+fn vertical_and_diagonal(){
+    assert_single(segments_intersection(v(1., -2.), v(1., 2.), v(0., 0.), v(2., 2.)), v(1., 1.));
+}
+
+#[test]
+fn crossing_is_symmetric(){
+    assert_single(segments_intersection(v(-1., 0.25), v(1., 0.25), v(0.5, -1.), v(0.5, 1.)), v(0.5, 0.25));
+    assert_single(segments_intersection(v(1., 0.25), v(-1., 0.25), v(0.5, 1.), v(0.5, -1.)), v(0.5, 0.25));
+}
+
+#[test]
+fn lines_cross_outside_of_segments(){
+    assert_none(segments_intersection(v(0., 0.), v(1., 0.), v(2., -1.), v(2., 1.)));
+    assert_none(segments_intersection(v(0., 0.), v(1., 1.), v(0., 3.), v(3., 0.)));
+}
+
+#[test]
+fn touching_end_points(){
+    assert_single(segments_intersection(v(0., 0.), v(1., 0.), v(1., 0.), v(1., 1.)), v(1., 0.));
+    assert_single(segments_intersection(v(0., 0.), v(2., 0.), v(1., 0.), v(1., 1.)), v(1., 0.));
+}
+
+#[test]
+fn parallel_segments(){
+    assert_none(segments_intersection(v(0., 0.), v(1., 0.), v(0., 1.), v(1., 1.)));
+    assert_none(segments_intersection(v(0., 0.), v(0., 1.), v(1., 0.), v(1., 1.)));
+}
+
+#[test]
+fn collinear_overlap(){
+    assert_overlap(segments_intersection(v(0., 0.), v(2., 0.), v(1., 0.), v(3., 0.)), v(1., 0.), v(2., 0.));
+    assert_overlap(segments_intersection(v(0., 0.), v(0., 2.), v(0., 3.), v(0., 1.)), v(0., 1.), v(0., 2.));
+}
+
+#[test]
+fn collinear_containment(){
+    assert_overlap(segments_intersection(v(0., 0.), v(4., 4.), v(1., 1.), v(2., 2.)), v(1., 1.), v(2., 2.));
+    assert_overlap(segments_intersection(v(1., 1.), v(2., 2.), v(0., 0.), v(4., 4.)), v(1., 1.), v(2., 2.));
+}
+
+#[test]
+fn collinear_touching(){
+    assert_single(segments_intersection(v(0., 0.), v(1., 0.), v(1., 0.), v(2., 0.)), v(1., 0.));
+}
+
+#[test]
+fn collinear_disjoint(){
+    assert_none(segments_intersection(v(0., 0.), v(1., 0.), v(2., 0.), v(3., 0.)));
+}
+
+#[test]
+fn degenerated_segments(){
+    assert_single(segments_intersection(v(1., 1.), v(1., 1.), v(1., 1.), v(1., 1.)), v(1., 1.));
+    assert_none(segments_intersection(v(1., 1.), v(1., 1.), v(2., 1.), v(2., 1.)));
+    assert_single(segments_intersection(v(0.5, 0.), v(0.5, 0.), v(0., 0.), v(1., 0.)), v(0.5, 0.));
+    assert_none(segments_intersection(v(0., 0.), v(1., 0.), v(0.5, 0.1), v(0.5, 0.1)));
+}
+
+#[test]
+fn closest_point_inside_segment(){
+    let (t, p) = closest_point_on_segment(v(0.5, 1.), v(0., 0.), v(1., 0.));
+    assert!((t - 0.5).abs() < TOL);
+    assert_close(p, v(0.5, 0.));
+}
+
+#[test]
+fn closest_point_clamped_to_ends(){
+    let (t, p) = closest_point_on_segment(v(-1., 1.), v(0., 0.), v(1., 0.));
+    assert_eq!(t, 0.);
+    assert_close(p, v(0., 0.));
+    let (t, p) = closest_point_on_segment(v(3., -1.), v(0., 0.), v(1., 0.));
+    assert_eq!(t, 1.);
+    assert_close(p, v(1., 0.));
+}
+
+#[test]
+fn closest_point_on_line_is_not_clamped(){
+    let (t, p) = closest_point_on_line(v(3., 1.), v(0., 0.), v(0., 1.));
+    assert!((t - 1.).abs() < TOL);
+    assert_close(p, v(0., 1.));
+    let (t, p) = closest_point_on_line(v(3., 0.), v(0., 0.), v(1., 1.));
+    assert!((t - 1.5).abs() < TOL);
+    assert_close(p, v(1.5, 1.5));
+}
+
+#[test]
+fn point_distance(){
+    assert!((point_segment_distance(v(0.5, 2.), v(0., 0.), v(1., 0.)) - 2.).abs() < TOL);
+    assert!((point_segment_distance(v(4., 4.), v(0., 0.), v(1., 0.)) - 5.).abs() < TOL);
+    assert!((point_segment_distance(v(0., 3.), v(0., 0.), v(0., 0.)) - 3.).abs() < TOL);
+    assert!(point_segment_distance(v(0.3, 0.), v(0., 0.), v(1., 0.)) < TOL);
+}
+
+#[test]
+fn closest_points_of_skew_segments(){
+    let (s, t, c1, c2) = closest_points_between_segments(v(0., 0.), v(1., 0.), v(2., 1.), v(2., 3.));
+    assert!((s - 1.).abs() < TOL && t.abs() < TOL);
+    assert_close(c1, v(1., 0.));
+    assert_close(c2, v(2., 1.));
+}
+
+#[test]
+fn closest_points_of_crossing_segments(){
+    let (_, _, c1, c2) = closest_points_between_segments(v(-1., -1.), v(1., 1.), v(-1., 1.), v(1., -1.));
+    assert_close(c1, v(0., 0.));
+    assert_close(c2, v(0., 0.));
+}
+
+#[test]
+fn closest_points_of_parallel_segments(){
+    let (_, _, c1, c2) = closest_points_between_segments(v(0., 0.), v(2., 0.), v(1., 1.), v(3., 1.));
+    assert!(((c2 - c1).x).abs() < TOL);
+    assert!(((c2 - c1).y - 1.).abs() < TOL);
+}
+
+#[test]
+fn closest_points_with_degenerated_segments(){
+    let (_, t, c1, c2) = closest_points_between_segments(v(0.5, 1.), v(0.5, 1.), v(0., 0.), v(1., 0.));
+    assert!((t - 0.5).abs() < TOL);
+    assert_close(c1, v(0.5, 1.));
+    assert_close(c2, v(0.5, 0.));
+    let (s, _, c1, c2) = closest_points_between_segments(v(0., 0.), v(0., 1.), v(2., 0.5), v(2., 0.5));
+    assert!((s - 0.5).abs() < TOL);
+    assert_close(c1, v(0., 0.5));
+    assert_close(c2, v(2., 0.5));
+}
+
+#[test]
+fn distance_between_segments(){
+    assert!(segments_distance(v(-1., -1.), v(1., 1.), v(-1., 1.), v(1., -1.)) < TOL);
+    assert!((segments_distance(v(0., 0.), v(1., 0.), v(0., 2.), v(1., 2.)) - 2.).abs() < TOL);
+    assert!((segments_distance(v(0., 0.), v(1., 0.), v(4., 4.), v(4., 8.)) - 5.).abs() < TOL);
+}
+
+#[test]
+fn ray_hits_segment(){
+    let hit = ray_segment_cast(v(0., 0.), v(1., 0.), 10., v(2., -1.), v(2., 1.)).expect("ray should hit");
+    assert!((hit.t - 2.).abs() < TOL);
+    assert_close(hit.point, v(2., 0.));
+    assert_close(hit.normal, v(-1., 0.));
+}
+
+#[test]
+fn ray_normal_faces_origin(){
+    let hit = ray_segment_cast(v(0., 0.), v(1., 0.), 10., v(2., 1.), v(2., -1.)).expect("ray should hit");
+    assert_close(hit.normal, v(-1., 0.));
+    let hit = ray_segment_cast(v(0., 5.), v(0., -1.), 10., v(-1., 0.), v(1., 0.)).expect("ray should hit");
+    assert_close(hit.normal, v(0., 1.));
+}
+
+#[test]
+fn ray_misses_segment(){
+    assert!(ray_segment_cast(v(0., 0.), v(1., 0.), 10., v(2., 1.), v(2., 3.)).is_none());
+    assert!(ray_segment_cast(v(0., 0.), v(-1., 0.), 10., v(2., -1.), v(2., 1.)).is_none());
+}
+
+#[test]
+fn ray_is_limited_by_max_t(){
+    assert!(ray_segment_cast(v(0., 0.), v(1., 0.), 1.5, v(2., -1.), v(2., 1.)).is_none());
+    assert!(ray_segment_cast(v(0., 0.), v(2., 0.), 1.5, v(2., -1.), v(2., 1.)).is_some());
+}
+
+#[test]
+fn ray_parallel_to_segment(){
+    assert!(ray_segment_cast(v(0., 0.), v(1., 0.), 10., v(2., 0.), v(3., 0.)).is_none());
+    assert!(ray_segment_cast(v(0., 0.), v(1., 0.), 10., v(0., 1.), v(3., 1.)).is_none());
+}
+
+#[test]
+fn ray_hits_segment_end(){
+    let hit = ray_segment_cast(v(0., 0.), v(1., 1.), 10., v(1., 1.), v(3., 1.)).expect("ray should hit");
+    assert_close(hit.point, v(1., 1.));
+}
+
+#[test]
+fn normals_are_left_of_direction(){
+    assert_close(segment_normal(v(0., 0.), v(2., 0.)), v(0., 1.));
+    assert_close(segment_normal(v(0., 0.), v(0., 2.)), v(-1., 0.));
+    assert!((cross(v(1., 0.), v(0., 1.)) - 1.).abs() < TOL);
+}
@@ -0,0 +1,124 @@
+use wgpu_tutorial::physics_engine::{
+    gjk::{distance, core_distance, shape_distance, ConvexProxy, GJK_MAX_ITERATIONS},
+    circle_body::Circle,
+    rect_body::Rect,
+    line_body::EdgeChain,
+    physic_obj_traits::CollisionObject,
+};
+
+mod common;
+use common::*;
+
+fn square_proxy(x: f32, y: f32, h: f32) -> ConvexProxy{
+    ConvexProxy::new(square(x, y, h), 0.)
+}
+
+#[test]
+fn separated_squares_face_to_face(){
+    let d = distance(&square_proxy(0., 0., 0.5), &square_proxy(2., 0.2, 0.5)).unwrap();
+    assert!((d.distance - 1.).abs() < TOL);
+    assert!((d.point_a.x - 0.5).abs() < TOL);
+    assert!((d.point_b.x - 1.5).abs() < TOL);
+    assert!((d.point_a.y - d.point_b.y).abs() < TOL);
+    assert_close(d.normal().unwrap(), v(1., 0.));
+}
+
+#[test]
+fn separated_squares_corner_to_corner(){
+    let d = distance(&square_proxy(0., 0., 0.5), &square_proxy(2., 2., 0.5)).unwrap();
+    assert!((d.distance - 2f32.sqrt()).abs() < TOL);
+    assert_close(d.point_a, v(0.5, 0.5));
+    assert_close(d.point_b, v(1.5, 1.5));
+}
+
+#[test]
+fn overlapping_squares(){
+    let d = distance(&square_proxy(0., 0., 0.5), &square_proxy(0.5, 0.2, 0.5)).unwrap();
+    assert!(d.overlap());
+    assert_eq!(d.distance, 0.);
+    assert!(d.normal().is_none());
+}
+
+#[test]
+fn circles_are_points_with_radius(){
+    let d = distance(&ConvexProxy::point(v(0., 0.), 0.2), &ConvexProxy::point(v(1., 0.), 0.3)).unwrap();
+    assert!((d.distance - 0.5).abs() < TOL);
+    assert_close(d.point_a, v(0.2, 0.));
+    assert_close(d.point_b, v(0.7, 0.));
+    let d = distance(&ConvexProxy::point(v(0., 0.), 0.6), &ConvexProxy::point(v(1., 0.), 0.6)).unwrap();
+    assert!(d.overlap());
+}
+
+#[test]
+fn circle_and_square(){
+    let d = distance(&ConvexProxy::point(v(0., 2.), 0.5), &square_proxy(0.3, 0., 0.5)).unwrap();
+    assert!((d.distance - 1.).abs() < TOL);
+    assert_close(d.point_a, v(0., 1.5));
+    assert_close(d.point_b, v(0., 0.5));
+}
+
+#[test]
+fn point_and_segment(){
+    let d = distance(&ConvexProxy::point(v(0.5, 1.), 0.), &ConvexProxy::new(vec![v(0., 0.), v(1., 0.)], 0.)).unwrap();
+    assert!((d.distance - 1.).abs() < TOL);
+    assert_close(d.point_b, v(0.5, 0.));
+}
+
+#[test]
+fn crossing_segments_touch(){
+    let a = ConvexProxy::new(vec![v(-1., -1.), v(1., 1.)], 0.);
+    let b = ConvexProxy::new(vec![v(-1., 1.), v(1., -1.)], 0.);
+    assert!(distance(&a, &b).unwrap().overlap());
+}
+
+#[test]
+fn duplicate_and_collinear_points(){
+    let a = ConvexProxy::new(vec![v(0., 0.), v(0., 0.), v(1., 0.), v(2., 0.), v(1., 0.)], 0.);
+    let b = ConvexProxy::new(vec![v(1., 2.), v(1., 2.)], 0.);
+    let d = distance(&a, &b).unwrap();
+    assert!((d.distance - 2.).abs() < TOL);
+    assert_close(d.point_a, v(1., 0.));
+}
+
+#[test]
+fn many_vertices_stay_within_iteration_limit(){
+    let circle_like : Vec<Vec2> = (0..256).map(|i| {
+        let a = i as f32 / 256. * std::f32::consts::TAU;
+        v(a.cos(), a.sin())
+    }).collect();
+    let d = distance(&ConvexProxy::new(circle_like.clone(), 0.), &ConvexProxy::new(circle_like.iter().map(|p| p + v(3., 0.5)).collect(), 0.)).unwrap();
+    assert!(d.iterations <= GJK_MAX_ITERATIONS);
+    let expected = v(3., 0.5).x.hypot(0.5) - 2.;
+    assert!((d.distance - expected).abs() < 0.001, "{} != {}", d.distance, expected);
+}
+
+#[test]
+fn empty_proxy_has_no_distance(){
+    assert!(distance(&ConvexProxy::new(vec![], 0.), &square_proxy(0., 0., 1.)).is_none());
+}
+
+#[test]
+fn core_distance_ignores_radius(){
+    let a = ConvexProxy::point(v(0., 0.), 0.5);
+    let b = ConvexProxy::point(v(1., 0.), 0.7);
+    assert!((core_distance(&a, &b).unwrap() - 1.).abs() < TOL);
+}
+
+#[test]
+fn distance_between_bodies(){
+    let rect = Rect::new(0., 0., 1., 1., 1.);
+    let circle = Circle::new(0, 2., 0., 0.5, 1.);
+    let d = shape_distance(&rect.get_col_type(), &circle.get_col_type()).unwrap();
+    assert!((d.distance - 1.).abs() < TOL);
+    assert_close(d.point_a, v(0.5, 0.));
+    assert_close(d.point_b, v(1.5, 0.));
+}
+
+#[test]
+fn distance_to_nearest_edge_segment(){
+    let terrain = EdgeChain::from_coords(vec![[-1., 0.], [0., -0.5], [1., 0.]]);
+    let circle = Circle::new(0, 1., 1., 0.1, 1.);
+    let d = shape_distance(&circle.get_col_type(), &terrain.get_col_type()).unwrap();
+    assert!((d.distance - 0.9).abs() < TOL);
+    assert_close(d.point_b, v(1., 0.));
+}
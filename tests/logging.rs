@@ -0,0 +1,88 @@
+use std::{sync::Mutex, thread::{self, ThreadId}};
+
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    convex_body::Convex2D,
+    fracture::Breakable,
+};
+
+mod common;
+use common::world_with_floor;
+
+//targets logged by every thread, tests run in parallel
+static RECORDS : Mutex<Vec<(ThreadId, String, log::Level)>> = Mutex::new(vec![]);
+
+struct Recorder;
+
+impl log::Log for Recorder{
+    fn enabled(&self, _: &log::Metadata) -> bool{
+        true
+    }
+
+    fn log(&self, record: &log::Record){
+        RECORDS.lock().unwrap().push((thread::current().id(), record.target().to_string(), record.level()));
+    }
+
+    fn flush(&self){}
+}
+
+fn init(){
+    //the first test sets the logger
+    if log::set_logger(&Recorder).is_ok(){
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+}
+
+//targets logged by this thread
+fn targets() -> Vec<(String, log::Level)>{
+    let id = thread::current().id();
+    RECORDS.lock().unwrap().iter().filter(|r| r.0 == id).map(|r| (r.1.clone(), r.2)).collect()
+}
+
+fn logged(target: &str) -> bool{
+    targets().iter().any(|(t, _)| t == target)
+}
+
+#[test]
+fn steps_log_stats_and_contacts(){
+    init();
+    let mut world = world_with_floor();
+    world.add_phy_obj(Circle::new(0, 0., -0.6, 0.1, 0.1));
+    for _ in 0..20{
+        world.simulation_step();
+    }
+    assert!(targets().iter().filter(|(t, _)| t == "physics::stats").all(|(_, level)| *level == log::Level::Debug));
+    assert!(logged("physics::stats"));
+    assert!(logged("physics::solver"));
+    assert!(targets().iter().all(|(t, _)| t.starts_with("physics::")));
+}
+
+#[test]
+fn disabled_contacts_and_sleep_are_logged(){
+    init();
+    let mut world = world_with_floor();
+    world.add_phy_obj(Circle::new(0, 0., -0.6, 0.1, 0.1));
+    world.set_pre_solve(|_| false);
+    world.simulation_step();
+    assert!(logged("physics::narrowphase"));
+    assert!(!logged("physics::solver"));
+
+    world.clear_pre_solve();
+    for _ in 0..2000{
+        world.simulation_step();
+    }
+    assert!(logged("physics::sleep"));
+}
+
+#[test]
+fn breaking_bodies_are_logged(){
+    init();
+    let mut world = world_with_floor();
+    let mut pane = Convex2D::new(0.0, -0.5, vec![(-0.1, -0.05), (0.1, -0.05), (0.1, 0.05), (-0.1, 0.05)], 0.5);
+    pane.breakable = Some(Breakable::new(0.0001));
+    world.add_phy_obj(pane);
+    for _ in 0..100{
+        world.simulation_step();
+    }
+    assert!(logged("physics::fracture"));
+}
@@ -0,0 +1,147 @@
+use std::{cell::RefCell, rc::Rc};
+
+use wgpu_tutorial::physics_engine::{
+    character::CharacterController,
+    circle_body::Circle,
+    convex_body::Convex2D,
+    line_body::EdgeChain,
+    physics_world::World,
+    physic_obj_traits::BodyType,
+};
+
+mod common;
+use common::*;
+
+fn slab(x: f32, y: f32, half_width: f32, half_height: f32) -> Convex2D{
+    let (w, h) = (half_width, half_height);
+    let mut slab = Convex2D::new(x, y, vec![(-w, -h), (w, -h), (w, h), (-w, h)], 0.);
+    slab.body_type = BodyType::Static;
+    slab
+}
+
+//static floor with its top at y = -0.7 and a one way platform with its top at y = -0.4
+fn world_with_platform() -> (World, usize){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., -1.));
+    world.add_phy_obj(slab(0., -0.8, 1., 0.1));
+    let mut platform = slab(0., -0.42, 0.2, 0.02);
+    platform.one_way = Some(v(0., 1.));
+    let platform = world.add_phy_obj(platform);
+    (world, platform)
+}
+
+//moves body up with the highest velocity for n steps, like a jump
+fn jump(world: &mut World, id: usize, n: usize){
+    for _ in 0..n{
+        *world.get_phy_obj_mut(id).unwrap().get_vel_mut() = v(0., 0.01);
+        world.simulation_step();
+    }
+}
+
+fn y_of(world: &World, id: usize) -> f32{
+    world.get_phy_obj(id).unwrap().get_pos().y
+}
+
+#[test]
+fn bodies_land_on_one_way_platforms(){
+    let (mut world, _) = world_with_platform();
+    let ball = world.add_phy_obj(Circle::new(0, 0., -0.2, 0.02, 0.1));
+    steps(&mut world, 300);
+    assert!((y_of(&world, ball) - -0.38).abs() < 0.003, "{}", y_of(&world, ball));
+}
+
+#[test]
+fn bodies_jump_up_through_one_way_platforms(){
+    let (mut world, _) = world_with_platform();
+    let ball = world.add_phy_obj(Circle::new(0, 0., -0.68, 0.02, 0.1));
+    steps(&mut world, 50);
+    jump(&mut world, ball, 40);
+    steps(&mut world, 300);
+    assert!((y_of(&world, ball) - -0.38).abs() < 0.003, "{}", y_of(&world, ball));
+}
+
+#[test]
+fn bodies_stop_halfway_fall_back_through(){
+    let (mut world, _) = world_with_platform();
+    let ball = world.add_phy_obj(Circle::new(0, 0., -0.68, 0.02, 0.1));
+    steps(&mut world, 50);
+    //the top of the ball reaches into the platform, but not over it
+    jump(&mut world, ball, 27);
+    steps(&mut world, 300);
+    assert!((y_of(&world, ball) - -0.68).abs() < 0.003, "{}", y_of(&world, ball));
+}
+
+#[test]
+fn one_way_edge_chains(){
+    let mut world = World::new();
+    world.set_gravity_dir(v(0., -1.));
+    world.add_phy_obj(slab(0., -0.8, 1., 0.1));
+    let mut ledge = EdgeChain::from_coords(vec![[-0.2, -0.4], [0.2, -0.4]]);
+    //solid from both sides without the one way direction
+    ledge.one_sided = false;
+    ledge.one_way = Some(v(0., 1.));
+    world.add_phy_obj(ledge);
+    let ball = world.add_phy_obj(Circle::new(0, 0., -0.68, 0.02, 0.1));
+    steps(&mut world, 50);
+    jump(&mut world, ball, 40);
+    steps(&mut world, 300);
+    assert!((y_of(&world, ball) - -0.38).abs() < 0.003, "{}", y_of(&world, ball));
+}
+
+#[test]
+fn pre_solve_disables_contacts(){
+    let (mut world, _) = world_with_platform();
+    let ghost = world.add_phy_obj(Circle::new(0, 0.5, -0.5, 0.02, 0.1));
+    let ball = world.add_phy_obj(Circle::new(0, 0.7, -0.5, 0.02, 0.1));
+    world.set_pre_solve(move |contact| contact.seen_from(ghost).is_none());
+    steps(&mut world, 300);
+    //the ghost falls through the floor until it reaches the bounds of the world
+    assert!(y_of(&world, ghost) < -0.75);
+    assert!((y_of(&world, ball) - -0.68).abs() < 0.003);
+
+    world.clear_pre_solve();
+    let ghost = world.add_phy_obj(Circle::new(0, 0.5, -0.5, 0.02, 0.1));
+    steps(&mut world, 300);
+    assert!((y_of(&world, ghost) - -0.68).abs() < 0.003);
+}
+
+#[test]
+fn pre_solve_sees_the_contact(){
+    let (mut world, platform) = world_with_platform();
+    let ball = world.add_phy_obj(Circle::new(0, 0., -0.3, 0.02, 0.1));
+    let seen : Rc<RefCell<Vec<(Vec2, Vec2)>>> = Rc::new(RefCell::new(vec![]));
+    let log = seen.clone();
+    world.set_pre_solve(move |contact| {
+        if let Some((other, normal)) = contact.seen_from(ball){
+            assert_eq!(other.get_id(), platform);
+            //relative velocity of the ball seen from the platform
+            let vel = if contact.body_b.get_id() == ball {contact.relative_vel} else {-contact.relative_vel};
+            log.borrow_mut().push((normal, vel));
+        }
+        true
+    });
+    steps(&mut world, 100);
+    let seen = seen.borrow();
+    assert!(!seen.is_empty());
+    let (normal, vel) = seen[0];
+    //towards the platform below, hit while falling
+    assert!(normal.y < -0.99);
+    assert!(vel.y < 0.);
+}
+
+#[test]
+fn characters_jump_through_one_way_platforms(){
+    let (world, platform) = world_with_platform();
+    let mut character = CharacterController::new(0., -0.7 + 0.061, 0.03, 0.03);
+    let mut vy : f32 = 0.;
+    for step in 0..120{
+        vy = if step == 5 {0.04} else {vy - 0.0009};
+        character.move_by(&world, v(0., vy));
+        if character.is_grounded() && vy < 0.{
+            vy = 0.;
+        }
+    }
+    assert_eq!(character.state().ground_body, Some(platform));
+    assert!((character.pos.y - 0.06 - -0.4).abs() < 0.002, "{:?}", character.pos);
+}
+
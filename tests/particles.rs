@@ -0,0 +1,162 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    convex_body::Convex2D,
+    line_body::EdgeChain,
+    particles::{ParticleSystem, Emitter, Particle},
+    physics_world::World,
+    physic_obj_traits::BodyType,
+};
+
+mod common;
+use common::*;
+
+//gravity pointing down with unit length
+fn world_down() -> World{
+    let mut world = World::new();
+    world.set_gravity_dir(Vec2::new(0., -1.));
+    world
+}
+
+//sprays straight down from the top of the world
+fn fountain(rate: f32, max_particles: usize) -> ParticleSystem{
+    let mut particles = ParticleSystem::new(max_particles);
+    let mut emitter = Emitter::new(Vec2::new(0., 0.5), -std::f32::consts::FRAC_PI_2, 1., rate);
+    emitter.speed = (0.01, 0.03);
+    emitter.lifetime = (1000., 1000.);
+    particles.add_emitter(emitter);
+    particles
+}
+
+#[test]
+fn emitters_spawn_at_their_rate_up_to_the_limit(){
+    let mut world = World::new();
+    let slow = world.add_particle_system(fountain(0.5, 1000));
+    let full = world.add_particle_system(fountain(10., 25));
+    steps(&mut world, 10);
+    assert_eq!(world.particle_systems[slow].particles.len(), 5);
+    assert_eq!(world.particle_systems[full].particles.len(), 25);
+
+    world.particle_systems[slow].emitters[0].enabled = false;
+    steps(&mut world, 10);
+    assert_eq!(world.particle_systems[slow].particles.len(), 5);
+}
+
+#[test]
+fn particles_leave_within_the_spread(){
+    let mut particles = ParticleSystem::new(1000).with_seed(7);
+    let emitter = particles.add_emitter(Emitter::new(Vec2::new(0., 0.), 0.3, 0.4, 0.));
+    particles.burst(emitter, 200);
+    assert_eq!(particles.particles.len(), 200);
+    let angles : Vec<f32> = particles.particles.iter().map(|p| p.vel.y.atan2(p.vel.x)).collect();
+    assert!(angles.iter().all(|a| (0.1..=0.5).contains(a)));
+    //the cone is filled, not just its center
+    assert!(angles.iter().any(|a| *a < 0.15) && angles.iter().any(|a| *a > 0.45));
+    let speeds = particles.particles.iter().map(|p| (p.vel.x.powi(2) + p.vel.y.powi(2)).sqrt());
+    assert!(speeds.into_iter().all(|s| (0.005..=0.01).contains(&s)));
+}
+
+#[test]
+fn particles_die_at_the_end_of_their_life(){
+    let mut world = World::new();
+    let mut particles = ParticleSystem::new(100);
+    let mut emitter = Emitter::new(Vec2::new(0., 0.), 0., 0., 0.);
+    emitter.lifetime = (10., 20.);
+    let emitter = particles.add_emitter(emitter);
+    particles.burst(emitter, 50);
+    let particles = world.add_particle_system(particles);
+    steps(&mut world, 9);
+    assert_eq!(world.particle_systems[particles].particles.len(), 50);
+    steps(&mut world, 11);
+    assert!(world.particle_systems[particles].particles.is_empty());
+}
+
+#[test]
+fn color_and_size_fade_over_the_life(){
+    let mut particle = Particle {
+        pos: Vec2::new(0., 0.),
+        vel: Vec2::new(0., 0.),
+        life: 100.,
+        lifetime: 100.,
+        color: [1., 1., 0., 1.],
+        end_color: [1., 0., 0., 1.],
+        size: 0.01,
+        end_size: 0.,
+    };
+    assert_eq!(particle.current_color(), [1., 1., 0., 1.]);
+    particle.life = 25.;
+    assert_eq!(particle.age(), 0.75);
+    assert_eq!(particle.current_color(), [1., 0.25, 0., 1.]);
+    assert!((particle.current_size() - 0.0025).abs() < 0.00001);
+}
+
+#[test]
+fn particles_land_on_static_bodies(){
+    let mut world = world_down();
+    let mut floor = Convex2D::new(0., -0.3, vec![(-0.5, -0.1), (0.5, -0.1), (0.5, 0.1), (-0.5, 0.1)], 0.);
+    floor.body_type = BodyType::Static;
+    world.add_phy_obj(floor);
+    let particles = world.add_particle_system(fountain(2., 1000));
+    steps(&mut world, 300);
+    let particles = &world.particle_systems[particles].particles;
+    let on_floor : Vec<&Particle> = particles.iter().filter(|p| p.pos.x.abs() < 0.5).collect();
+    assert!(on_floor.len() > 500);
+    assert!(on_floor.iter().all(|p| p.pos.y > -0.2 - 0.0001));
+}
+
+#[test]
+fn fast_particles_do_not_pass_through_terrain(){
+    let mut world = world_down();
+    world.add_phy_obj(EdgeChain::from_coords(vec![[-1., -0.5], [1., -0.5]]));
+    let mut particles = fountain(5., 2000);
+    particles.emitters[0].speed = (0.05, 0.05);
+    particles.restitution = 0.;
+    let particles = world.add_particle_system(particles);
+    steps(&mut world, 300);
+    let particles = &world.particle_systems[particles].particles;
+    assert!(particles.iter().all(|p| p.pos.y > -0.5));
+    //resting on the edge
+    assert!(particles.iter().any(|p| (p.pos.y - -0.495).abs() < 0.0001));
+}
+
+#[test]
+fn particles_pass_one_sided_terrain_from_below(){
+    let mut world = world_down();
+    world.add_phy_obj(EdgeChain::from_coords(vec![[-1., -0.5], [-0.5, -0.5], [0., -0.5], [0.5, -0.5], [1., -0.5]]));
+    let mut particles = ParticleSystem::new(200);
+    let mut emitter = Emitter::new(Vec2::new(0., -0.8), std::f32::consts::FRAC_PI_2, 0.5, 2.);
+    emitter.speed = (0.05, 0.05);
+    emitter.lifetime = (1000., 1000.);
+    particles.add_emitter(emitter);
+    particles.restitution = 0.;
+    let particles = world.add_particle_system(particles);
+    steps(&mut world, 300);
+    //shot up through the terrain, they land on top of it, also over the joints
+    let above : Vec<&Particle> = world.particle_systems[particles].particles.iter().filter(|p| p.pos.y > -0.5).collect();
+    assert!(!above.is_empty());
+    assert!(above.iter().all(|p| (p.pos.y - -0.495).abs() < 0.0001), "{:?}", above.iter().map(|p| p.pos).collect::<Vec<_>>());
+}
+
+#[test]
+fn dynamic_bodies_are_not_hit(){
+    let mut world = world_down();
+    let mut ball = Circle::new(0, 0., 0., 0.2, 1.);
+    ball.gravity_scale = 0.;
+    let ball = world.add_phy_obj(ball);
+    let mut particles = fountain(5., 2000);
+    particles.collide = true;
+    let particles = world.add_particle_system(particles);
+    steps(&mut world, 100);
+    //the spray falls through the ball, which is not pushed either
+    assert!(world.particle_systems[particles].particles.iter().any(|p| p.pos.y < -0.3));
+    assert_eq!(*world.get_phy_obj(ball).unwrap().get_pos(), Vec2::new(0., 0.));
+}
+
+#[test]
+fn particles_outside_of_the_world_disappear(){
+    let mut world = world_down();
+    let particles = world.add_particle_system(fountain(1., 1000));
+    steps(&mut world, 200);
+    let particles = &world.particle_systems[particles].particles;
+    assert!(particles.len() < 200);
+    assert!(particles.iter().all(|p| p.pos.y >= -1.));
+}
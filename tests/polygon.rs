@@ -0,0 +1,129 @@
+use wgpu_tutorial::physics_engine::{
+    convex_body::Convex2D,
+    polygon::{self, PolygonError, MAX_POLYGON_VERTICES},
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn valid_polygon_is_unchanged(){
+    assert_eq!(polygon::validate_convex(&square(0., 0., 0.5)), Ok(square(0., 0., 0.5)));
+}
+
+#[test]
+fn clockwise_polygon_is_reversed(){
+    let mut verts = square(0., 0., 0.5);
+    verts.reverse();
+    let result = polygon::validate_convex(&verts).unwrap();
+    assert!(polygon::signed_area(&result) > 0.);
+    assert_eq!(result.len(), 4);
+}
+
+#[test]
+fn duplicate_and_collinear_vertices_are_removed(){
+    let verts = vec![
+        v(-0.5, -0.5), v(-0.5, -0.5), v(0., -0.5), v(0.5, -0.5),
+        v(0.5, 0.5), v(-0.5, 0.5), v(-0.5, -0.5),
+    ];
+    assert_eq!(polygon::validate_convex(&verts), Ok(square(0., 0., 0.5)));
+}
+
+#[test]
+fn concave_polygon_is_rejected(){
+    let verts = vec![v(-0.5, -0.5), v(0.5, -0.5), v(0., 0.), v(0.5, 0.5), v(-0.5, 0.5)];
+    assert_eq!(polygon::validate_convex(&verts), Err(PolygonError::NotConvex(2)));
+}
+
+#[test]
+fn degenerate_polygons_are_rejected(){
+    assert_eq!(polygon::validate_convex(&[v(0., 0.), v(1., 0.)]), Err(PolygonError::TooFewVertices(2)));
+    assert_eq!(polygon::validate_convex(&[v(0., 0.), v(0.5, 0.), v(1., 0.)]), Err(PolygonError::ZeroArea));
+    assert_eq!(polygon::validate_convex(&[v(0., 0.), v(f32::NAN, 0.), v(1., 1.)]), Err(PolygonError::NotFinite));
+}
+
+#[test]
+fn vertex_count_is_capped(){
+    let n = MAX_POLYGON_VERTICES + 1;
+    let circle : Vec<Vec2> = (0..n).map(|i| {
+        let a = i as f32 / n as f32 * std::f32::consts::TAU;
+        v(a.cos(), a.sin())
+    }).collect();
+    assert_eq!(polygon::validate_convex(&circle), Err(PolygonError::TooManyVertices(n)));
+    assert!(polygon::validate_convex(&circle[..MAX_POLYGON_VERTICES]).is_ok());
+}
+
+#[test]
+fn hull_of_point_cloud(){
+    let points = vec![
+        v(0., 0.), v(0.5, 0.5), v(-0.5, 0.5), v(0.2, -0.1),
+        v(-0.5, -0.5), v(0.5, -0.5), v(0., 0.5), v(0.5, -0.5),
+    ];
+    let hull = polygon::convex_hull(&points);
+    assert_eq!(hull, vec![v(-0.5, -0.5), v(0.5, -0.5), v(0.5, 0.5), v(-0.5, 0.5)]);
+}
+
+#[test]
+fn hull_of_collinear_points_has_no_area(){
+    assert_eq!(polygon::hull_polygon(&[v(0., 0.), v(0.5, 0.5), v(1., 1.)]), Err(PolygonError::ZeroArea));
+    assert_eq!(polygon::hull_polygon(&[v(0., 0.), v(0., 0.)]), Err(PolygonError::TooFewVertices(1)));
+}
+
+#[test]
+fn convex_body_constructors(){
+    let body = Convex2D::try_new(0., 0., vec![(0., 0.1), (0.1, -0.1), (-0.1, -0.1)], 1.).unwrap();
+    assert_eq!(body.vertices(), &[(-0.1, -0.1), (0.1, -0.1), (0., 0.1)]);
+
+    let concave = vec![(-0.1, -0.1), (0.1, -0.1), (0., 0.), (0.1, 0.1), (-0.1, 0.1)];
+    assert!(Convex2D::try_new(0., 0., concave.clone(), 1.).is_err());
+    let body = Convex2D::from_hull(0., 0., concave, 1.).unwrap();
+    assert_eq!(body.vertices().len(), 4);
+
+    let err = Convex2D::try_new(0., 0., vec![(0., 0.)], 1.).err().unwrap();
+    assert_eq!(err.to_string(), "polygon needs at least 3 distinct vertices, got 1");
+}
+
+fn l_shape() -> Vec<Vec2>{
+    vec![v(0., 0.), v(2., 0.), v(2., 1.), v(1., 1.), v(1., 2.), v(0., 2.)]
+}
+
+#[test]
+fn triangulation_covers_the_polygon(){
+    let verts = l_shape();
+    let triangles = polygon::triangulate(&verts).unwrap();
+    assert_eq!(triangles.len(), verts.len() - 2);
+    let area : f32 = triangles.iter().map(|t| polygon::signed_area(&[verts[t[0]], verts[t[1]], verts[t[2]]])).sum();
+    assert!((area - 3.).abs() < 1e-5);
+    assert!(triangles.iter().all(|t| polygon::signed_area(&[verts[t[0]], verts[t[1]], verts[t[2]]]) > 0.));
+}
+
+#[test]
+fn concave_polygon_decomposes_into_convex_pieces(){
+    let mut verts = l_shape();
+    verts.reverse();
+    let pieces = polygon::decompose(&verts).unwrap();
+    assert_eq!(pieces.len(), 2);
+    for piece in pieces.iter(){
+        assert!(polygon::validate_convex(piece).is_ok());
+    }
+    let area : f32 = pieces.iter().map(|p| polygon::signed_area(p)).sum();
+    assert!((area - 3.).abs() < 1e-5);
+}
+
+#[test]
+fn convex_polygon_stays_one_piece(){
+    assert_eq!(polygon::decompose(&square(0., 0., 0.5)).unwrap().len(), 1);
+}
+
+#[test]
+fn self_intersecting_polygon_is_rejected(){
+    let bow_tie = vec![v(0., 0.), v(2., 1.), v(2., 0.), v(0., 2.)];
+    assert!(!polygon::is_simple(&bow_tie));
+    assert_eq!(polygon::decompose(&bow_tie), Err(PolygonError::NotSimple));
+}
+
+#[test]
+fn centroid_of_l_shape(){
+    let c = polygon::centroid(&l_shape());
+    assert!((c.x - 5./6.).abs() < 1e-5 && (c.y - 5./6.).abs() < 1e-5);
+}
@@ -0,0 +1,148 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    rect_body::Rect,
+    capsule_body::Capsule,
+    line_body::EdgeChain,
+    col_relations::{polygon_polygon_contact, capsule_circle_contact, capsule_capsule_contact, capsule_polygon_contact},
+    physic_obj_traits::{CollisionRelation, TransposeObject},
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn polygons_side_by_side(){
+    let c = polygon_polygon_contact(&square(0., 0., 0.5), &square(0.9, 0.1, 0.5)).expect("squares overlap");
+    assert_close(c.normal, v(1., 0.));
+    assert!((c.depth - 0.1).abs() < TOL);
+    let c = polygon_polygon_contact(&square(0.9, 0.1, 0.5), &square(0., 0., 0.5)).expect("squares overlap");
+    assert_close(c.normal, v(-1., 0.));
+    assert!((c.depth - 0.1).abs() < TOL);
+
+    assert!(polygon_polygon_contact(&square(0., 0., 0.5), &square(1.1, 0., 0.5)).is_none());
+}
+
+#[test]
+fn rectangles_stacked_and_rotated(){
+    let bottom = Rect::new(0., 0., 0.4, 0.2, 1.);
+    let top = Rect::new(0.1, 0.17, 0.4, 0.2, 1.);
+    let c = Rect::get_contact(&bottom, &top).expect("rectangles overlap");
+    assert_close(c.normal, v(0., 1.));
+    assert!((c.depth - 0.03).abs() < TOL);
+
+    //corner of a diamond poking into the bottom face of the box above
+    let mut diamond = Rect::new(0., 0., 0.2, 0.2, 1.);
+    diamond.set_angle(cgmath::Rad(std::f32::consts::FRAC_PI_4));
+    let above = Rect::new(0., 0.23, 0.4, 0.2, 1.);
+    let c = Rect::get_contact(&diamond, &above).expect("corner touches the box");
+    assert_close(c.normal, v(0., 1.));
+    assert!((c.depth - (0.02f32.sqrt() - 0.13)).abs() < TOL);
+}
+
+#[test]
+fn circle_on_a_rectangle_face(){
+    let rect = Rect::new(0., 0., 0.4, 0.2, 1.);
+    let circle = Circle::new(0, 0.05, 0.15, 0.1, 1.);
+    let c = Rect::get_contact(&rect, &circle).expect("circle touches the top face");
+    assert_close(c.normal, v(0., 1.));
+    assert!((c.depth - 0.05).abs() < TOL);
+    assert_close(c.point, v(0.05, 0.1));
+}
+
+#[test]
+fn circle_at_a_rectangle_corner(){
+    let rect = Rect::new(0., 0., 0.4, 0.2, 1.);
+    let circle = Circle::new(0, 0.23, 0.14, 0.06, 1.);
+    let c = Rect::get_contact(&rect, &circle).expect("circle touches the corner");
+    assert_close(c.normal, v(0.6, 0.8));
+    assert!((c.depth - 0.01).abs() < TOL);
+    assert_close(c.point, v(0.2, 0.1));
+    assert!(Rect::get_contact(&rect, &Circle::new(0, 0.25, 0.15, 0.06, 1.)).is_none());
+}
+
+#[test]
+fn circle_center_inside_a_rectangle(){
+    let rect = Rect::new(0., 0., 0.4, 0.2, 1.);
+    let circle = Circle::new(0, 0.18, 0., 0.02, 1.);
+    let c = Rect::get_contact(&rect, &circle).expect("circle is inside");
+    //pushed out through the nearest face
+    assert_close(c.normal, v(1., 0.));
+    assert!((c.depth - 0.04).abs() < TOL);
+    assert_close(c.point, v(0.2, 0.));
+}
+
+#[test]
+fn capsule_against_circles(){
+    let (a, b) = (v(-0.2, 0.), v(0.2, 0.));
+    //side of the capsule
+    let c = capsule_circle_contact(a, b, 0.05, v(0.1, 0.12), 0.1).expect("circle touches the side");
+    assert_close(c.normal, v(0., 1.));
+    assert!((c.depth - 0.03).abs() < TOL);
+    assert_close(c.point, v(0.1, 0.05));
+    //rounded end
+    let c = capsule_circle_contact(a, b, 0.05, v(0.3, 0.), 0.1).expect("circle touches the end");
+    assert_close(c.normal, v(1., 0.));
+    assert!((c.depth - 0.05).abs() < TOL);
+    assert!(capsule_circle_contact(a, b, 0.05, v(0.36, 0.), 0.1).is_none());
+
+    let capsule = Capsule::new(0., 0., 0.2, 0.05, 1.);
+    let circle = Circle::new(0, 0.1, 0.12, 0.1, 1.);
+    let c = Capsule::get_contact(&capsule, &circle).expect("circle touches the side");
+    assert_close(c.normal, v(0., 1.));
+}
+
+#[test]
+fn capsules_parallel_and_crossing(){
+    let c = capsule_capsule_contact(v(-0.2, 0.), v(0.2, 0.), 0.05, v(-0.1, 0.08), v(0.3, 0.08), 0.05)
+        .expect("capsules lie on each other");
+    assert_close(c.normal, v(0., 1.));
+    assert!((c.depth - 0.02).abs() < TOL);
+
+    //upright capsule touching the end of a lying one
+    let lying = Capsule::new(0., 0., 0.2, 0.05, 1.);
+    let mut upright = Capsule::new(0.28, 0., 0.2, 0.05, 1.);
+    upright.set_angle(cgmath::Rad(std::f32::consts::FRAC_PI_2));
+    let c = Capsule::get_contact(&lying, &upright).expect("capsules cross");
+    assert_close(c.normal, v(1., 0.));
+    assert!((c.depth - 0.02).abs() < TOL);
+    upright.pos.x = 0.31;
+    assert!(Capsule::get_contact(&lying, &upright).is_none());
+}
+
+#[test]
+fn capsule_lying_on_polygons(){
+    let c = capsule_polygon_contact(v(-0.2, 0.54), v(0.2, 0.54), 0.05, &square(0., 0., 0.5)).expect("capsule rests on the square");
+    //points from the capsule into the polygon
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.01).abs() < TOL);
+    assert_close(c.point, v(0., 0.49));
+
+    //tilted, only the lower end reaches the square
+    let c = capsule_polygon_contact(v(-0.2, 0.54), v(0.2, 0.7), 0.05, &square(0., 0., 0.5)).expect("lower end touches");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.01).abs() < TOL);
+    assert_close(c.point, v(-0.2, 0.49));
+
+    let capsule = Capsule::new(0., 0.14, 0.2, 0.05, 1.);
+    let c = Capsule::get_contact(&capsule, &Rect::new(0., 0., 0.4, 0.2, 1.)).expect("capsule rests on the box");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.01).abs() < TOL);
+}
+
+#[test]
+fn capsule_and_rectangle_on_an_edge_chain(){
+    let chain = EdgeChain::new(0., 0., vec![v(-1., 0.), v(0., 0.), v(1., 0.)]);
+    let capsule = Capsule::new(0.1, 0.04, 0.2, 0.05, 1.);
+    let c = Capsule::get_contact(&capsule, &chain).expect("capsule rests on the chain");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.01).abs() < TOL);
+
+    let rect = Rect::new(0.1, 0.09, 0.4, 0.2, 1.);
+    let c = Rect::get_contact(&rect, &chain).expect("box rests on the chain");
+    assert_close(c.normal, v(0., -1.));
+    assert!((c.depth - 0.01).abs() < TOL);
+
+    //the chain is solid from above only
+    assert!(Capsule::get_contact(&Capsule::new(0.1, -0.04, 0.2, 0.05, 1.), &chain).is_none());
+    assert!(Rect::get_contact(&Rect::new(0.1, -0.09, 0.4, 0.2, 1.), &chain).is_none());
+}
@@ -0,0 +1,105 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    rect_body::Rect,
+    physics_world::World,
+};
+
+use cgmath::InnerSpace;
+
+mod common;
+use common::*;
+
+//floor without changing the gravity
+fn floor_only() -> (World, usize){
+    let mut world = World::new();
+    let floor = add_floor(&mut world);
+    (world, floor)
+}
+
+//three boxes stacked on the floor
+fn stack(world: &mut World) -> Vec<usize>{
+    (0..3).map(|k| world.add_phy_obj(Rect::new(0.0, -0.64 + 0.121*k as f32, 0.2, 0.12, 0.6))).collect()
+}
+
+#[test]
+fn resting_stack_falls_asleep(){
+    let (mut world, floor) = floor_only();
+    let boxes = stack(&mut world);
+    steps(&mut world, 600);
+    assert!(boxes.iter().all(|&id| !world.is_awake(id)));
+    //static bodies are never awake
+    assert!(!world.is_awake(floor));
+
+    let poses : Vec<Vec2> = boxes.iter().map(|&id| *world.get_phy_obj(id).unwrap().get_pos()).collect();
+    steps(&mut world, 100);
+    for (&id, pos) in boxes.iter().zip(poses){
+        assert_eq!(*world.get_phy_obj(id).unwrap().get_pos(), pos);
+        assert_eq!(*world.get_phy_obj(id).unwrap().get_vel(), Vec2::new(0., 0.));
+    }
+}
+
+#[test]
+fn falling_body_stays_awake(){
+    let (mut world, _) = floor_only();
+    let circle = world.add_phy_obj(Circle::new(0, 0.0, 0.9, 0.05, 1.));
+    //the gravity is weak, the body is still slow after the time to sleep
+    steps(&mut world, 150);
+    assert!(world.is_awake(circle));
+    assert!(world.get_phy_obj(circle).unwrap().get_pos().y < 0.85);
+}
+
+#[test]
+fn impulse_wakes_the_whole_island(){
+    let (mut world, _) = floor_only();
+    let boxes = stack(&mut world);
+    steps(&mut world, 600);
+    assert!(boxes.iter().all(|&id| !world.is_awake(id)));
+
+    world.apply_impulse(boxes[0], Vec2::new(0.001, 0.));
+    assert!(boxes.iter().all(|&id| world.is_awake(id)));
+    steps(&mut world, 1);
+    assert!(world.get_phy_obj(boxes[0]).unwrap().get_pos().x > 0.);
+}
+
+#[test]
+fn contact_wakes_sleeping_body(){
+    let (mut world, _) = floor_only();
+    let rect = world.add_phy_obj(Rect::new(0.0, -0.64, 0.2, 0.12, 0.6));
+    steps(&mut world, 300);
+    assert!(!world.is_awake(rect));
+
+    world.add_phy_obj(Circle::new(0, 0.0, -0.5, 0.05, 1.));
+    let mut woken = false;
+    for _ in 0..300{
+        world.simulation_step();
+        woken |= world.is_awake(rect);
+    }
+    assert!(woken);
+}
+
+#[test]
+fn only_noticeable_gravity_changes_wake_the_world(){
+    let (mut world, _) = floor_only();
+    let boxes = stack(&mut world);
+    steps(&mut world, 600);
+    assert!(boxes.iter().all(|&id| !world.is_awake(id)));
+
+    //following the mouse turns the gravity a little every frame
+    let strength = world.gravity_dir().magnitude();
+    let turned = |deg: f32| Vec2::new(deg.to_radians().sin(), -deg.to_radians().cos()) * strength;
+    world.set_gravity_dir(turned(0.2));
+    world.set_gravity_dir(turned(0.4));
+    assert!(boxes.iter().all(|&id| !world.is_awake(id)));
+    //the small turns add up
+    world.set_gravity_dir(turned(0.6));
+    assert!(boxes.iter().all(|&id| world.is_awake(id)));
+}
+
+#[test]
+fn sleeping_can_be_disabled(){
+    let (mut world, _) = floor_only();
+    let boxes = stack(&mut world);
+    world.allow_sleeping = false;
+    steps(&mut world, 600);
+    assert!(boxes.iter().all(|&id| world.is_awake(id)));
+}
@@ -0,0 +1,83 @@
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    soft_body::SoftBody,
+    physics_world::World,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn ring_and_grid_layout(){
+    let ring = SoftBody::ring([0., 0.], 0.1, 12, 0.1, 0.01);
+    assert_eq!(ring.points.len(), 12);
+    assert_eq!(ring.get_springs().len(), 24);
+    //closed line for drawing
+    let coords = ring.outline_coords();
+    assert_eq!(coords.len(), 13);
+    assert_eq!(coords[0], coords[12]);
+    //regular 12-gon
+    assert!((ring.area() - 3. * 0.01).abs() < 0.0001);
+
+    let grid = SoftBody::grid([0., 0.], 4, 3, 0.1, 0.1, 0.01);
+    assert_eq!(grid.points.len(), 12);
+    //rows, columns and both diagonals of every cell
+    assert_eq!(grid.get_springs().len(), 3*3 + 4*2 + 2*3*2);
+    assert_eq!(grid.outline_vertices().len(), 10);
+    assert!((grid.area() - 0.06).abs() < 0.0001);
+    assert!((grid.center() - Vec2::new(0.15, 0.1)).x.abs() < 0.0001);
+}
+
+#[test]
+fn blob_rests_on_the_floor_and_keeps_its_area(){
+    let mut world = world_with_floor();
+    let blob = world.add_soft_body(SoftBody::ring([0., -0.3], 0.15, 20, 0.05, 0.02));
+    let rest_area = world.soft_bodies[blob].area();
+    steps(&mut world, 400);
+    let blob = &world.soft_bodies[blob];
+    let lowest = blob.points.iter().map(|p| p.pos.y).fold(f32::MAX, f32::min);
+    assert!((lowest - -0.68).abs() < 0.002, "{lowest}");
+    assert!((blob.area() / rest_area - 1.).abs() < 0.05);
+}
+
+#[test]
+fn grid_keeps_its_shape(){
+    let mut world = world_with_floor();
+    let jelly = world.add_soft_body(SoftBody::grid([0.4, -0.4], 5, 4, 0.05, 0.05, 0.02));
+    let rest_area = world.soft_bodies[jelly].area();
+    steps(&mut world, 400);
+    let jelly = &world.soft_bodies[jelly];
+    assert!((jelly.area() / rest_area - 1.).abs() < 0.05);
+    //resting on its bottom row
+    assert!((jelly.center().y - (-0.68 + 0.075)).abs() < 0.005, "{:?}", jelly.center());
+}
+
+#[test]
+fn soft_body_pushes_rigid_bodies(){
+    let mut world = World::new();
+    world.set_gravity_dir(Vec2::new(1., 0.));
+    let mut ball = Circle::new(0, 0., 0., 0.05, 0.1);
+    ball.gravity_scale = 0.;
+    let ball = world.add_phy_obj(ball);
+    world.add_soft_body(SoftBody::ring([-0.3, 0.], 0.1, 16, 0.05, 0.02));
+    steps(&mut world, 200);
+    assert!(world.get_phy_obj(ball).unwrap().get_pos().x > 0.05);
+}
+
+#[test]
+fn pinned_particles_stay(){
+    let mut world = world_with_floor();
+    let mut jelly = SoftBody::grid([0., 0.], 3, 3, 0.05, 0.05, 0.01);
+    jelly.pin(6);
+    jelly.pin(8);
+    //soft springs without shape matching let it sag
+    jelly.stiffness = 0.05;
+    jelly.shape_matching = 0.;
+    let jelly = world.add_soft_body(jelly);
+    steps(&mut world, 100);
+    let jelly = &world.soft_bodies[jelly];
+    assert_eq!(jelly.points[6].pos, Vec2::new(0., 0.1));
+    assert_eq!(jelly.points[8].pos, Vec2::new(0.1, 0.1));
+    //stretched below its rest position
+    assert!(jelly.points[0].pos.y < -0.005, "{:?}", jelly.points[0].pos);
+}
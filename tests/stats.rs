@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use wgpu_tutorial::physics_engine::{
+    circle_body::Circle,
+    convex_body::Convex2D,
+    gjk::GJK_MAX_ITERATIONS,
+};
+
+mod common;
+use common::*;
+
+fn square_body(x: f32, y: f32) -> Convex2D{
+    Convex2D::new(x, y, square(0., 0., 0.05).into_iter().map(Into::into).collect(), 0.1)
+}
+
+#[test]
+fn pairs_of_separated_bodies(){
+    let mut world = world_with_floor();
+    for i in 0..3{
+        world.add_phy_obj(Circle::new(0, -0.5 + 0.5 * i as f32, 0.5, 0.05, 0.1));
+    }
+    world.simulation_step();
+    let stats = world.stats();
+    //every pair has a dynamic body, no boxes overlap
+    assert_eq!(stats.pairs_checked, 6);
+    assert_eq!(stats.pairs_tested, 0);
+    assert_eq!(stats.contacts, 0);
+    assert_eq!(stats.bodies_awake, 3);
+}
+
+#[test]
+fn contacts_of_resting_bodies(){
+    let mut world = world_with_floor();
+    world.add_phy_obj(Circle::new(0, 0., -0.6, 0.1, 0.1));
+    world.add_phy_obj(Circle::new(0, 0.5, -0.6, 0.1, 0.1));
+    for _ in 0..20{
+        world.simulation_step();
+    }
+    let stats = *world.stats();
+    assert_eq!(stats.pairs_tested, 2);
+    assert_eq!(stats.contacts, 2);
+    assert_eq!(stats.contacts_disabled, 0);
+    assert_eq!(world.contacts().len(), 2);
+
+    let ball = 1;
+    world.set_pre_solve(move |contact| contact.seen_from(ball).is_none());
+    world.simulation_step();
+    assert_eq!(world.stats().contacts_disabled, 1);
+    assert_eq!(world.contacts().len(), 1);
+}
+
+#[test]
+fn sleeping_bodies_are_not_awake(){
+    let mut world = world_with_floor();
+    world.add_phy_obj(Circle::new(0, 0., -0.6, 0.1, 0.1));
+    for _ in 0..2000{
+        world.simulation_step();
+    }
+    let stats = world.stats();
+    assert_eq!(stats.bodies_awake, 0);
+    //the floor only pairs with the sleeping ball
+    assert_eq!(stats.pairs_checked, 0);
+}
+
+#[test]
+fn gjk_queries_are_counted(){
+    let mut world = world_with_floor();
+    world.add_phy_obj(Circle::new(0, 0., 0.5, 0.05, 0.1));
+    world.simulation_step();
+    assert_eq!(world.stats().gjk_calls, 0);
+
+    let mut bullet = square_body(-0.5, 0.);
+    bullet.bullet = true;
+    let bullet = world.add_phy_obj(bullet);
+    world.apply_impulse(bullet, v(0.01, 0.));
+    world.simulation_step();
+    let stats = world.stats();
+    assert!(stats.gjk_calls > 0);
+    assert!(stats.gjk_iterations <= stats.gjk_calls * GJK_MAX_ITERATIONS);
+}
+
+#[test]
+fn phase_timings(){
+    let mut world = world_with_floor();
+    for i in 0..20{
+        world.add_phy_obj(square_body(-0.5 + 0.06 * i as f32, -0.6 + 0.01 * i as f32));
+    }
+    world.profiling = true;
+    world.simulation_step();
+    let stats = *world.stats();
+    let phases = stats.integration + stats.deformables + stats.broad_phase + stats.narrow_phase + stats.solver;
+    assert!(stats.step > Duration::ZERO);
+    assert!(phases <= stats.step);
+
+    world.profiling = false;
+    world.simulation_step();
+    let stats = world.stats();
+    assert_eq!(stats.step, Duration::ZERO);
+    assert_eq!(stats.narrow_phase, Duration::ZERO);
+    //the counters are kept without timings
+    assert!(stats.pairs_checked > 0);
+}